@@ -0,0 +1,218 @@
+use std::fs::File;
+use std::io::{stdin, stdout, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::exit;
+use std::str::FromStr;
+
+use clap::{Args, ValueEnum};
+use cubing::alg::{Alg, SimplifyOptions};
+use cubing::kpuzzle::KPuzzle;
+use serde_json::{json, Value};
+
+use crate::{load_kpuzzle, Metric, PuzzleSource};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum BatchOperation {
+    /// Validate each alg, and print it in normalized form.
+    Parse,
+    /// Invert each alg.
+    Invert,
+    /// Simplify each alg.
+    Simplify,
+    /// Count the moves in each alg.
+    Count,
+    /// Calculate the order of each alg (the number of repetitions needed to get back to the default pattern).
+    Order,
+}
+
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    #[clap(value_enum)]
+    operation: BatchOperation,
+
+    /// File to read algs from (one per line). Defaults to stdin.
+    #[clap(long)]
+    input: Option<PathBuf>,
+
+    /// Read the alg from this (1-indexed) column of CSV/TSV input, instead of using the whole line.
+    /// Quoted fields are supported, but cannot span multiple lines.
+    #[clap(long)]
+    column: Option<usize>,
+
+    /// Column delimiter (e.g. `$'\t'` for TSV).
+    #[clap(long, default_value_t = ',', requires = "column")]
+    delimiter: char,
+
+    /// Skip the first line of the input.
+    #[clap(long)]
+    header: bool,
+
+    /// Quantum move order for `simplify`.
+    #[clap(long, value_parser = clap::value_parser!(i32).range(1..))]
+    quantum_move_order: Option<i32>,
+
+    /// Metric for `count`.
+    #[clap(long, value_enum, default_value_t = Metric::Htm)]
+    metric: Metric,
+
+    #[command(flatten)]
+    puzzle_source: PuzzleSource,
+}
+
+// Splits a line of CSV into fields, handling quoted fields (with `""` for a literal quote).
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == '"' && field.is_empty() {
+            in_quotes = true;
+        } else if c == delimiter {
+            fields.push(std::mem::take(&mut field));
+        } else {
+            field.push(c);
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn process_alg(
+    alg_str: &str,
+    args: &BatchArgs,
+    kpuzzle: Option<&KPuzzle>,
+) -> Result<Value, (String, Option<usize>)> {
+    let alg = Alg::from_str(alg_str).map_err(|e| (e.description, e.position))?;
+    Ok(match args.operation {
+        BatchOperation::Parse => json!(alg.to_string()),
+        BatchOperation::Invert => json!(alg.invert().to_string()),
+        BatchOperation::Simplify => {
+            let options = SimplifyOptions {
+                quantum_move_order: args.quantum_move_order,
+            };
+            json!(alg.simplify(&options).to_string())
+        }
+        BatchOperation::Count => json!(alg.count_moves(args.metric.into())),
+        BatchOperation::Order => {
+            let kpuzzle = kpuzzle.expect("A puzzle is required to calculate the order.");
+            let transformation = kpuzzle
+                .transformation_from_alg(&alg)
+                .map_err(|e| (e.to_string(), None))?;
+            json!(transformation.pattern_order(&kpuzzle.default_pattern()))
+        }
+    })
+}
+
+/// Processes each line of the input and writes the results as JSON Lines, e.g.:
+///
+/// ```text
+/// {"alg":"R U R'","line":1,"result":"R U' R'"}
+/// {"alg":"R (U","error":"Invalid alg string (at character 2)","line":2,"position":2}
+/// ```
+///
+/// Returns the exit code: 0 if every line succeeded, and 1 otherwise.
+pub fn run_batch(args: BatchArgs) -> i32 {
+    let kpuzzle = match args.operation {
+        BatchOperation::Order => Some(load_kpuzzle(&args.puzzle_source).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            exit(1);
+        })),
+        _ => None,
+    };
+    let reader: Box<dyn BufRead> = match &args.input {
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => {
+                eprintln!("Could not read {}: {}", path.display(), e);
+                exit(1);
+            }
+        },
+        None => Box::new(BufReader::new(stdin())),
+    };
+
+    let mut stdout = stdout().lock();
+    let mut exit_code = 0;
+    for (i, line) in reader.lines().enumerate() {
+        let line_number = i + 1;
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("Could not read line {}: {}", line_number, e);
+                exit(1);
+            }
+        };
+        if args.header && line_number == 1 {
+            continue;
+        }
+        let line = line.trim_end_matches('\r');
+        let alg_str = match args.column {
+            Some(column) => {
+                let fields = split_fields(line, args.delimiter);
+                match column.checked_sub(1).and_then(|index| fields.get(index)) {
+                    Some(field) => field.clone(),
+                    None => {
+                        exit_code = 1;
+                        let output = json!({
+                            "line": line_number,
+                            "error": format!("Missing column: {}", column),
+                            "position": null,
+                        });
+                        writeln!(stdout, "{}", output).expect("Could not write output.");
+                        continue;
+                    }
+                }
+            }
+            None => {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                line.to_owned()
+            }
+        };
+        let output = match process_alg(&alg_str, &args, kpuzzle.as_ref()) {
+            Ok(result) => json!({
+                "line": line_number,
+                "alg": alg_str,
+                "result": result,
+            }),
+            Err((error, position)) => {
+                exit_code = 1;
+                json!({
+                    "line": line_number,
+                    "alg": alg_str,
+                    "error": error,
+                    "position": position,
+                })
+            }
+        };
+        writeln!(stdout, "{}", output).expect("Could not write output.");
+    }
+    exit_code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_fields;
+
+    #[test]
+    fn splits_fields() {
+        assert_eq!(split_fields("a,R U,c", ','), vec!["a", "R U", "c"]);
+        assert_eq!(
+            split_fields("\"[R, U]\",\"say \"\"hi\"\"\",", ','),
+            vec!["[R, U]", "say \"hi\"", ""]
+        );
+        assert_eq!(split_fields("1\tR U R'", '\t'), vec!["1", "R U R'"]);
+    }
+}
@@ -0,0 +1,117 @@
+use std::process::exit;
+
+use clap::{Args, ValueEnum};
+use cubing::twizzle_link::{
+    TwizzleControlPanel, TwizzleLink, TwizzleLinkTarget, TwizzlePuzzle, TwizzleSetupAnchor,
+    TwizzleStickering, TwizzleVisualization,
+};
+use serde_json::json;
+
+use crate::parse_alg_or_exit;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SetupAnchor {
+    /// The setup alg is applied to the default pattern (before the alg).
+    Start,
+    /// The setup alg is chosen so that the alg ends at the default pattern.
+    End,
+}
+
+#[derive(Args, Debug)]
+pub struct LinkArgs {
+    #[clap(long)]
+    setup: Option<String>,
+
+    #[clap(long)]
+    alg: Option<String>,
+
+    /// Puzzle ID (e.g. `3x3x3` or `megaminx`).
+    #[clap(long)]
+    puzzle: Option<String>,
+
+    /// Stickering (e.g. `OLL` or `PLL`).
+    #[clap(long)]
+    stickering: Option<String>,
+
+    #[clap(long, value_enum)]
+    setup_anchor: Option<SetupAnchor>,
+
+    /// Visualization (e.g. `2D`, `3D`, or `PG3D`).
+    #[clap(long)]
+    visualization: Option<String>,
+
+    /// Control panel (`auto`, `bottom-row`, or `none`).
+    #[clap(long)]
+    control_panel: Option<String>,
+
+    #[clap(long)]
+    title: Option<String>,
+
+    /// Link to Twizzle Explorer instead of Twizzle Editor.
+    #[clap(long)]
+    explore: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ParseLinkArgs {
+    /// A Twizzle (`alpha.twizzle.net`) or `alg.cubing.net` URL.
+    url: String,
+}
+
+/// Prints a Twizzle link, after checking that the algs are valid for the puzzle.
+pub fn run_link(args: LinkArgs) {
+    let link = TwizzleLink {
+        target: if args.explore {
+            TwizzleLinkTarget::Explore
+        } else {
+            TwizzleLinkTarget::Edit
+        },
+        setup: args.setup.map(|setup| parse_alg_or_exit(&setup, 1)),
+        alg: args.alg.map(|alg| parse_alg_or_exit(&alg, 1)),
+        puzzle: args.puzzle.as_deref().map(TwizzlePuzzle::from),
+        stickering: args.stickering.as_deref().map(TwizzleStickering::from),
+        setup_anchor: args.setup_anchor.map(|setup_anchor| match setup_anchor {
+            SetupAnchor::Start => TwizzleSetupAnchor::Start,
+            SetupAnchor::End => TwizzleSetupAnchor::End,
+        }),
+        visualization: args
+            .visualization
+            .as_deref()
+            .map(TwizzleVisualization::from),
+        control_panel: args.control_panel.as_deref().map(TwizzleControlPanel::from),
+        title: args.title,
+    };
+    match link.to_url() {
+        Ok(url) => println!("{}", url),
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Prints the parameters of the link as JSON (with `null` for missing parameters).
+pub fn run_parse_link(args: ParseLinkArgs) {
+    let link = match args.url.parse::<TwizzleLink>() {
+        Ok(link) => link,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+    let output = json!({
+        "target": match link.target {
+            TwizzleLinkTarget::Edit => "edit",
+            TwizzleLinkTarget::Explore => "explore",
+        },
+        "setup": link.setup.map(|setup| setup.to_string()),
+        "alg": link.alg.map(|alg| alg.to_string()),
+        "puzzle": link.puzzle.map(|puzzle| puzzle.to_string()),
+        "stickering": link.stickering.map(|stickering| stickering.to_string()),
+        "setup_anchor": link.setup_anchor.map(|setup_anchor| setup_anchor.as_str()),
+        "visualization": link.visualization.map(|visualization| visualization.to_string()),
+        "control_panel": link.control_panel.map(|control_panel| control_panel.to_string()),
+        "title": link.title,
+    });
+    println!("{}", output);
+}
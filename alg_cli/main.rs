@@ -1,11 +1,25 @@
+mod batch;
+mod link;
+mod search;
+
+use std::fs::read;
 use std::io::{read_to_string, stdin, stdout};
+use std::path::PathBuf;
 use std::process::exit;
 use std::str::FromStr;
 
-use clap::{Args, Command, CommandFactory, Parser, Subcommand};
+use batch::{run_batch, BatchArgs};
+use clap::{Args, Command, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::generator::generate;
 use clap_complete::{Generator, Shell};
-use cubing::alg::Alg;
+use cubing::alg::{Alg, MirrorPlane, MoveCountMetric, SimplifyOptions};
+use cubing::kpuzzle::{KPattern, KPuzzle};
+use cubing::puzzles::{
+    cube2x2x2_pattern_to_facelets, cube3x3x3_pattern_to_facelets, kpuzzle_by_id, render_net,
+    NetStyle,
+};
+use link::{run_link, run_parse_link, LinkArgs, ParseLinkArgs};
+use search::{run_scramble, run_solve, ScrambleArgs, SolveArgs};
 
 /// Alg tool
 #[derive(Parser, Debug)]
@@ -24,6 +38,41 @@ pub enum AlgCLICommand {
     /// Invert the provided alg
     Invert(AlgSource),
 
+    /// Simplify the provided alg by combining and cancelling adjacent moves
+    Simplify(SimplifyArgs),
+
+    /// Expand all groupings, commutators, and conjugates in the provided alg
+    Expand(AlgSource),
+
+    /// Count the moves in the provided alg
+    Count(CountArgs),
+
+    /// Mirror the provided alg
+    Mirror(MirrorArgs),
+
+    /// Apply the provided alg to the default pattern of a puzzle, and print the resulting pattern
+    Apply(ApplyArgs),
+
+    /// Apply an operation to each alg in a file (one per line, or a CSV column), printing JSON Lines.
+    /// Exits with code 1 if any line had an error.
+    Batch(BatchArgs),
+
+    /// Solve the pattern produced by a setup alg
+    Solve(SolveArgs),
+
+    /// Generate scrambles for a puzzle
+    Scramble(ScrambleArgs),
+
+    /// Print a Twizzle link for the given algs
+    Link(LinkArgs),
+
+    /// Print the parameters of a Twizzle or alg.cubing.net link as JSON
+    ParseLink(ParseLinkArgs),
+
+    /// Check whether two algs have the same effect on a puzzle.
+    /// Exits with code 0 if they do, 1 if they don't, and 2 for invalid input (like `diff`).
+    Equivalent(EquivalentArgs),
+
     /// Print completions for the given shell.
     Completions(CompletionsArgs),
 }
@@ -38,6 +87,127 @@ pub struct AlgSource {
     stdin: bool,
 }
 
+#[derive(Args, Debug)]
+pub struct SimplifyArgs {
+    #[command(flatten)]
+    alg_source: AlgSource,
+
+    /// Expand groupings, commutators, and conjugates before simplifying.
+    #[clap(long)]
+    expand: bool,
+
+    /// Reduce move amounts modulo this order (e.g. `4` for cubes).
+    #[clap(long, value_parser = clap::value_parser!(i32).range(1..))]
+    quantum_move_order: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Metric {
+    /// Half turn metric (outer block turns, slices count as 2).
+    Htm,
+    /// Quarter turn metric.
+    Qtm,
+    /// Slice turn metric.
+    Stm,
+    /// Execution turn metric (including rotations).
+    Etm,
+}
+
+impl From<Metric> for MoveCountMetric {
+    fn from(metric: Metric) -> Self {
+        match metric {
+            Metric::Htm => MoveCountMetric::Htm,
+            Metric::Qtm => MoveCountMetric::Qtm,
+            Metric::Stm => MoveCountMetric::Stm,
+            Metric::Etm => MoveCountMetric::Etm,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct CountArgs {
+    #[command(flatten)]
+    alg_source: AlgSource,
+
+    #[clap(long, value_enum, default_value_t = Metric::Htm)]
+    metric: Metric,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Plane {
+    /// Swap `R` and `L`.
+    M,
+    /// Swap `U` and `D`.
+    E,
+    /// Swap `F` and `B`.
+    S,
+}
+
+impl From<Plane> for MirrorPlane {
+    fn from(plane: Plane) -> Self {
+        match plane {
+            Plane::M => MirrorPlane::M,
+            Plane::E => MirrorPlane::E,
+            Plane::S => MirrorPlane::S,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct MirrorArgs {
+    #[command(flatten)]
+    alg_source: AlgSource,
+
+    #[clap(long, value_enum, default_value_t = Plane::M)]
+    plane: Plane,
+}
+
+#[derive(Debug, Args)]
+#[group(required = false, multiple = false)]
+pub struct PuzzleSource {
+    /// Puzzle ID (e.g. `3x3x3` or `megaminx`). Defaults to `3x3x3`.
+    #[clap(long, group = "puzzle_source")]
+    puzzle: Option<String>,
+
+    /// Path to a custom puzzle definition in KPuzzle JSON format.
+    #[clap(long, group = "puzzle_source")]
+    kpuzzle_json: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PatternFormat {
+    /// KPattern JSON.
+    Json,
+    /// A text net, with a letter for each sticker (3x3x3 and 2x2x2 only).
+    Net,
+    /// A net with colored stickers for terminals (3x3x3 and 2x2x2 only).
+    AnsiNet,
+    /// A facelet string in URFDLB order (3x3x3 and 2x2x2 only).
+    Facelets,
+}
+
+#[derive(Args, Debug)]
+pub struct ApplyArgs {
+    #[command(flatten)]
+    alg_source: AlgSource,
+
+    #[command(flatten)]
+    puzzle_source: PuzzleSource,
+
+    #[clap(long, value_enum, default_value_t = PatternFormat::Json)]
+    format: PatternFormat,
+}
+
+#[derive(Args, Debug)]
+pub struct EquivalentArgs {
+    alg_a: String,
+
+    alg_b: String,
+
+    #[command(flatten)]
+    puzzle_source: PuzzleSource,
+}
+
 #[derive(Args, Debug)]
 pub struct CompletionsArgs {
     /// Print completions for the given shell.
@@ -65,46 +235,144 @@ pub fn get_options() -> AlgCLIArgs {
     args
 }
 
+// Reads the alg source, exiting with code 1 if it is not a valid alg.
+fn read_alg(alg_source: AlgSource) -> Alg {
+    let alg = match alg_source.alg {
+        Some(alg) => alg,
+        None => {
+            assert!(alg_source.stdin);
+            match read_to_string(stdin()) {
+                Ok(alg) => alg,
+                Err(e) => {
+                    eprintln!("Could not read alg from stdin: {}", e);
+                    exit(1);
+                }
+            }
+        }
+    };
+    parse_alg_or_exit(&alg, 1)
+}
+
+fn parse_alg_or_exit(alg: &str, exit_code: i32) -> Alg {
+    match Alg::from_str(alg) {
+        Ok(alg) => alg,
+        Err(e) => {
+            eprintln!("Invalid alg: {}", e);
+            exit(exit_code);
+        }
+    }
+}
+
+fn load_kpuzzle(puzzle_source: &PuzzleSource) -> Result<KPuzzle, String> {
+    if let Some(path) = &puzzle_source.kpuzzle_json {
+        let json_bytes =
+            read(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        return KPuzzle::try_from_json(&json_bytes)
+            .map_err(|e| format!("Invalid KPuzzle definition: {}", e));
+    }
+    let puzzle_id = puzzle_source.puzzle.as_deref().unwrap_or("3x3x3");
+    kpuzzle_by_id(puzzle_id).ok_or_else(|| format!("Unknown puzzle: {}", puzzle_id))
+}
+
+fn format_pattern(pattern: &KPattern, format: PatternFormat) -> Result<String, String> {
+    match format {
+        PatternFormat::Json => {
+            // Converting to a `serde_json::Value` sorts the orbits by name, for stable output.
+            let value = serde_json::to_value(pattern.to_data()).map_err(|e| e.to_string())?;
+            serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+        }
+        PatternFormat::Net => render_net(pattern, NetStyle::Letters).map_err(|e| e.to_string()),
+        PatternFormat::AnsiNet => render_net(pattern, NetStyle::Ansi).map_err(|e| e.to_string()),
+        PatternFormat::Facelets => if pattern.kpuzzle().definition().name == "2x2x2" {
+            cube2x2x2_pattern_to_facelets(pattern)
+        } else {
+            cube3x3x3_pattern_to_facelets(pattern)
+        }
+        .map_err(|e| e.to_string()),
+    }
+}
+
 fn main() {
     let args = get_options();
 
     match args.command {
         AlgCLICommand::Parse(parse_args) => {
-            let alg = match parse_args.alg {
-                Some(alg) => alg,
-                None => {
-                    assert!(parse_args.stdin);
-                    read_to_string(stdin()).unwrap()
-                }
+            read_alg(parse_args);
+            eprintln!("Alg parsed successfully.");
+        }
+        AlgCLICommand::Invert(invert_args) => {
+            println!("{}", read_alg(invert_args).invert())
+        }
+        AlgCLICommand::Simplify(simplify_args) => {
+            let mut alg = read_alg(simplify_args.alg_source);
+            if simplify_args.expand {
+                alg = alg.expand();
+            }
+            let options = SimplifyOptions {
+                quantum_move_order: simplify_args.quantum_move_order,
             };
-            let exit_code = match Alg::from_str(&alg) {
-                Ok(_alg) => {
-                    eprintln!("Alg parsed successfully.");
-                    0
-                }
+            println!("{}", alg.simplify(&options))
+        }
+        AlgCLICommand::Expand(expand_args) => {
+            println!("{}", read_alg(expand_args).expand())
+        }
+        AlgCLICommand::Count(count_args) => {
+            let alg = read_alg(count_args.alg_source);
+            println!("{}", alg.count_moves(count_args.metric.into()))
+        }
+        AlgCLICommand::Mirror(mirror_args) => {
+            let alg = read_alg(mirror_args.alg_source);
+            match alg.mirror(mirror_args.plane.into()) {
+                Ok(alg) => println!("{}", alg),
                 Err(e) => {
-                    eprintln!("Invalid alg: {}", e);
-                    1
+                    eprintln!("Could not mirror alg: {}", e);
+                    exit(1);
                 }
-            };
-            exit(exit_code)
+            }
         }
-        AlgCLICommand::Invert(invert_args) => {
-            let alg = match invert_args.alg {
-                Some(alg) => alg,
-                None => {
-                    assert!(invert_args.stdin);
-                    read_to_string(stdin()).unwrap()
-                }
-            };
-            let alg = match Alg::from_str(&alg) {
-                Ok(alg) => alg,
+        AlgCLICommand::Apply(apply_args) => {
+            let alg = read_alg(apply_args.alg_source);
+            let kpuzzle = load_kpuzzle(&apply_args.puzzle_source).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1);
+            });
+            let output = kpuzzle
+                .default_pattern()
+                .apply_alg(&alg)
+                .map_err(|e| format!("Could not apply alg: {}", e))
+                .and_then(|pattern| format_pattern(&pattern, apply_args.format));
+            match output {
+                Ok(output) => println!("{}", output),
                 Err(e) => {
-                    eprintln!("Invalid alg: {}", e);
+                    eprintln!("{}", e);
                     exit(1);
                 }
+            }
+        }
+        AlgCLICommand::Batch(batch_args) => exit(run_batch(batch_args)),
+        AlgCLICommand::Solve(solve_args) => run_solve(solve_args),
+        AlgCLICommand::Link(link_args) => run_link(link_args),
+        AlgCLICommand::ParseLink(parse_link_args) => run_parse_link(parse_link_args),
+        AlgCLICommand::Scramble(scramble_args) => run_scramble(scramble_args),
+        AlgCLICommand::Equivalent(equivalent_args) => {
+            let alg_a = parse_alg_or_exit(&equivalent_args.alg_a, 2);
+            let alg_b = parse_alg_or_exit(&equivalent_args.alg_b, 2);
+            let kpuzzle = load_kpuzzle(&equivalent_args.puzzle_source).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(2);
+            });
+            let transformation = |alg: &Alg| {
+                kpuzzle.transformation_from_alg(alg).unwrap_or_else(|e| {
+                    eprintln!("Could not apply alg: {}", e);
+                    exit(2);
+                })
             };
-            println!("{}", alg.invert())
+            if transformation(&alg_a) == transformation(&alg_b) {
+                eprintln!("The algs are equivalent.");
+            } else {
+                eprintln!("The algs are not equivalent.");
+                exit(1);
+            }
         }
         AlgCLICommand::Completions(_completions_args) => {
             panic!("Completions should have been printed during options parsing, followed by program exit.");
@@ -0,0 +1,265 @@
+use std::process::exit;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use cubing::alg::{Alg, AlgNode, Move, QuantumMove};
+use cubing::kpuzzle::KPuzzle;
+use cubing::scramble::{
+    random_scramble_for_event_with_options, EventScrambleOptions, RandomMovesScrambler,
+};
+use cubing::search::{
+    Cube2x2x2Solver, IddfsOptions, IddfsSolver, SearchError, TwoPhaseOptions, TwoPhaseSolver,
+};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::{load_kpuzzle, parse_alg_or_exit, PuzzleSource};
+
+#[derive(Args, Debug)]
+pub struct SolveArgs {
+    /// Setup alg to apply to the default pattern of the puzzle.
+    #[clap(long)]
+    setup: String,
+
+    #[command(flatten)]
+    puzzle_source: PuzzleSource,
+
+    /// Only use these moves (e.g. `<R,U>`). Every amount of each move is used.
+    ///
+    /// By default, the 3x3x3 uses the two-phase solver and the 2x2x2 uses the
+    /// optimal solver. Otherwise (or if moves are specified), solutions are
+    /// found using a brute-force search that is only practical for short solutions.
+    #[clap(long)]
+    moves: Option<String>,
+
+    /// Maximum number of moves in a solution.
+    ///
+    /// Defaults to 21 for the two-phase solver and 8 for brute-force search.
+    #[clap(long)]
+    max_depth: Option<usize>,
+
+    /// Number of solutions to print (the two-phase solver can only find 1).
+    #[clap(long, default_value_t = 1)]
+    num_solutions: usize,
+
+    /// Give up after this many seconds.
+    #[clap(long, default_value_t = 10)]
+    timeout: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct ScrambleArgs {
+    #[command(flatten)]
+    puzzle_source: PuzzleSource,
+
+    /// Number of scrambles to print.
+    #[clap(long, default_value_t = 1)]
+    count: usize,
+
+    /// Seed for reproducible scrambles.
+    #[clap(long)]
+    seed: Option<u64>,
+
+    /// Generate random-move scrambles using only these moves (e.g. `<R,U>`).
+    ///
+    /// By default, the 3x3x3 and 2x2x2 use random-state scrambles, Megaminx
+    /// and Clock use WCA-style scrambles, and other puzzles use random-move
+    /// scrambles with all of their moves. Square-1 is not supported.
+    #[clap(long)]
+    moves: Option<String>,
+
+    /// Number of moves for random-move scrambles, or the maximum number of
+    /// moves for random-state scrambles. Megaminx and Clock scrambles have a
+    /// fixed length.
+    #[clap(long)]
+    length: Option<usize>,
+}
+
+// Parses a move set like `<R,U>` (or `R U`) into quantum moves.
+fn parse_move_set(move_set: &str) -> Result<Vec<QuantumMove>, String> {
+    let move_set = move_set.trim();
+    let move_set = move_set
+        .strip_prefix('<')
+        .and_then(|move_set| move_set.strip_suffix('>'))
+        .unwrap_or(move_set);
+    let mut quantum_moves = Vec::<QuantumMove>::default();
+    for move_str in move_set.split([',', ' ']).filter(|s| !s.is_empty()) {
+        let r#move = move_str
+            .parse::<Move>()
+            .map_err(|_| format!("Invalid move in move set: {}", move_str))?;
+        if !quantum_moves.contains(&r#move.quantum) {
+            quantum_moves.push(r#move.quantum.as_ref().clone());
+        }
+    }
+    if quantum_moves.is_empty() {
+        return Err("The move set is empty.".to_owned());
+    }
+    Ok(quantum_moves)
+}
+
+fn is_rotation(quantum_move: &QuantumMove) -> bool {
+    matches!(quantum_move.family.as_str(), "x" | "y" | "z") || quantum_move.family.ends_with('v')
+}
+
+fn num_non_rotation_moves(alg: &Alg) -> usize {
+    alg.nodes
+        .iter()
+        .filter(|node| !matches!(node, AlgNode::MoveNode(r#move) if is_rotation(&r#move.quantum)))
+        .count()
+}
+
+// The given move set, or all the (non-rotation) moves defined by the puzzle.
+fn quantum_moves_or_exit(kpuzzle: &KPuzzle, move_set: &Option<String>) -> Vec<QuantumMove> {
+    if let Some(move_set) = move_set {
+        return parse_move_set(move_set).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            exit(1);
+        });
+    }
+    let mut quantum_moves: Vec<QuantumMove> = kpuzzle
+        .definition()
+        .moves
+        .keys()
+        .map(|r#move| r#move.quantum.as_ref().clone())
+        .filter(|quantum_move| !is_rotation(quantum_move))
+        .collect();
+    quantum_moves.sort_by_key(|quantum_move| quantum_move.to_string());
+    quantum_moves
+}
+
+fn exit_on_error<T>(result: Result<T, impl std::fmt::Display>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1);
+    })
+}
+
+pub fn run_solve(args: SolveArgs) {
+    let kpuzzle = exit_on_error(load_kpuzzle(&args.puzzle_source));
+    let setup = parse_alg_or_exit(&args.setup, 1);
+    let pattern = exit_on_error(kpuzzle.default_pattern().apply_alg(&setup));
+
+    let timeout = Duration::from_secs(args.timeout);
+    let start = Instant::now();
+    let (max_depth, solutions): (Option<usize>, Result<Vec<Alg>, SearchError>) =
+        match (&args.moves, kpuzzle.definition().name.as_str()) {
+            (None, "3x3x3") => {
+                if args.num_solutions > 1 {
+                    eprintln!("Note: The two-phase solver only finds 1 solution.");
+                }
+                let options = TwoPhaseOptions {
+                    max_length: args.max_depth.unwrap_or(21),
+                    timeout: Some(timeout),
+                };
+                (
+                    Some(options.max_length),
+                    TwoPhaseSolver::new()
+                        .solve(&pattern, &options)
+                        .map(|solution| solution.into_iter().collect()),
+                )
+            }
+            (None, "2x2x2") => (
+                args.max_depth,
+                Cube2x2x2Solver::new()
+                    .all_optimal_solutions(&pattern)
+                    .map(|solutions| {
+                        solutions
+                            .into_iter()
+                            .filter(|solution| {
+                                // The solution may start with a rotation, which does not count.
+                                args.max_depth.is_none_or(|max_depth| {
+                                    num_non_rotation_moves(solution) <= max_depth
+                                })
+                            })
+                            .take(args.num_solutions)
+                            .collect()
+                    }),
+            ),
+            _ => {
+                let quantum_moves = quantum_moves_or_exit(&kpuzzle, &args.moves);
+                let options = IddfsOptions {
+                    max_depth: args.max_depth.unwrap_or(IddfsOptions::default().max_depth),
+                    max_num_solutions: args.num_solutions,
+                    timeout: Some(timeout),
+                };
+                (
+                    Some(options.max_depth),
+                    IddfsSolver::try_new(kpuzzle, &quantum_moves)
+                        .and_then(|solver| solver.solve(&pattern, &options)),
+                )
+            }
+        };
+
+    let solutions = exit_on_error(solutions);
+    if solutions.is_empty() {
+        let within = max_depth
+            .map(|max_depth| format!(" within {} moves", max_depth))
+            .unwrap_or_default();
+        if start.elapsed() >= timeout {
+            eprintln!(
+                "No solution found{} before the timeout ({} seconds).",
+                within, args.timeout
+            );
+        } else {
+            eprintln!("No solution found{}.", within);
+        }
+        exit(1);
+    }
+    for solution in solutions {
+        println!("{}", solution);
+    }
+}
+
+pub fn run_scramble(args: ScrambleArgs) {
+    let kpuzzle = exit_on_error(load_kpuzzle(&args.puzzle_source));
+    let mut rng: Box<dyn RngCore> = match args.seed {
+        Some(seed) => Box::new(StdRng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+
+    let event_id = match (&args.moves, kpuzzle.definition().name.as_str()) {
+        (_, "square1") => {
+            eprintln!("Scrambles are not supported for square1.");
+            exit(1);
+        }
+        (None, "3x3x3") => Some("333"),
+        (None, "2x2x2") => Some("222"),
+        (None, "megaminx") => Some("minx"),
+        (None, "clock") => Some("clock"),
+        _ => None,
+    };
+    if let Some(event_id) = event_id {
+        let options = EventScrambleOptions {
+            max_length: args.length,
+            ..EventScrambleOptions::default()
+        };
+        for _ in 0..args.count {
+            let scramble = exit_on_error(random_scramble_for_event_with_options(
+                event_id, &options, &mut rng,
+            ));
+            println!("{}", scramble);
+        }
+        return;
+    }
+
+    let quantum_moves = quantum_moves_or_exit(&kpuzzle, &args.moves);
+    let scrambler = exit_on_error(RandomMovesScrambler::try_new_with_all_amounts(
+        kpuzzle,
+        &quantum_moves,
+    ));
+    let length = args.length.unwrap_or(25);
+    for _ in 0..args.count {
+        let scramble = scrambler.random_moves(length, &mut rng);
+        // Runs of commuting moves are only generated in one order, so a move
+        // set where every move commutes can run out of moves.
+        if scramble.nodes.len() < length {
+            eprintln!(
+                "Could not generate a scramble with {} moves (only {} moves are possible without cancellations).",
+                length,
+                scramble.nodes.len()
+            );
+            exit(1);
+        }
+        println!("{}", scramble);
+    }
+}
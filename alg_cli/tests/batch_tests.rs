@@ -0,0 +1,62 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+
+fn run_batch(args: &[&str], input: &str) -> (Vec<Value>, Option<i32>) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_alg"))
+        .arg("batch")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Could not run `alg`.");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let lines = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Output line is not valid JSON."))
+        .collect();
+    (lines, output.status.code())
+}
+
+#[test]
+fn batch_reports_each_line() {
+    let (lines, exit_code) = run_batch(&["invert"], "R U R'\n\nR (U\n[R, U]\n");
+    assert_eq!(
+        lines,
+        vec![
+            json!({"line": 1, "alg": "R U R'", "result": "R U' R'"}),
+            json!({
+                "line": 3,
+                "alg": "R (U",
+                "error": "Invalid alg string (at character 2)",
+                "position": 2,
+            }),
+            json!({"line": 4, "alg": "[R, U]", "result": "[U, R]"}),
+        ]
+    );
+    assert_eq!(exit_code, Some(1));
+}
+
+#[test]
+fn batch_succeeds_when_every_line_is_valid() {
+    let (lines, exit_code) = run_batch(
+        &["count", "--column", "2", "--header"],
+        "name,alg\nsexy,\"[R, U]\"\nsune,R U R' U R U2' R'\n",
+    );
+    assert_eq!(
+        lines,
+        vec![
+            json!({"line": 2, "alg": "[R, U]", "result": 4}),
+            json!({"line": 3, "alg": "R U R' U R U2' R'", "result": 7}),
+        ]
+    );
+    assert_eq!(exit_code, Some(0));
+}
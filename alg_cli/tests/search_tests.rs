@@ -0,0 +1,102 @@
+use std::process::{Command, Output};
+
+use cubing::alg::Alg;
+use cubing::puzzles::{available_puzzles, kpuzzle_by_id};
+
+fn run_alg(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_alg"))
+        .args(args)
+        .output()
+        .expect("Could not run `alg`.")
+}
+
+#[test]
+fn scrambles_every_puzzle() {
+    for puzzle_info in available_puzzles() {
+        let output = run_alg(&["scramble", "--puzzle", &puzzle_info.id, "--seed", "1"]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        if puzzle_info.id == "square1" {
+            assert_eq!(output.status.code(), Some(1));
+            assert_eq!(stderr, "Scrambles are not supported for square1.\n");
+            continue;
+        }
+        assert!(
+            output.status.success(),
+            "Could not scramble {}: {}",
+            puzzle_info.id,
+            stderr
+        );
+        let scramble = stdout.trim_end().parse::<Alg>().unwrap_or_else(|e| {
+            panic!(
+                "Invalid scramble for {}: {} ({})",
+                puzzle_info.id, stdout, e
+            )
+        });
+        // The scramble is printed in a form that parses back to the same alg.
+        assert_eq!(
+            scramble.to_string(),
+            stdout.trim_end(),
+            "{}",
+            puzzle_info.id
+        );
+        let kpuzzle = kpuzzle_by_id(&puzzle_info.id).unwrap();
+        assert_ne!(
+            kpuzzle.default_pattern().apply_alg(&scramble).unwrap(),
+            kpuzzle.default_pattern(),
+            "{}: {}",
+            puzzle_info.id,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn rejects_scrambles_that_are_too_short() {
+    let output = run_alg(&["scramble", "--moves", "<R>", "--length", "5"]);
+    assert_eq!(output.status.code(), Some(1));
+    let output = run_alg(&["scramble", "--puzzle", "clock", "--length", "25"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(run_alg(&["scramble", "--moves", "<R,U>", "--length", "5"])
+        .status
+        .success());
+}
+
+#[test]
+fn solve_respects_timeout() {
+    // `F` cannot be solved using `<R,U>`, so only the timeout can end the search.
+    let output = run_alg(&[
+        "solve",
+        "--puzzle",
+        "2x2x2",
+        "--setup",
+        "F",
+        "--moves",
+        "<R,U>",
+        "--max-depth",
+        "100",
+        "--timeout",
+        "1",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "No solution found within 100 moves before the timeout (1 seconds).\n"
+    );
+}
+
+#[test]
+fn solve_does_not_count_rotations() {
+    // The optimal solution is `x R'`.
+    let output = run_alg(&[
+        "solve",
+        "--puzzle",
+        "2x2x2",
+        "--setup",
+        "L",
+        "--max-depth",
+        "1",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "x R'\n");
+}
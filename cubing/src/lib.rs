@@ -31,3 +31,23 @@ pub mod kpuzzle {
 pub mod puzzles {
     pub use cubing_core::puzzles::*;
 }
+
+pub mod reconstruction {
+    pub use cubing_core::reconstruction::*;
+}
+
+pub mod search {
+    pub use cubing_core::search::*;
+}
+
+pub mod scramble {
+    pub use cubing_core::scramble::*;
+}
+
+pub mod smart_cube {
+    pub use cubing_core::smart_cube::*;
+}
+
+pub mod twizzle_link {
+    pub use cubing_core::twizzle_link::*;
+}
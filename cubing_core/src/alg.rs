@@ -39,3 +39,14 @@ mod parse;
 pub use parse::AlgParseError;
 
 mod special_notation;
+
+mod expand;
+
+mod simplify;
+pub use simplify::SimplifyOptions;
+
+mod move_count;
+pub use move_count::MoveCountMetric;
+
+mod mirror;
+pub use mirror::{AlgMirrorError, MirrorPlane};
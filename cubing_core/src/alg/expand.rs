@@ -0,0 +1,80 @@
+use super::{Alg, AlgNode};
+
+impl Alg {
+    /// Expands all groupings, commutators, and conjugates into a flat sequence of moves.
+    ///
+    /// Pauses, newlines, and comments are kept, and Square-1 tuples (e.g. `(3, -4)`) are left as-is.
+    ///
+    /// ```
+    /// use cubing_core::alg::Alg;
+    ///
+    /// let alg = "[R, U] (F2 D')2".parse::<Alg>().unwrap();
+    /// assert_eq!(alg.expand().to_string(), "R U R' U' F2 D' F2 D'");
+    /// ```
+    pub fn expand(&self) -> Alg {
+        let mut nodes = vec![];
+        for node in &self.nodes {
+            expand_node_into(node, &mut nodes);
+        }
+        Alg { nodes }
+    }
+}
+
+fn expand_alg_into(alg: &Alg, nodes: &mut Vec<AlgNode>) {
+    for node in &alg.nodes {
+        expand_node_into(node, nodes);
+    }
+}
+
+fn expand_node_into(node: &AlgNode, nodes: &mut Vec<AlgNode>) {
+    match node {
+        AlgNode::GroupingNode(grouping) => {
+            if grouping.square1_tuple().is_some() {
+                nodes.push(node.clone());
+                return;
+            }
+            let inner = if grouping.amount < 0 {
+                grouping.alg.invert()
+            } else {
+                grouping.alg.as_ref().clone()
+            };
+            for _ in 0..grouping.amount.unsigned_abs() {
+                expand_alg_into(&inner, nodes);
+            }
+        }
+        AlgNode::CommutatorNode(commutator) => {
+            expand_alg_into(&commutator.a, nodes);
+            expand_alg_into(&commutator.b, nodes);
+            expand_alg_into(&commutator.a.invert(), nodes);
+            expand_alg_into(&commutator.b.invert(), nodes);
+        }
+        AlgNode::ConjugateNode(conjugate) => {
+            expand_alg_into(&conjugate.a, nodes);
+            expand_alg_into(&conjugate.b, nodes);
+            expand_alg_into(&conjugate.a.invert(), nodes);
+        }
+        AlgNode::MoveNode(_)
+        | AlgNode::PauseNode(_)
+        | AlgNode::NewlineNode(_)
+        | AlgNode::LineCommentNode(_) => nodes.push(node.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alg::Alg;
+
+    fn expand(s: &str) -> String {
+        s.parse::<Alg>().unwrap().expand().to_string()
+    }
+
+    #[test]
+    fn expands_nested_algs() {
+        assert_eq!(expand("[R: U]"), "R U R'");
+        assert_eq!(expand("[[R: U], D]"), "R U R' D R U' R' D'");
+        assert_eq!(expand("(R U)2'"), "U' R' U' R'");
+        assert_eq!(expand("(R U)0 F"), "F");
+        assert_eq!(expand("R . U"), "R . U");
+        assert_eq!(expand("(3, -4) / (R)"), "(3, -4) / R");
+    }
+}
@@ -25,7 +25,7 @@ impl Grouping {
         }
     }
 
-    fn square1_tuple(&self) -> Option<(&Move, &Move)> {
+    pub(crate) fn square1_tuple(&self) -> Option<(&Move, &Move)> {
         if self.alg.nodes.len() == 2 && self.amount == 1 {
             // Square-1 notation
             if let AlgNode::MoveNode(move_0) = &self.alg.nodes[0] {
@@ -0,0 +1,186 @@
+use std::{error::Error, fmt::Display, sync::Arc};
+
+use super::{Alg, AlgNode, Commutator, Conjugate, Grouping, Move, QuantumMove};
+
+/// The plane to reflect an alg across in [`Alg::mirror`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MirrorPlane {
+    /// The plane of the `M` slice, swapping `R` and `L`.
+    #[default]
+    M,
+    /// The plane of the `E` slice, swapping `U` and `D`.
+    E,
+    /// The plane of the `S` slice, swapping `F` and `B`.
+    S,
+}
+
+impl MirrorPlane {
+    // The faces, rotation, and slice along the axis perpendicular to the plane.
+    fn axis(&self) -> ((char, char), &'static str, &'static str) {
+        match self {
+            MirrorPlane::M => (('R', 'L'), "x", "M"),
+            MirrorPlane::E => (('U', 'D'), "y", "E"),
+            MirrorPlane::S => (('F', 'B'), "z", "S"),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Could not mirror an alg, usually because it contains moves that are not cube moves.
+pub struct AlgMirrorError {
+    pub description: String,
+}
+
+impl From<String> for AlgMirrorError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for AlgMirrorError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for AlgMirrorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+impl Error for AlgMirrorError {}
+
+// Returns the mirrored family, and whether the amount is inverted.
+fn mirror_family(family: &str, plane: MirrorPlane) -> Option<(String, bool)> {
+    let ((face_1, face_2), rotation, slice) = plane.axis();
+    if matches!(family, "x" | "y" | "z" | "M" | "E" | "S") {
+        let on_axis = family == rotation || family == slice;
+        return Some((family.to_owned(), !on_axis));
+    }
+    let mut chars = family.chars();
+    let first = chars.next()?;
+    let suffix = chars.as_str();
+    let face = first.to_ascii_uppercase();
+    if !"UDFBRL".contains(face) {
+        return None;
+    }
+    match (first.is_ascii_lowercase(), suffix) {
+        (false, "" | "w" | "v") | (true, "") => {}
+        _ => return None,
+    }
+    let mirrored_face = if face == face_1 {
+        face_2
+    } else if face == face_2 {
+        face_1
+    } else {
+        return Some((family.to_owned(), true));
+    };
+    let mirrored_first = if first.is_ascii_lowercase() {
+        mirrored_face.to_ascii_lowercase()
+    } else {
+        mirrored_face
+    };
+    Some((format!("{}{}", mirrored_first, suffix), true))
+}
+
+fn mirror_move(r#move: &Move, plane: MirrorPlane) -> Result<Move, AlgMirrorError> {
+    let Some((family, invert)) = mirror_family(&r#move.quantum.family, plane) else {
+        return Err(format!("Cannot mirror move: {}", r#move.quantum).into());
+    };
+    Ok(Move {
+        quantum: Arc::new(QuantumMove {
+            family,
+            prefix: r#move.quantum.prefix.clone(),
+        }),
+        amount: if invert {
+            -r#move.amount
+        } else {
+            r#move.amount
+        },
+    })
+}
+
+impl Alg {
+    /// Reflects a cube alg across the given plane. For example, mirroring across the `M` plane
+    /// turns a right-handed alg into the corresponding left-handed alg.
+    ///
+    /// ```
+    /// use cubing_core::alg::{Alg, MirrorPlane};
+    ///
+    /// let alg = "R U R' U R U2' R'".parse::<Alg>().unwrap();
+    /// assert_eq!(
+    ///     alg.mirror(MirrorPlane::M).unwrap().to_string(),
+    ///     "L' U' L U' L' U2 L"
+    /// );
+    /// ```
+    pub fn mirror(&self, plane: MirrorPlane) -> Result<Alg, AlgMirrorError> {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| -> Result<AlgNode, AlgMirrorError> {
+                Ok(match node {
+                    AlgNode::MoveNode(r#move) => mirror_move(r#move, plane)?.into(),
+                    AlgNode::GroupingNode(grouping) => AlgNode::GroupingNode(Grouping {
+                        alg: Arc::new(grouping.alg.mirror(plane)?),
+                        amount: grouping.amount,
+                    }),
+                    AlgNode::CommutatorNode(commutator) => AlgNode::CommutatorNode(Commutator {
+                        a: Arc::new(commutator.a.mirror(plane)?),
+                        b: Arc::new(commutator.b.mirror(plane)?),
+                    }),
+                    AlgNode::ConjugateNode(conjugate) => AlgNode::ConjugateNode(Conjugate {
+                        a: Arc::new(conjugate.a.mirror(plane)?),
+                        b: Arc::new(conjugate.b.mirror(plane)?),
+                    }),
+                    AlgNode::PauseNode(_)
+                    | AlgNode::NewlineNode(_)
+                    | AlgNode::LineCommentNode(_) => node.clone(),
+                })
+            })
+            .collect::<Result<Vec<AlgNode>, AlgMirrorError>>()?;
+        Ok(Alg { nodes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alg::Alg;
+
+    use super::MirrorPlane;
+
+    fn mirror(s: &str, plane: MirrorPlane) -> String {
+        s.parse::<Alg>().unwrap().mirror(plane).unwrap().to_string()
+    }
+
+    #[test]
+    fn mirrors_algs() {
+        assert_eq!(
+            mirror("[r Rw 2L Lv: x M y E z S]", MirrorPlane::M),
+            "[l' Lw' 2R' Rv': x M y' E' z' S']"
+        );
+        assert_eq!(
+            mirror("(U F2 D')2 . // hi", MirrorPlane::E),
+            "(D' F2' U)2 . // hi"
+        );
+        assert_eq!(mirror("[F, B']", MirrorPlane::S), "[B', F]");
+        assert!("R /"
+            .parse::<Alg>()
+            .unwrap()
+            .mirror(MirrorPlane::M)
+            .is_err());
+        assert!("BR".parse::<Alg>().unwrap().mirror(MirrorPlane::M).is_err());
+    }
+
+    #[test]
+    fn mirroring_twice_is_identity() {
+        let alg = "R U2 F' r' 3-4Dw B2 M x [Lv: E'] (S z)2"
+            .parse::<Alg>()
+            .unwrap();
+        for plane in [MirrorPlane::M, MirrorPlane::E, MirrorPlane::S] {
+            assert_eq!(alg.mirror(plane).unwrap().mirror(plane).unwrap(), alg);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::{Alg, AlgNode, Move, MovePrefix};
+
+/// The metric to use for [`Alg::count_moves`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MoveCountMetric {
+    /// Half turn metric (outer block turn metric): any turn of an outer block (including wide moves)
+    /// counts as 1 move, and slice moves count as 2. Rotations count as 0.
+    #[default]
+    Htm,
+    /// Quarter turn metric: like [`MoveCountMetric::Htm`], but each quarter turn counts separately
+    /// (e.g. `R2` counts as 2).
+    Qtm,
+    /// Slice turn metric: like [`MoveCountMetric::Htm`], but slice moves count as 1.
+    Stm,
+    /// Execution turn metric: every move counts as 1, including rotations.
+    Etm,
+}
+
+#[derive(PartialEq, Eq)]
+enum MoveKind {
+    Rotation,
+    Slice,
+    Block,
+}
+
+fn move_kind(r#move: &Move) -> MoveKind {
+    let family = r#move.quantum.family.as_str();
+    if matches!(family, "x" | "y" | "z") || family.ends_with('v') {
+        return MoveKind::Rotation;
+    }
+    if matches!(family, "M" | "E" | "S") {
+        return MoveKind::Slice;
+    }
+    // A layer prefix on a wide move (e.g. `3Rw` or `3r`) gives the depth of the block, so only a
+    // bare inner layer move (e.g. `2R`) is a slice.
+    let is_wide = family.ends_with('w') || family.starts_with(|c: char| c.is_ascii_lowercase());
+    match &r#move.quantum.prefix {
+        Some(MovePrefix::Layer(layer)) if layer.layer > 1 && !is_wide => MoveKind::Slice,
+        Some(MovePrefix::Range(range)) if range.outer_layer > 1 => MoveKind::Slice,
+        _ => MoveKind::Block,
+    }
+}
+
+fn count_move(r#move: &Move, metric: MoveCountMetric) -> usize {
+    let kind = move_kind(r#move);
+    let amount = r#move.amount.unsigned_abs() as usize;
+    match (metric, kind) {
+        (MoveCountMetric::Etm, _) => 1,
+        (_, MoveKind::Rotation) => 0,
+        (MoveCountMetric::Htm, MoveKind::Slice) => 2,
+        (MoveCountMetric::Qtm, MoveKind::Slice) => 2 * amount,
+        (MoveCountMetric::Qtm, MoveKind::Block) => amount,
+        (MoveCountMetric::Htm | MoveCountMetric::Stm, _) => 1,
+    }
+}
+
+impl Alg {
+    /// Counts the moves in the alg (after expanding groupings, commutators, and conjugates) using
+    /// the given metric.
+    ///
+    /// ```
+    /// use cubing_core::alg::{Alg, MoveCountMetric};
+    ///
+    /// let alg = "[R, U2] M x".parse::<Alg>().unwrap();
+    /// assert_eq!(alg.count_moves(MoveCountMetric::Htm), 6);
+    /// assert_eq!(alg.count_moves(MoveCountMetric::Qtm), 8);
+    /// assert_eq!(alg.count_moves(MoveCountMetric::Stm), 5);
+    /// assert_eq!(alg.count_moves(MoveCountMetric::Etm), 6);
+    /// ```
+    pub fn count_moves(&self, metric: MoveCountMetric) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| match node {
+                AlgNode::MoveNode(r#move) => count_move(r#move, metric),
+                AlgNode::GroupingNode(grouping) => {
+                    grouping.alg.count_moves(metric) * grouping.amount.unsigned_abs() as usize
+                }
+                AlgNode::CommutatorNode(commutator) => {
+                    2 * (commutator.a.count_moves(metric) + commutator.b.count_moves(metric))
+                }
+                AlgNode::ConjugateNode(conjugate) => {
+                    2 * conjugate.a.count_moves(metric) + conjugate.b.count_moves(metric)
+                }
+                AlgNode::PauseNode(_) | AlgNode::NewlineNode(_) | AlgNode::LineCommentNode(_) => 0,
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alg::Alg;
+
+    use super::MoveCountMetric;
+
+    fn count(s: &str, metric: MoveCountMetric) -> usize {
+        s.parse::<Alg>().unwrap().count_moves(metric)
+    }
+
+    #[test]
+    fn counts_moves() {
+        let t_perm = "R U R' U' R' F R2 U' R' U' R U R' F'";
+        assert_eq!(count(t_perm, MoveCountMetric::Htm), 14);
+        assert_eq!(count(t_perm, MoveCountMetric::Qtm), 15);
+        assert_eq!(count("(R U2 r Rw 2R 2-3Rw)2'", MoveCountMetric::Htm), 16);
+        assert_eq!(count("(R U2 r Rw 2R 2-3Rw)2'", MoveCountMetric::Stm), 12);
+        assert_eq!(count("[R: U] . y Uv' x2", MoveCountMetric::Htm), 3);
+        assert_eq!(count("[R: U] . y Uv' x2", MoveCountMetric::Qtm), 3);
+        assert_eq!(count("[R: U] . y Uv' x2", MoveCountMetric::Etm), 6);
+        assert_eq!(count("M2 E S'", MoveCountMetric::Qtm), 8);
+        assert_eq!(count("2Rw 3Rw' 3r 2R", MoveCountMetric::Htm), 5);
+        assert_eq!(count("2Rw 3Rw' 3r 2R", MoveCountMetric::Stm), 4);
+        assert_eq!(count("3Rw2 2R2", MoveCountMetric::Qtm), 6);
+    }
+}
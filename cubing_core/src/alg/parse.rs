@@ -22,12 +22,17 @@ use super::{
 /// Could not parse an alg (or alg part), usually due to invalid syntax.
 pub struct AlgParseError {
     pub description: String,
+    /// The character index in the input at which parsing failed, if known.
+    pub position: Option<usize>,
 }
 
 // TODO: is Rust smart enough to optimize this using just the `From<&str>` definition?
 impl From<String> for AlgParseError {
     fn from(description: String) -> Self {
-        Self { description }
+        Self {
+            description,
+            position: None,
+        }
     }
 }
 
@@ -35,6 +40,7 @@ impl From<&str> for AlgParseError {
     fn from(description: &str) -> Self {
         Self {
             description: description.to_owned(),
+            position: None,
         }
     }
 }
@@ -333,10 +339,14 @@ impl FromStr for Alg {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match all_consuming(parse_alg)(s) {
             Ok((_, alg)) => Ok(alg),
-            Err(s) => {
-                eprintln!("Alg parsing error: {}", s);
-                Err("Invalid alg string".into()) // TODO: pass on error
+            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                let position = s[..s.len() - e.input.len()].chars().count();
+                Err(AlgParseError {
+                    description: format!("Invalid alg string (at character {})", position),
+                    position: Some(position),
+                })
             }
+            Err(nom::Err::Incomplete(_)) => Err("Invalid alg string".into()),
         }
     }
 }
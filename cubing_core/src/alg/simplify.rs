@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use super::{
+    amount::Amount,
+    r#move::{_PLUSPLUS_, _SLASH_},
+    Alg, AlgNode, Commutator, Conjugate, Grouping, Move,
+};
+
+/// Options for [`Alg::simplify`].
+#[derive(Debug, Clone, Default)]
+pub struct SimplifyOptions {
+    /// If set, move amounts are reduced modulo this order into the range `(-order/2, order/2]`
+    /// (e.g. `R3` becomes `R'` and `R2'` becomes `R2` for an order of 4), and moves that reduce to
+    /// an amount of 0 are removed.
+    pub quantum_move_order: Option<Amount>,
+}
+
+impl Alg {
+    /// Combines adjacent moves of the same quantum move (e.g. `R R` becomes `R2`, and `R R'` cancels
+    /// completely). This is applied recursively inside groupings, commutators, and conjugates, but
+    /// does not cancel moves across their boundaries. Pauses, newlines, and comments are kept and
+    /// also prevent cancellation across them.
+    ///
+    /// ```
+    /// use cubing_core::alg::{Alg, SimplifyOptions};
+    ///
+    /// let alg = "R U U' R2 F".parse::<Alg>().unwrap();
+    /// assert_eq!(alg.simplify(&SimplifyOptions::default()).to_string(), "R3 F");
+    /// let options = SimplifyOptions {
+    ///     quantum_move_order: Some(4),
+    /// };
+    /// assert_eq!(alg.simplify(&options).to_string(), "R' F");
+    /// ```
+    pub fn simplify(&self, options: &SimplifyOptions) -> Alg {
+        let mut nodes: Vec<AlgNode> = vec![];
+        for node in &self.nodes {
+            match node {
+                AlgNode::MoveNode(r#move) => push_move(&mut nodes, r#move, options),
+                AlgNode::GroupingNode(grouping) => {
+                    if grouping.square1_tuple().is_some() {
+                        nodes.push(node.clone());
+                        continue;
+                    }
+                    let alg = grouping.alg.simplify(options);
+                    if grouping.amount == 0 || alg.nodes.is_empty() {
+                        continue;
+                    }
+                    nodes.push(AlgNode::GroupingNode(Grouping {
+                        alg: Arc::new(alg),
+                        amount: grouping.amount,
+                    }));
+                }
+                AlgNode::CommutatorNode(commutator) => {
+                    let a = commutator.a.simplify(options);
+                    let b = commutator.b.simplify(options);
+                    // A commutator with an empty side is the identity.
+                    if a.nodes.is_empty() || b.nodes.is_empty() {
+                        continue;
+                    }
+                    nodes.push(AlgNode::CommutatorNode(Commutator {
+                        a: Arc::new(a),
+                        b: Arc::new(b),
+                    }));
+                }
+                AlgNode::ConjugateNode(conjugate) => {
+                    let a = conjugate.a.simplify(options);
+                    let b = conjugate.b.simplify(options);
+                    if b.nodes.is_empty() {
+                        continue;
+                    }
+                    if a.nodes.is_empty() {
+                        nodes.extend(b.nodes);
+                        continue;
+                    }
+                    nodes.push(AlgNode::ConjugateNode(Conjugate {
+                        a: Arc::new(a),
+                        b: Arc::new(b),
+                    }));
+                }
+                AlgNode::PauseNode(_) | AlgNode::NewlineNode(_) | AlgNode::LineCommentNode(_) => {
+                    nodes.push(node.clone())
+                }
+            }
+        }
+        Alg { nodes }
+    }
+}
+
+fn is_mergeable(r#move: &Move) -> bool {
+    r#move.quantum.family != _SLASH_ && r#move.quantum.family != _PLUSPLUS_
+}
+
+fn reduce_amount(amount: Amount, options: &SimplifyOptions) -> Amount {
+    match options.quantum_move_order {
+        Some(order) if order > 0 => {
+            let amount = amount.rem_euclid(order);
+            if amount > order / 2 {
+                amount - order
+            } else {
+                amount
+            }
+        }
+        _ => amount,
+    }
+}
+
+fn push_move(nodes: &mut Vec<AlgNode>, r#move: &Move, options: &SimplifyOptions) {
+    if !is_mergeable(r#move) {
+        nodes.push(r#move.clone().into());
+        return;
+    }
+    let mut amount = r#move.amount;
+    if let Some(AlgNode::MoveNode(previous)) = nodes.last() {
+        if previous.quantum == r#move.quantum {
+            amount += previous.amount;
+            nodes.pop();
+        }
+    }
+    let amount = reduce_amount(amount, options);
+    if amount != 0 {
+        nodes.push(
+            Move {
+                quantum: r#move.quantum.clone(),
+                amount,
+            }
+            .into(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alg::Alg;
+
+    use super::SimplifyOptions;
+
+    fn simplify(s: &str, quantum_move_order: Option<i32>) -> String {
+        s.parse::<Alg>()
+            .unwrap()
+            .simplify(&SimplifyOptions { quantum_move_order })
+            .to_string()
+    }
+
+    #[test]
+    fn cancels_moves() {
+        assert_eq!(simplify("R U U' R'", None), "");
+        assert_eq!(simplify("R U U' R2", None), "R3");
+        assert_eq!(simplify("R U U' R2", Some(4)), "R'");
+        assert_eq!(simplify("R2 R2' R4 F", Some(4)), "F");
+        assert_eq!(simplify("R2' R2'", Some(4)), "");
+        assert_eq!(simplify("R2'", Some(4)), "R2");
+        assert_eq!(simplify("U2 U", Some(3)), "");
+        assert_eq!(simplify("R Rw r 2R", None), "R Rw r 2R");
+        assert_eq!(simplify("R . R'", None), "R . R'");
+        assert_eq!(simplify("/ /", None), "/ /");
+    }
+
+    #[test]
+    fn simplifies_recursively() {
+        assert_eq!(simplify("(R R)2 [F F': U]", None), "(R2)2 U");
+        assert_eq!(simplify("[R2 R2, U] [F U U', D D]", Some(4)), "[F, D2]");
+        assert_eq!(simplify("(3, -4) / (3, -4)", None), "(3, -4) / (3, -4)");
+    }
+}
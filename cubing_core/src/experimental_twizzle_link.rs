@@ -1,25 +1,31 @@
+//! Links with string parameters. See [`crate::twizzle_link`] for typed parameters and alg validation.
+
 use url::Url;
 
-use crate::alg::Alg;
+use crate::{alg::Alg, twizzle_link::TwizzleLink};
 
-#[derive(Default)]
-pub enum ExperimentalSetupAnchor {
-    #[default]
-    Start,
-    End,
-}
+pub use crate::twizzle_link::{
+    TwizzleLinkParseError, TwizzleLinkTarget as ExperimentalTwizzleLinkTarget,
+    TwizzleSetupAnchor as ExperimentalSetupAnchor,
+};
 
 #[derive(Default)]
 pub struct ExperimentalTwizzleLinkParameters<'a> {
+    pub target: ExperimentalTwizzleLinkTarget,
     pub setup: Option<&'a Alg>,
     pub alg: Option<&'a Alg>,
     pub puzzle: Option<&'a str>,
     pub stickering: Option<&'a str>,
     pub setup_anchor: Option<&'a str>,
+    pub title: Option<&'a str>,
 }
 
 pub fn experimental_twizzle_link(parameters: ExperimentalTwizzleLinkParameters) -> String {
-    let mut url = Url::parse("https://alpha.twizzle.net/edit/").unwrap();
+    let mut url = Url::parse(match parameters.target {
+        ExperimentalTwizzleLinkTarget::Edit => "https://alpha.twizzle.net/edit/",
+        ExperimentalTwizzleLinkTarget::Explore => "https://alpha.twizzle.net/explore/",
+    })
+    .unwrap();
     if let Some(setup) = parameters.setup {
         url.query_pairs_mut()
             .append_pair("setup-alg", &setup.to_string());
@@ -32,10 +38,124 @@ pub fn experimental_twizzle_link(parameters: ExperimentalTwizzleLinkParameters)
     }
     if let Some(setup_anchor) = parameters.setup_anchor {
         url.query_pairs_mut()
-            .append_pair("setup_anchor", setup_anchor);
+            .append_pair("setup-anchor", setup_anchor);
     }
     if let Some(stickering) = parameters.stickering {
         url.query_pairs_mut().append_pair("stickering", stickering);
     }
+    if let Some(title) = parameters.title {
+        url.query_pairs_mut().append_pair("title", title);
+    }
     url.to_string()
 }
+
+/// The parameters of a Twizzle or `alg.cubing.net` link, as parsed by [`parse_experimental_twizzle_link`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExperimentalTwizzleLink {
+    pub target: ExperimentalTwizzleLinkTarget,
+    pub setup: Option<Alg>,
+    pub alg: Option<Alg>,
+    pub puzzle: Option<String>,
+    pub stickering: Option<String>,
+    pub setup_anchor: Option<ExperimentalSetupAnchor>,
+    pub title: Option<String>,
+}
+
+impl ExperimentalTwizzleLink {
+    /// Parameters for [`experimental_twizzle_link`], to build a Twizzle link with the same parameters.
+    pub fn parameters(&self) -> ExperimentalTwizzleLinkParameters<'_> {
+        ExperimentalTwizzleLinkParameters {
+            target: self.target,
+            setup: self.setup.as_ref(),
+            alg: self.alg.as_ref(),
+            puzzle: self.puzzle.as_deref(),
+            stickering: self.stickering.as_deref(),
+            setup_anchor: self.setup_anchor.map(|setup_anchor| setup_anchor.as_str()),
+            title: self.title.as_deref(),
+        }
+    }
+}
+
+/// Parses a link to Twizzle (`/edit/` or `/explore/` on `alpha.twizzle.net`
+/// or `twizzle.net`) or `alg.cubing.net`.
+///
+/// Unknown query parameters are ignored. For Twizzle links, this round-trips with [`experimental_twizzle_link`]:
+///
+/// ```
+/// use cubing_core::experimental_twizzle_link::{experimental_twizzle_link, parse_experimental_twizzle_link};
+///
+/// let link = parse_experimental_twizzle_link("https://alg.cubing.net/?alg=R_U-_R-&setup=F2&puzzle=2x2x2").unwrap();
+/// assert_eq!(link.alg.as_ref().unwrap().to_string(), "R U' R'");
+/// assert_eq!(
+///     experimental_twizzle_link(link.parameters()),
+///     "https://alpha.twizzle.net/edit/?setup-alg=F2&alg=R+U%27+R%27&puzzle=2x2x2"
+/// );
+/// ```
+pub fn parse_experimental_twizzle_link(
+    link: &str,
+) -> Result<ExperimentalTwizzleLink, TwizzleLinkParseError> {
+    let link = link.parse::<TwizzleLink>()?;
+    Ok(ExperimentalTwizzleLink {
+        target: link.target,
+        setup: link.setup,
+        alg: link.alg,
+        puzzle: link.puzzle.map(|puzzle| puzzle.as_str().to_owned()),
+        stickering: link
+            .stickering
+            .map(|stickering| stickering.as_str().to_owned()),
+        setup_anchor: link.setup_anchor,
+        title: link.title,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alg::Alg;
+
+    use super::{
+        experimental_twizzle_link, parse_experimental_twizzle_link, ExperimentalSetupAnchor,
+        ExperimentalTwizzleLink, ExperimentalTwizzleLinkTarget,
+    };
+
+    #[test]
+    fn round_trips_twizzle_links() {
+        let link = ExperimentalTwizzleLink {
+            target: ExperimentalTwizzleLinkTarget::Explore,
+            setup: Some("F R U".parse::<Alg>().unwrap()),
+            alg: Some("[R, U']\n// comment & more".parse::<Alg>().unwrap()),
+            puzzle: Some("3x3x3".to_owned()),
+            stickering: Some("OLL".to_owned()),
+            setup_anchor: Some(ExperimentalSetupAnchor::End),
+            title: Some("Sune? 100%".to_owned()),
+        };
+        let url = experimental_twizzle_link(link.parameters());
+        assert!(url.starts_with("https://alpha.twizzle.net/explore/?setup-alg=F+R+U&"));
+        assert_eq!(parse_experimental_twizzle_link(&url).unwrap(), link);
+
+        let empty = parse_experimental_twizzle_link("https://twizzle.net/edit").unwrap();
+        assert_eq!(empty, ExperimentalTwizzleLink::default());
+    }
+
+    #[test]
+    fn parses_alg_cubing_net_links() {
+        let link = parse_experimental_twizzle_link(
+            "https://alg.cubing.net/?alg=2%26%2345;3Rw_(3,_%26%2345;4)_/&setup=R-&title=my_alg",
+        )
+        .unwrap();
+        assert_eq!(link.title.unwrap(), "my_alg");
+        assert_eq!(link.alg.unwrap().to_string(), "2-3Rw (3, -4) /");
+        assert_eq!(link.setup.unwrap().to_string(), "R'");
+    }
+
+    #[test]
+    fn rejects_invalid_links() {
+        assert!(parse_experimental_twizzle_link("R U R'").is_err());
+        assert!(parse_experimental_twizzle_link("https://example.com/edit/?alg=R").is_err());
+        assert!(parse_experimental_twizzle_link("https://alpha.twizzle.net/play/").is_err());
+        assert!(parse_experimental_twizzle_link("https://alpha.twizzle.net/edit/?alg=(R").is_err());
+        assert!(parse_experimental_twizzle_link(
+            "https://alpha.twizzle.net/edit/?setup-anchor=middle"
+        )
+        .is_err());
+    }
+}
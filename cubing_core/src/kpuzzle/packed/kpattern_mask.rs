@@ -0,0 +1,201 @@
+use super::{orientation_packer::OrientationWithMod, KPattern, KPuzzle, KPuzzleOrbitInfo};
+
+// Masked positions hold this piece value, so that they compare as equal regardless of the actual piece.
+const MASKED_PIECE: u8 = u8::MAX;
+const MASKED_ORIENTATION: OrientationWithMod = OrientationWithMod {
+    orientation: 0,
+    orientation_mod: 1,
+};
+
+/// Which parts of a piece position are compared by a [`KPatternMask`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PieceMask {
+    /// Both the piece and its orientation are compared.
+    #[default]
+    Regular,
+    /// Nothing about the position is compared.
+    Ignored,
+    /// Only the orientation is compared (e.g. the last layer pieces for OLL).
+    OrientationOnly,
+    /// Only the piece is compared (e.g. the last layer pieces for PLL).
+    PermutationOnly,
+}
+
+/// A mask over the piece positions of a [`KPuzzle`], for comparing
+/// [`KPattern`]s while ignoring some pieces or some aspects of them.
+///
+/// Masks apply to positions rather than to pieces: a position that is marked
+/// as [`PieceMask::Ignored`] is ignored no matter which piece is there.
+///
+/// ```
+/// use cubing_core::{
+///     alg::Alg,
+///     kpuzzle::{KPatternMask, PieceMask},
+///     puzzles::cube3x3x3_kpuzzle,
+/// };
+///
+/// let kpuzzle = cube3x3x3_kpuzzle();
+/// let mut mask = KPatternMask::new(kpuzzle);
+/// for orbit in kpuzzle.orbit_info_iter() {
+///     for i in 0..orbit.num_pieces {
+///         if orbit.name.0 != "CENTERS" && i < 4 {
+///             mask.set(orbit, i, PieceMask::Ignored);
+///         }
+///     }
+/// }
+/// let pattern = kpuzzle
+///     .default_pattern()
+///     .apply_alg(&"R U R' U R U2' R'".parse::<Alg>().unwrap())
+///     .unwrap();
+/// assert!(mask.is_solved(&pattern));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KPatternMask {
+    kpuzzle: KPuzzle,
+    // Indexed in the same order as `KPuzzle::orbit_info_iter()`.
+    orbits: Vec<Vec<PieceMask>>,
+}
+
+impl KPatternMask {
+    /// Creates a mask with every position set to [`PieceMask::Regular`].
+    pub fn new<T: Into<KPuzzle>>(kpuzzle: T) -> Self {
+        let kpuzzle: KPuzzle = kpuzzle.into();
+        let orbits = kpuzzle
+            .orbit_info_iter()
+            .map(|orbit| vec![PieceMask::Regular; orbit.num_pieces as usize])
+            .collect();
+        Self { kpuzzle, orbits }
+    }
+
+    pub fn kpuzzle(&self) -> &KPuzzle {
+        &self.kpuzzle
+    }
+
+    fn orbit_index(&self, orbit: &KPuzzleOrbitInfo) -> usize {
+        self.kpuzzle
+            .orbit_info_iter()
+            .position(|orbit_info| orbit_info.name == orbit.name)
+            .expect("Orbit does not belong to the puzzle of this mask.")
+    }
+
+    pub fn get(&self, orbit: &KPuzzleOrbitInfo, i: u8) -> PieceMask {
+        self.orbits[self.orbit_index(orbit)][i as usize]
+    }
+
+    pub fn set(&mut self, orbit: &KPuzzleOrbitInfo, i: u8, piece_mask: PieceMask) {
+        let orbit_index = self.orbit_index(orbit);
+        self.orbits[orbit_index][i as usize] = piece_mask;
+    }
+
+    /// Sets every position in the orbit.
+    pub fn set_orbit(&mut self, orbit: &KPuzzleOrbitInfo, piece_mask: PieceMask) {
+        let orbit_index = self.orbit_index(orbit);
+        self.orbits[orbit_index].fill(piece_mask);
+    }
+
+    /// Returns a copy of the pattern where the masked parts of each position are
+    /// replaced by placeholder values. Masked patterns can be compared using `==`.
+    pub fn apply(&self, pattern: &KPattern) -> KPattern {
+        let mut masked = pattern.clone();
+        for (orbit, piece_masks) in self.kpuzzle.orbit_info_iter().zip(&self.orbits) {
+            for (i, piece_mask) in piece_masks.iter().enumerate() {
+                let i = i as u8;
+                if matches!(piece_mask, PieceMask::Ignored | PieceMask::OrientationOnly) {
+                    masked.set_piece(orbit, i, MASKED_PIECE);
+                }
+                if matches!(piece_mask, PieceMask::Ignored | PieceMask::PermutationOnly) {
+                    masked.set_orientation_with_mod(orbit, i, &MASKED_ORIENTATION);
+                }
+            }
+        }
+        masked
+    }
+
+    /// Compares two patterns, ignoring the masked parts of each position.
+    pub fn patterns_equal(&self, pattern1: &KPattern, pattern2: &KPattern) -> bool {
+        for (orbit, piece_masks) in self.kpuzzle.orbit_info_iter().zip(&self.orbits) {
+            for (i, piece_mask) in piece_masks.iter().enumerate() {
+                let i = i as u8;
+                let same_piece = || pattern1.get_piece(orbit, i) == pattern2.get_piece(orbit, i);
+                let same_orientation = || {
+                    pattern1.get_orientation_with_mod(orbit, i)
+                        == pattern2.get_orientation_with_mod(orbit, i)
+                };
+                let equal = match piece_mask {
+                    PieceMask::Regular => same_piece() && same_orientation(),
+                    PieceMask::Ignored => true,
+                    PieceMask::OrientationOnly => same_orientation(),
+                    PieceMask::PermutationOnly => same_piece(),
+                };
+                if !equal {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Compares the pattern to the default pattern of the puzzle, ignoring the masked parts of each position.
+    pub fn is_solved(&self, pattern: &KPattern) -> bool {
+        self.patterns_equal(pattern, &self.kpuzzle.default_pattern())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alg::Alg, kpuzzle::KPattern, puzzles::cube3x3x3_kpuzzle};
+
+    use super::{KPatternMask, PieceMask};
+
+    fn pattern(alg: &str) -> KPattern {
+        cube3x3x3_kpuzzle()
+            .default_pattern()
+            .apply_alg(&alg.parse::<Alg>().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn masks_positions() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let edges = kpuzzle.lookup_orbit(&"EDGES".into()).unwrap();
+        let corners = kpuzzle.lookup_orbit(&"CORNERS".into()).unwrap();
+        let mut mask = KPatternMask::new(kpuzzle);
+        assert!(mask.is_solved(&pattern("")));
+        assert!(!mask.is_solved(&pattern("U")));
+
+        mask.set_orbit(corners, PieceMask::Ignored);
+        for i in 0..4 {
+            mask.set(edges, i, PieceMask::OrientationOnly);
+        }
+        assert_eq!(mask.get(edges, 0), PieceMask::OrientationOnly);
+        assert_eq!(mask.get(edges, 4), PieceMask::Regular);
+        // Permutes U layer edges and twists corners.
+        assert!(mask.is_solved(&pattern("R U R' U R U2' R' U")));
+        // Flips two U layer edges.
+        let flip = "M' U M' U M' U M' U2 M' U M' U M' U M'";
+        assert!(!mask.is_solved(&pattern(flip)));
+
+        for i in 0..4 {
+            mask.set(edges, i, PieceMask::PermutationOnly);
+        }
+        assert!(!mask.is_solved(&pattern("R U R' U R U2' R' U")));
+        assert!(mask.is_solved(&pattern(flip)));
+    }
+
+    #[test]
+    fn applies_to_patterns() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let mut mask = KPatternMask::new(kpuzzle);
+        mask.set_orbit(
+            kpuzzle.lookup_orbit(&"CORNERS".into()).unwrap(),
+            PieceMask::Ignored,
+        );
+        let a_perm = pattern("R' F R' B2 R F' R' B2 R2");
+        assert_ne!(a_perm, kpuzzle.default_pattern());
+        assert_eq!(mask.apply(&a_perm), mask.apply(&kpuzzle.default_pattern()));
+        assert_ne!(
+            mask.apply(&pattern("M2 U2 M2 U2")),
+            mask.apply(&kpuzzle.default_pattern())
+        );
+    }
+}
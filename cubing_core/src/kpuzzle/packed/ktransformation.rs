@@ -8,7 +8,8 @@ use crate::{
 };
 
 use super::{
-    kpuzzle::KPuzzleOrbitInfo, packed_orbit_data::PackedOrbitData, ConversionError, KPuzzle,
+    kpuzzle::KPuzzleOrbitInfo, packed_orbit_data::PackedOrbitData, ConversionError, KPattern,
+    KPuzzle,
 };
 
 #[derive(Clone, Eq)]
@@ -274,6 +275,60 @@ impl KTransformation {
         new_ktransformation
     }
 
+    /// The number of times the transformation must be applied to get back to the identity.
+    pub fn order(&self) -> u64 {
+        let mut order: u64 = 1;
+        for orbit_info in self.kpuzzle().orbit_info_iter() {
+            let num_orientations = orbit_info.num_orientations as u64;
+            let mut visited = vec![false; orbit_info.num_pieces as usize];
+            for start in 0..orbit_info.num_pieces {
+                if visited[start as usize] {
+                    continue;
+                }
+                let mut cycle_length: u64 = 0;
+                let mut total_orientation_delta: u64 = 0;
+                let mut i = start;
+                while !visited[i as usize] {
+                    visited[i as usize] = true;
+                    cycle_length += 1;
+                    total_orientation_delta += self.get_orientation_delta(orbit_info, i) as u64;
+                    i = self.get_permutation_idx(orbit_info, i);
+                }
+                let twist_order = num_orientations
+                    / gcd(num_orientations, total_orientation_delta % num_orientations);
+                order = lcm(order, cycle_length * twist_order);
+            }
+        }
+        order
+    }
+
+    /// The number of times the transformation must be applied to `pattern` to get back to `pattern`.
+    ///
+    /// This divides [`KTransformation::order`], and can be smaller if the pattern has identical pieces
+    /// or ignored orientations (such as the centers of the default 3x3x3 pattern).
+    pub fn pattern_order(&self, pattern: &KPattern) -> u64 {
+        let order = self.order();
+        let mut divisors = vec![];
+        let mut d: u64 = 1;
+        while d * d <= order {
+            if order.is_multiple_of(d) {
+                divisors.push(d);
+                divisors.push(order / d);
+            }
+            d += 1;
+        }
+        divisors.sort();
+        for d in divisors {
+            let Ok(amount) = Amount::try_from(d) else {
+                break;
+            };
+            if pattern.apply_transformation(&self.self_multiply(amount)) == *pattern {
+                return d;
+            }
+        }
+        order
+    }
+
     pub(crate) fn self_multiply(&self, amount: Amount) -> Self {
         if amount == 1 {
             return self.clone();
@@ -301,6 +356,18 @@ impl KTransformation {
     }
 }
 
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
 struct KPuzzleDebug {
     kpuzzle: KPuzzle,
 }
@@ -378,6 +445,7 @@ impl PartialEq for KTransformationBuffer {
 
 #[cfg(test)]
 mod tests {
+    use crate::alg::Alg;
     use crate::alg::AlgParseError;
     use crate::alg::Move;
     use crate::kpuzzle::InvalidAlgError;
@@ -418,4 +486,32 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn order() -> Result<(), String> {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let orders = |alg_str: &str| -> Result<(u64, u64), String> {
+            let alg = alg_str
+                .parse::<Alg>()
+                .map_err(|e: AlgParseError| e.description)?;
+            let transformation = kpuzzle
+                .transformation_from_alg(&alg)
+                .map_err(|e: InvalidAlgError| e.to_string())?;
+            Ok((
+                transformation.order(),
+                transformation.pattern_order(&kpuzzle.default_pattern()),
+            ))
+        };
+
+        assert_eq!(orders("")?, (1, 1));
+        assert_eq!(orders("R")?, (4, 4));
+        assert_eq!(orders("R2")?, (2, 2));
+        // The center orientations are ignored in the default pattern.
+        assert_eq!(orders("R U")?, (420, 105));
+        assert_eq!(orders("R U R' U'")?, (6, 6));
+        assert_eq!(orders("R U2 D' B D'")?, (1260, 1260));
+        assert_eq!(orders("x")?, (4, 4));
+
+        Ok(())
+    }
 }
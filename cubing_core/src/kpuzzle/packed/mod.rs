@@ -18,5 +18,18 @@ pub use kpattern::{KPattern, KPatternBuffer};
 mod orientation_packer;
 pub use orientation_packer::OrientationWithMod;
 
+mod ranking;
+pub use ranking::{
+    combination_rank, combination_unrank, num_combinations, num_permutations, permutation_parity,
+    permutation_rank, permutation_unrank, InvalidKPatternRankerError, KPatternRanker,
+    KPatternRankerOrbit, OrientationRanking,
+};
+
 mod derived_moves_validator;
 mod lookup_move;
+
+mod kpattern_mask;
+pub use kpattern_mask::{KPatternMask, PieceMask};
+
+mod symmetries;
+pub use symmetries::{KPuzzleSymmetries, SymmetryError};
@@ -0,0 +1,637 @@
+use std::fmt::Display;
+
+use super::{kpuzzle::KPuzzleOrbitInfo, orientation_packer::OrientationWithMod, KPattern, KPuzzle};
+
+use crate::kpuzzle::KPuzzleOrbitName;
+
+const NOT_RANKED: u8 = 0xFF;
+
+/// The number of permutations of `n` elements, if it fits in a `u64`.
+pub fn num_permutations(n: usize) -> Option<u64> {
+    (1..=(n as u64)).try_fold(1u64, |acc, i| acc.checked_mul(i))
+}
+
+/// The number of `k`-element subsets of an `n`-element set, if it fits in a `u64`.
+pub fn num_combinations(n: usize, k: usize) -> Option<u64> {
+    if k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k);
+    let mut result: u64 = 1;
+    for i in 0..k {
+        // This division is always exact, since `result` is `C(n, i + 1) * (i + 1)` at this point.
+        result = result.checked_mul((n - i) as u64)? / ((i + 1) as u64);
+    }
+    Some(result)
+}
+
+/// Computes the lexicographic rank of a permutation of the values `0..n` (i.e.
+/// its Lehmer code interpreted as a factorial-base number).
+///
+/// `permutation` must contain each value in `0..permutation.len()` exactly
+/// once, and must have at most 20 elements (so that the rank fits in a `u64`).
+pub fn permutation_rank(permutation: &[u8]) -> u64 {
+    // Bitmask of values seen so far, so that each Lehmer digit is a popcount.
+    // This supports up to 128 values, which is plenty for any `u64` rank.
+    let mut seen: u128 = 0;
+    let mut rank: u64 = 0;
+    let n = permutation.len() as u64;
+    for (i, &value) in permutation.iter().enumerate() {
+        let smaller_unseen = value as u64 - (seen & ((1u128 << value) - 1)).count_ones() as u64;
+        rank = rank * (n - i as u64) + smaller_unseen;
+        seen |= 1u128 << value;
+    }
+    rank
+}
+
+/// Writes the permutation of `0..permutation.len()` with the given lexicographic rank into `permutation`.
+///
+/// This is the inverse of [`permutation_rank`].
+pub fn permutation_unrank(rank: u64, permutation: &mut [u8]) {
+    let n = permutation.len();
+    let mut digits = vec![0u8; n];
+    let mut rank = rank;
+    for (i, digit) in digits.iter_mut().enumerate().rev() {
+        let radix = (n - i) as u64;
+        *digit = (rank % radix) as u8;
+        rank /= radix;
+    }
+    let mut unused: Vec<u8> = (0..n as u8).collect();
+    for (i, digit) in digits.into_iter().enumerate() {
+        permutation[i] = unused.remove(digit as usize);
+    }
+}
+
+/// Computes the parity of a permutation of `0..n` (`true` for odd).
+pub fn permutation_parity(permutation: &[u8]) -> bool {
+    let mut visited = vec![false; permutation.len()];
+    let mut parity = false;
+    for start in 0..permutation.len() {
+        if visited[start] {
+            continue;
+        }
+        let mut current = start;
+        let mut cycle_length = 0;
+        while !visited[current] {
+            visited[current] = true;
+            current = permutation[current] as usize;
+            cycle_length += 1;
+        }
+        if cycle_length % 2 == 0 {
+            parity = !parity;
+        }
+    }
+    parity
+}
+
+/// Computes the lexicographic rank of the set of positions for which `is_member` is `true`, among all subsets of the same size.
+pub fn combination_rank(is_member: &[bool]) -> u64 {
+    let mut rank: u64 = 0;
+    let mut remaining = is_member.iter().filter(|&&b| b).count();
+    let n = is_member.len();
+    for (i, &member) in is_member.iter().enumerate() {
+        if remaining == 0 {
+            break;
+        }
+        if member {
+            remaining -= 1;
+        } else {
+            // Count all subsets that would have chosen this position instead.
+            rank += num_combinations(n - i - 1, remaining - 1).unwrap();
+        }
+    }
+    rank
+}
+
+/// Writes the subset with the given lexicographic rank (with `num_members` members) into `is_member`.
+///
+/// This is the inverse of [`combination_rank`].
+pub fn combination_unrank(rank: u64, num_members: usize, is_member: &mut [bool]) {
+    let n = is_member.len();
+    let mut rank = rank;
+    let mut remaining = num_members;
+    for (i, member) in is_member.iter_mut().enumerate() {
+        if remaining == 0 {
+            *member = false;
+            continue;
+        }
+        let num_with_member = num_combinations(n - i - 1, remaining - 1).unwrap();
+        if rank < num_with_member {
+            *member = true;
+            remaining -= 1;
+        } else {
+            *member = false;
+            rank -= num_with_member;
+        }
+    }
+}
+
+/// How the orientations in an orbit (or sub-orbit) are ranked by a [`KPatternRanker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrientationRanking {
+    /// Orientations do not contribute to the rank.
+    Ignore,
+    /// Every position contributes its orientation to the rank.
+    #[default]
+    All,
+    /// Every position except the last contributes its orientation to the rank.
+    /// The last orientation is determined by the sum of orientations in the
+    /// default pattern, which is preserved by the moves of puzzles like the
+    /// 3x3x3 cube.
+    ConstrainedSum,
+}
+
+/// Specifies an orbit (or a subset of its positions) to rank using a [`KPatternRanker`].
+#[derive(Debug, Clone)]
+pub struct KPatternRankerOrbit {
+    pub orbit_name: KPuzzleOrbitName,
+    /// The positions to rank, or `None` for all positions in the orbit. The
+    /// pieces at these positions must be a rearrangement of the pieces at
+    /// the same positions in the default pattern.
+    pub positions: Option<Vec<u8>>,
+    pub rank_permutation: bool,
+    pub orientation: OrientationRanking,
+}
+
+impl KPatternRankerOrbit {
+    /// Ranks both the permutation and orientation of all positions in the orbit.
+    pub fn new(orbit_name: impl Into<KPuzzleOrbitName>) -> Self {
+        Self {
+            orbit_name: orbit_name.into(),
+            positions: None,
+            rank_permutation: true,
+            orientation: OrientationRanking::All,
+        }
+    }
+}
+
+/// An error due to an invalid [`KPatternRanker`] specification, or a pattern that cannot be ranked with it.
+#[derive(Debug)]
+pub struct InvalidKPatternRankerError {
+    pub description: String,
+}
+
+impl From<String> for InvalidKPatternRankerError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for InvalidKPatternRankerError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for InvalidKPatternRankerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[derive(Debug)]
+struct OrbitRanker {
+    orbit_index: usize,
+    positions: Vec<u8>,
+    // From piece value to its index in `index_to_piece`, or `NOT_RANKED`.
+    piece_to_index: [u8; 0x100],
+    index_to_piece: Vec<u8>,
+    // The `orientation_mod` of each piece (by index) in the default pattern.
+    index_to_orientation_mod: Vec<u8>,
+    rank_permutation: bool,
+    orientation: OrientationRanking,
+    orientation_radix: u8,
+    default_orientation_sum: u8,
+    num_permutation_ranks: u64,
+    num_orientation_ranks: u64,
+}
+
+/// Maps patterns of a [`KPuzzle`] to dense integer indices in `0..num_ranks()` and back.
+///
+/// This is useful for pruning tables, counting patterns, and compact storage.
+/// Permutations are ranked lexicographically (see [`permutation_rank`]), and
+/// orientations are ranked as a mixed-radix number using each orbit's
+/// `num_orientations` (or its `orientation_mod`, if all the ranked pieces of
+/// the default pattern share the same one). Ranks for multiple orbits are
+/// combined in the order they are specified.
+///
+/// ```
+/// use cubing_core::kpuzzle::{KPatternRanker, KPatternRankerOrbit, OrientationRanking};
+/// use cubing_core::puzzles::cube3x3x3_kpuzzle;
+///
+/// let kpuzzle = cube3x3x3_kpuzzle();
+/// let ranker = KPatternRanker::try_new(
+///     kpuzzle,
+///     vec![KPatternRankerOrbit {
+///         orientation: OrientationRanking::ConstrainedSum,
+///         ..KPatternRankerOrbit::new("CORNERS")
+///     }],
+/// )
+/// .unwrap();
+/// assert_eq!(ranker.num_ranks(), 40320 * 2187);
+///
+/// let pattern = kpuzzle.default_pattern().apply_alg(&"R U".parse().unwrap()).unwrap();
+/// let rank = ranker.rank(&pattern).unwrap();
+/// // Only corners are ranked, so edges are restored to the default pattern.
+/// let unranked = ranker.unrank(rank).unwrap();
+/// assert_ne!(unranked, pattern);
+/// assert_eq!(ranker.rank(&unranked).unwrap(), rank);
+/// ```
+#[derive(Debug)]
+pub struct KPatternRanker {
+    kpuzzle: KPuzzle,
+    orbit_rankers: Vec<OrbitRanker>,
+    num_ranks: u64,
+}
+
+impl KPatternRanker {
+    pub fn try_new(
+        kpuzzle: impl Into<KPuzzle>,
+        orbits: Vec<KPatternRankerOrbit>,
+    ) -> Result<Self, InvalidKPatternRankerError> {
+        let kpuzzle: KPuzzle = kpuzzle.into();
+        let default_pattern = kpuzzle.default_pattern();
+        let mut orbit_rankers = Vec::with_capacity(orbits.len());
+        let mut num_ranks: u64 = 1;
+        for orbit in orbits {
+            let Some(orbit_index) = kpuzzle
+                .orbit_info_iter()
+                .position(|orbit_info| orbit_info.name == orbit.orbit_name)
+            else {
+                return Err(format!(
+                    "Orbit `{}` does not exist in puzzle `{}`.",
+                    orbit.orbit_name,
+                    kpuzzle.definition().name
+                )
+                .into());
+            };
+            let orbit_info = &kpuzzle.data.ordered_orbit_info[orbit_index];
+            let orbit_ranker =
+                OrbitRanker::try_new(orbit_index, orbit_info, orbit, &default_pattern)?;
+            num_ranks = num_ranks
+                .checked_mul(orbit_ranker.num_permutation_ranks)
+                .and_then(|n| n.checked_mul(orbit_ranker.num_orientation_ranks))
+                .ok_or_else(|| {
+                    InvalidKPatternRankerError::from(format!(
+                        "The number of ranks for puzzle `{}` does not fit in a `u64`.",
+                        kpuzzle.definition().name
+                    ))
+                })?;
+            orbit_rankers.push(orbit_ranker);
+        }
+        Ok(Self {
+            kpuzzle,
+            orbit_rankers,
+            num_ranks,
+        })
+    }
+
+    pub fn kpuzzle(&self) -> &KPuzzle {
+        &self.kpuzzle
+    }
+
+    /// All ranks are in the range `0..num_ranks()`.
+    pub fn num_ranks(&self) -> u64 {
+        self.num_ranks
+    }
+
+    pub fn rank(&self, pattern: &KPattern) -> Result<u64, InvalidKPatternRankerError> {
+        let bytes = unsafe { pattern.byte_slice() };
+        let mut rank: u64 = 0;
+        let mut lehmer_scratch = [0u8; 0x100];
+        for orbit_ranker in &self.orbit_rankers {
+            let orbit_info = &self.kpuzzle.data.ordered_orbit_info[orbit_ranker.orbit_index];
+            let num_positions = orbit_ranker.positions.len();
+            if orbit_ranker.rank_permutation {
+                for (i, &position) in orbit_ranker.positions.iter().enumerate() {
+                    let piece = bytes[orbit_info.pieces_or_permutations_offset + position as usize];
+                    let index = orbit_ranker.piece_to_index[piece as usize];
+                    if index == NOT_RANKED {
+                        return Err(format!(
+                            "Piece {} at position {} in orbit `{}` is not one of the ranked pieces.",
+                            piece, position, orbit_info.name
+                        )
+                        .into());
+                    }
+                    lehmer_scratch[i] = index;
+                }
+                rank = rank * orbit_ranker.num_permutation_ranks
+                    + permutation_rank(&lehmer_scratch[0..num_positions]);
+            }
+            let num_orientation_positions = match orbit_ranker.orientation {
+                OrientationRanking::Ignore => 0,
+                OrientationRanking::All => num_positions,
+                OrientationRanking::ConstrainedSum => num_positions - 1,
+            };
+            let mut orientation_rank: u64 = 0;
+            for &position in &orbit_ranker.positions[0..num_orientation_positions] {
+                let packed = bytes[orbit_info.orientations_offset + position as usize];
+                let orientation = orbit_info.orientation_packer.unpack(&packed).orientation;
+                orientation_rank = orientation_rank * orbit_ranker.orientation_radix as u64
+                    + (orientation % orbit_ranker.orientation_radix) as u64;
+            }
+            rank = rank * orbit_ranker.num_orientation_ranks + orientation_rank;
+        }
+        Ok(rank)
+    }
+
+    /// Constructs a pattern with the given rank. Positions (and
+    /// permutations/orientations) that are not ranked are taken from the
+    /// default pattern.
+    pub fn unrank(&self, rank: u64) -> Result<KPattern, InvalidKPatternRankerError> {
+        if rank >= self.num_ranks {
+            return Err(format!(
+                "Rank {} is out of range (must be less than {}).",
+                rank, self.num_ranks
+            )
+            .into());
+        }
+        let mut pattern = self.kpuzzle.default_pattern();
+        let mut rank = rank;
+        let mut permutation = [0u8; 0x100];
+        for orbit_ranker in self.orbit_rankers.iter().rev() {
+            let orbit_info = &self.kpuzzle.data.ordered_orbit_info[orbit_ranker.orbit_index];
+            let num_positions = orbit_ranker.positions.len();
+
+            let mut orientation_rank = rank % orbit_ranker.num_orientation_ranks;
+            rank /= orbit_ranker.num_orientation_ranks;
+            let permutation_rank = rank % orbit_ranker.num_permutation_ranks;
+            rank /= orbit_ranker.num_permutation_ranks;
+
+            if orbit_ranker.rank_permutation {
+                permutation_unrank(permutation_rank, &mut permutation[0..num_positions]);
+                for (i, &position) in orbit_ranker.positions.iter().enumerate() {
+                    let index = permutation[i];
+                    pattern.set_piece(
+                        orbit_info,
+                        position,
+                        orbit_ranker.index_to_piece[index as usize],
+                    );
+                    // Orientation mods travel with pieces.
+                    let orientation_mod = orbit_ranker.index_to_orientation_mod[index as usize];
+                    pattern.set_orientation_with_mod(
+                        orbit_info,
+                        position,
+                        &OrientationWithMod {
+                            orientation: 0,
+                            orientation_mod,
+                        },
+                    );
+                }
+            }
+
+            let (num_orientation_positions, has_constrained_position) =
+                match orbit_ranker.orientation {
+                    OrientationRanking::Ignore => continue,
+                    OrientationRanking::All => (num_positions, false),
+                    OrientationRanking::ConstrainedSum => (num_positions - 1, true),
+                };
+            let radix = orbit_ranker.orientation_radix;
+            let mut sum: u64 = 0;
+            for &position in orbit_ranker.positions[0..num_orientation_positions]
+                .iter()
+                .rev()
+            {
+                let orientation = (orientation_rank % radix as u64) as u8;
+                orientation_rank /= radix as u64;
+                sum += orientation as u64;
+                self.set_orientation(&mut pattern, orbit_info, position, orientation);
+            }
+            if has_constrained_position {
+                let last_position = orbit_ranker.positions[num_positions - 1];
+                let orientation = ((orbit_ranker.default_orientation_sum as u64 + radix as u64
+                    - sum % radix as u64)
+                    % radix as u64) as u8;
+                self.set_orientation(&mut pattern, orbit_info, last_position, orientation);
+            }
+        }
+        Ok(pattern)
+    }
+
+    fn set_orientation(
+        &self,
+        pattern: &mut KPattern,
+        orbit_info: &KPuzzleOrbitInfo,
+        position: u8,
+        orientation: u8,
+    ) {
+        let orientation_mod = pattern
+            .get_orientation_with_mod(orbit_info, position)
+            .orientation_mod;
+        pattern.set_orientation_with_mod(
+            orbit_info,
+            position,
+            &OrientationWithMod {
+                orientation,
+                orientation_mod,
+            },
+        );
+    }
+}
+
+impl OrbitRanker {
+    fn try_new(
+        orbit_index: usize,
+        orbit_info: &KPuzzleOrbitInfo,
+        orbit: KPatternRankerOrbit,
+        default_pattern: &KPattern,
+    ) -> Result<Self, InvalidKPatternRankerError> {
+        let positions = orbit
+            .positions
+            .unwrap_or_else(|| (0..orbit_info.num_pieces).collect());
+        if positions.is_empty() {
+            return Err(format!("No positions specified for orbit `{}`.", orbit_info.name).into());
+        }
+        let mut seen_positions = [false; 0x100];
+        let mut piece_to_index = [NOT_RANKED; 0x100];
+        let mut index_to_piece = Vec::with_capacity(positions.len());
+        let mut index_to_orientation_mod = Vec::with_capacity(positions.len());
+        let mut orientation_radix: Option<u8> = None;
+        let mut uniform_orientation_mod = true;
+        let mut default_orientation_sum: u64 = 0;
+        for &position in &positions {
+            if position >= orbit_info.num_pieces || seen_positions[position as usize] {
+                return Err(format!(
+                    "Invalid or repeated position {} for orbit `{}`.",
+                    position, orbit_info.name
+                )
+                .into());
+            }
+            seen_positions[position as usize] = true;
+            let piece = default_pattern.get_piece(orbit_info, position);
+            if orbit.rank_permutation && piece_to_index[piece as usize] != NOT_RANKED {
+                return Err(format!(
+                    "The default pattern has identical pieces ({}) in orbit `{}`, so its permutation cannot be ranked.",
+                    piece, orbit_info.name
+                )
+                .into());
+            }
+            piece_to_index[piece as usize] = index_to_piece.len() as u8;
+            index_to_piece.push(piece);
+
+            let orientation_with_mod =
+                default_pattern.get_orientation_with_mod(orbit_info, position);
+            index_to_orientation_mod.push(orientation_with_mod.orientation_mod);
+            default_orientation_sum += orientation_with_mod.orientation as u64;
+            let effective_mod = match orientation_with_mod.orientation_mod {
+                0 => orbit_info.num_orientations,
+                orientation_mod => orientation_mod,
+            };
+            match orientation_radix {
+                None => orientation_radix = Some(effective_mod),
+                Some(radix) => uniform_orientation_mod &= radix == effective_mod,
+            }
+        }
+        let orientation_radix = if uniform_orientation_mod {
+            orientation_radix.unwrap()
+        } else {
+            orbit_info.num_orientations
+        };
+
+        let num_permutation_ranks = if orbit.rank_permutation {
+            num_permutations(positions.len()).ok_or_else(|| {
+                InvalidKPatternRankerError::from(format!(
+                    "Too many permutations to rank for orbit `{}`.",
+                    orbit_info.name
+                ))
+            })?
+        } else {
+            1
+        };
+        let num_orientation_positions = match orbit.orientation {
+            OrientationRanking::Ignore => 0,
+            OrientationRanking::All => positions.len(),
+            OrientationRanking::ConstrainedSum => positions.len() - 1,
+        };
+        let num_orientation_ranks = (orientation_radix as u64)
+            .checked_pow(num_orientation_positions as u32)
+            .ok_or_else(|| {
+                InvalidKPatternRankerError::from(format!(
+                    "Too many orientations to rank for orbit `{}`.",
+                    orbit_info.name
+                ))
+            })?;
+
+        Ok(Self {
+            orbit_index,
+            positions,
+            piece_to_index,
+            index_to_piece,
+            index_to_orientation_mod,
+            rank_permutation: orbit.rank_permutation,
+            orientation: orbit.orientation,
+            orientation_radix,
+            default_orientation_sum: (default_orientation_sum % orientation_radix as u64) as u8,
+            num_permutation_ranks,
+            num_orientation_ranks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        combination_rank, combination_unrank, num_combinations, num_permutations,
+        permutation_parity, permutation_rank, permutation_unrank, KPatternRanker,
+        KPatternRankerOrbit, OrientationRanking,
+    };
+    use crate::puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle};
+
+    #[test]
+    fn permutation_ranks_round_trip() {
+        let mut seen = [false; 120];
+        let mut permutation = [0u8; 5];
+        for rank in 0..num_permutations(5).unwrap() {
+            permutation_unrank(rank, &mut permutation);
+            assert_eq!(permutation_rank(&permutation), rank);
+            seen[rank as usize] = true;
+        }
+        assert!(seen.iter().all(|&b| b));
+        assert_eq!(permutation_rank(&[0, 1, 2, 3]), 0);
+        assert_eq!(permutation_rank(&[3, 2, 1, 0]), 23);
+        assert!(!permutation_parity(&[0, 1, 2, 3]));
+        assert!(permutation_parity(&[1, 0, 2, 3]));
+        assert!(!permutation_parity(&[1, 2, 0, 3]));
+    }
+
+    #[test]
+    fn combination_ranks_round_trip() {
+        assert_eq!(num_combinations(12, 4), Some(495));
+        let mut is_member = [false; 12];
+        for rank in 0..495 {
+            combination_unrank(rank, 4, &mut is_member);
+            assert_eq!(is_member.iter().filter(|&&b| b).count(), 4);
+            assert_eq!(combination_rank(&is_member), rank);
+        }
+    }
+
+    #[test]
+    fn ranks_2x2x2_with_fixed_corner() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let ranker = KPatternRanker::try_new(
+            kpuzzle,
+            vec![KPatternRankerOrbit {
+                positions: Some(vec![0, 1, 2, 3, 4, 5, 7]),
+                orientation: OrientationRanking::ConstrainedSum,
+                ..KPatternRankerOrbit::new("CORNERS")
+            }],
+        )
+        .unwrap();
+        assert_eq!(ranker.num_ranks(), 3674160);
+        assert_eq!(ranker.rank(&kpuzzle.default_pattern()).unwrap(), 0);
+
+        let pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&"R U' F2 R' U".parse().unwrap())
+            .unwrap();
+        let rank = ranker.rank(&pattern).unwrap();
+        assert_ne!(rank, 0);
+        assert_eq!(ranker.unrank(rank).unwrap(), pattern);
+        assert!(ranker.unrank(ranker.num_ranks()).is_err());
+    }
+
+    #[test]
+    fn ranks_multiple_orbits() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let ranker = KPatternRanker::try_new(
+            kpuzzle,
+            vec![
+                KPatternRankerOrbit {
+                    rank_permutation: false,
+                    orientation: OrientationRanking::ConstrainedSum,
+                    ..KPatternRankerOrbit::new("EDGES")
+                },
+                KPatternRankerOrbit {
+                    rank_permutation: false,
+                    orientation: OrientationRanking::ConstrainedSum,
+                    ..KPatternRankerOrbit::new("CORNERS")
+                },
+                // Centers have an `orientation_mod` of 1, so they only contribute their permutation.
+                KPatternRankerOrbit::new("CENTERS"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(ranker.num_ranks(), 2048 * 2187 * 720);
+
+        let pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&"F R U x".parse().unwrap())
+            .unwrap();
+        let rank = ranker.rank(&pattern).unwrap();
+        let unranked = ranker.unrank(rank).unwrap();
+        assert_eq!(ranker.rank(&unranked).unwrap(), rank);
+
+        assert!(KPatternRanker::try_new(kpuzzle, vec![KPatternRankerOrbit::new("WINGS")]).is_err());
+        let full = KPatternRanker::try_new(
+            kpuzzle,
+            vec![
+                KPatternRankerOrbit::new("EDGES"),
+                KPatternRankerOrbit::new("CORNERS"),
+                KPatternRankerOrbit::new("CENTERS"),
+            ],
+        );
+        assert!(full.is_err());
+    }
+}
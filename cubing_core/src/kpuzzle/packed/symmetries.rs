@@ -0,0 +1,644 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+};
+
+use crate::alg::Move;
+
+use super::{KPattern, KPuzzle, KPuzzleOrbitInfo, KTransformation, OrientationWithMod};
+
+// Guards against generators that aren't actually rotations (e.g. `R`), which
+// would otherwise try to enumerate the entire puzzle group.
+const MAX_NUM_SYMMETRIES: usize = 1000;
+
+/// An error due to moves that do not describe symmetries of a puzzle.
+#[derive(Debug)]
+pub struct SymmetryError {
+    pub description: String,
+}
+
+impl From<String> for SymmetryError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for SymmetryError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for SymmetryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SymmetryOrbit {
+    // Like the permutation of a `KTransformation`: the position that each position takes its piece from.
+    source: Vec<u8>,
+    // Like the orientation delta of a `KTransformation`, added after negating the orientation for mirrored symmetries.
+    offset: Vec<u8>,
+}
+
+/// A symmetry acts on an orbit as `i ↦ (pieces[source[i]], offset[i] ± orientation[source[i]])`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Symmetry {
+    orbits: Vec<SymmetryOrbit>,
+    mirrored: bool,
+}
+
+impl Symmetry {
+    fn identity(kpuzzle: &KPuzzle) -> Self {
+        Self {
+            orbits: kpuzzle
+                .orbit_info_iter()
+                .map(|orbit| SymmetryOrbit {
+                    source: (0..orbit.num_pieces).collect(),
+                    offset: vec![0; orbit.num_pieces as usize],
+                })
+                .collect(),
+            mirrored: false,
+        }
+    }
+
+    fn from_transformation(transformation: &KTransformation) -> Self {
+        Self {
+            orbits: transformation
+                .kpuzzle()
+                .orbit_info_iter()
+                .map(|orbit| SymmetryOrbit {
+                    source: (0..orbit.num_pieces)
+                        .map(|i| transformation.get_permutation_idx(orbit, i))
+                        .collect(),
+                    offset: (0..orbit.num_pieces)
+                        .map(|i| transformation.get_orientation_delta(orbit, i))
+                        .collect(),
+                })
+                .collect(),
+            mirrored: false,
+        }
+    }
+
+    // Returns the symmetry that applies `self` followed by `other`.
+    fn then(&self, other: &Symmetry, kpuzzle: &KPuzzle) -> Symmetry {
+        let orbits = kpuzzle
+            .orbit_info_iter()
+            .zip(self.orbits.iter().zip(&other.orbits))
+            .map(|(orbit, (first, second))| {
+                let n = orbit.num_orientations as i32;
+                SymmetryOrbit {
+                    source: second
+                        .source
+                        .iter()
+                        .map(|&i| first.source[i as usize])
+                        .collect(),
+                    offset: second
+                        .source
+                        .iter()
+                        .zip(&second.offset)
+                        .map(|(&i, &offset)| {
+                            let first_offset = first.offset[i as usize] as i32;
+                            let first_offset = if other.mirrored {
+                                -first_offset
+                            } else {
+                                first_offset
+                            };
+                            (offset as i32 + first_offset).rem_euclid(n) as u8
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        Symmetry {
+            orbits,
+            mirrored: self.mirrored != other.mirrored,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DefaultOrbit {
+    pieces: Vec<u8>,
+    orientations: Vec<OrientationWithMod>,
+    // The first position of each piece value.
+    piece_positions: HashMap<u8, u8>,
+}
+
+/// A group of symmetries of a [`KPuzzle`] (whole-puzzle rotations, optionally
+/// combined with a mirror), for comparing [`KPattern`]s up to symmetry.
+///
+/// A symmetry `S` acts on a pattern by conjugation: the pattern reached by a
+/// transformation `T` from the default pattern is mapped to the pattern
+/// reached by `S⁻¹ T S`. For example, conjugating the pattern for `R` by the
+/// rotation `y` gives the pattern for `F`. Unlike applying a rotation to a
+/// pattern, this keeps the pieces that define the reference frame of the
+/// puzzle (like the centers of a 3x3x3) in place.
+///
+/// ```
+/// use cubing_core::{
+///     alg::{Alg, Move},
+///     kpuzzle::{KPattern, KPuzzleSymmetries},
+///     puzzles::cube3x3x3_kpuzzle,
+/// };
+///
+/// let kpuzzle = cube3x3x3_kpuzzle();
+/// let pattern = |alg: &str| -> KPattern {
+///     kpuzzle
+///         .default_pattern()
+///         .apply_alg(&alg.parse::<Alg>().unwrap())
+///         .unwrap()
+/// };
+///
+/// let symmetries = KPuzzleSymmetries::new_rotations(kpuzzle).unwrap();
+/// assert_eq!(symmetries.num_symmetries(), 24);
+/// assert!(symmetries.are_symmetric(&pattern("R U R' U'"), &pattern("F R F' R'")));
+/// assert!(!symmetries.are_symmetric(&pattern("R U R' U'"), &pattern("L' U' L U")));
+///
+/// let move_pair = |move1: &str, move2: &str| -> (Move, Move) {
+///     (move1.parse().unwrap(), move2.parse().unwrap())
+/// };
+/// let symmetries = symmetries
+///     .with_mirror(&[
+///         move_pair("R", "L'"),
+///         move_pair("L", "R'"),
+///         move_pair("U", "U'"),
+///         move_pair("F", "F'"),
+///     ])
+///     .unwrap();
+/// assert_eq!(symmetries.num_symmetries(), 48);
+/// assert!(symmetries.are_symmetric(&pattern("R U R' U'"), &pattern("L' U' L U")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KPuzzleSymmetries {
+    kpuzzle: KPuzzle,
+    // Indexed in the same order as `KPuzzle::orbit_info_iter()`.
+    default_orbits: Vec<DefaultOrbit>,
+    generators: Vec<Symmetry>,
+    symmetries: Vec<Symmetry>,
+}
+
+fn transformation_from_move(kpuzzle: &KPuzzle, m: &Move) -> Result<KTransformation, SymmetryError> {
+    kpuzzle
+        .transformation_from_move(m)
+        .map_err(|e| format!("Invalid move `{}`: {}", m, e).into())
+}
+
+impl KPuzzleSymmetries {
+    /// Uses the rotations `x`, `y`, and `z` (whichever ones the puzzle defines) to generate the rotation group.
+    pub fn new_rotations<T: Into<KPuzzle>>(kpuzzle: T) -> Result<Self, SymmetryError> {
+        let kpuzzle: KPuzzle = kpuzzle.into();
+        let rotations: Vec<Move> = ["x", "y", "z"]
+            .into_iter()
+            .map(|move_str| move_str.parse::<Move>().expect("Invalid rotation move"))
+            .filter(|m| kpuzzle.transformation_from_move(m).is_ok())
+            .collect();
+        if rotations.is_empty() {
+            return Err(format!(
+                "Puzzle `{}` does not define any of the rotations `x`, `y`, or `z`.",
+                kpuzzle.definition().name
+            )
+            .into());
+        }
+        Self::from_rotation_moves(kpuzzle, &rotations)
+    }
+
+    /// Generates a group of symmetries from the given whole-puzzle rotations (e.g. `Rv` and `Uv` for Megaminx).
+    pub fn from_rotation_moves<T: Into<KPuzzle>>(
+        kpuzzle: T,
+        rotations: &[Move],
+    ) -> Result<Self, SymmetryError> {
+        let kpuzzle: KPuzzle = kpuzzle.into();
+        let default_pattern = kpuzzle.default_pattern();
+        let default_orbits = kpuzzle
+            .orbit_info_iter()
+            .map(|orbit| {
+                let pieces: Vec<u8> = (0..orbit.num_pieces)
+                    .map(|i| default_pattern.get_piece(orbit, i))
+                    .collect();
+                let mut piece_positions = HashMap::new();
+                for (i, piece) in pieces.iter().enumerate().rev() {
+                    piece_positions.insert(*piece, i as u8);
+                }
+                DefaultOrbit {
+                    pieces,
+                    orientations: (0..orbit.num_pieces)
+                        .map(|i| *default_pattern.get_orientation_with_mod(orbit, i))
+                        .collect(),
+                    piece_positions,
+                }
+            })
+            .collect();
+        let mut symmetries = Self {
+            kpuzzle,
+            default_orbits,
+            generators: vec![],
+            symmetries: vec![],
+        };
+        for rotation in rotations {
+            let transformation = transformation_from_move(&symmetries.kpuzzle, rotation)?;
+            symmetries.add_generator(Symmetry::from_transformation(&transformation))?;
+        }
+        symmetries.generate()?;
+        Ok(symmetries)
+    }
+
+    /// Adds a mirror symmetry, specified by pairs of moves that are mirror images of each other.
+    ///
+    /// For example, mirroring a 3x3x3 across the `M` slice can be specified
+    /// using `R`/`L'`, `L`/`R'`, `U`/`U'`, and `F`/`F'`. The pairs need to
+    /// include enough moves to move every piece of the puzzle.
+    pub fn with_mirror(mut self, move_pairs: &[(Move, Move)]) -> Result<Self, SymmetryError> {
+        let mut transformation_pairs = vec![];
+        for (move1, move2) in move_pairs {
+            transformation_pairs.push((
+                transformation_from_move(&self.kpuzzle, move1)?,
+                transformation_from_move(&self.kpuzzle, move2)?,
+            ));
+        }
+        let orbits = self
+            .kpuzzle
+            .orbit_info_iter()
+            .map(|orbit| find_mirror_orbit(orbit, &transformation_pairs))
+            .collect::<Result<Vec<SymmetryOrbit>, SymmetryError>>()?;
+        self.add_generator(Symmetry {
+            orbits,
+            mirrored: true,
+        })?;
+        self.generate()?;
+        Ok(self)
+    }
+
+    pub fn kpuzzle(&self) -> &KPuzzle {
+        &self.kpuzzle
+    }
+
+    /// The size of the symmetry group (including the identity).
+    pub fn num_symmetries(&self) -> usize {
+        self.symmetries.len()
+    }
+
+    /// Returns every rotation in the group (i.e. every symmetry that is not mirrored), starting with the identity.
+    pub fn rotation_transformations(&self) -> Vec<KTransformation> {
+        self.symmetries
+            .iter()
+            .filter(|symmetry| !symmetry.mirrored)
+            .map(|symmetry| {
+                let mut transformation = self.kpuzzle.identity_transformation();
+                for (orbit, symmetry_orbit) in self.kpuzzle.orbit_info_iter().zip(&symmetry.orbits)
+                {
+                    for i in 0..orbit.num_pieces {
+                        transformation.set_permutation_idx(
+                            orbit,
+                            i,
+                            symmetry_orbit.source[i as usize],
+                        );
+                        transformation.set_orientation_delta(
+                            orbit,
+                            i,
+                            symmetry_orbit.offset[i as usize],
+                        );
+                    }
+                }
+                transformation
+            })
+            .collect()
+    }
+
+    /// Returns the pattern conjugated by each symmetry, in the same order for
+    /// every pattern (starting with the pattern itself). This may contain
+    /// duplicates if the pattern is itself symmetric.
+    pub fn symmetric_patterns(&self, pattern: &KPattern) -> Vec<KPattern> {
+        self.symmetries
+            .iter()
+            .map(|symmetry| self.conjugate(pattern, symmetry))
+            .collect()
+    }
+
+    /// Returns a canonical representative of the symmetry class of the pattern.
+    ///
+    /// Two patterns have the same canonical representative exactly when they are symmetric.
+    pub fn canonical_pattern(&self, pattern: &KPattern) -> KPattern {
+        self.symmetric_patterns(pattern)
+            .into_iter()
+            .min_by(|pattern1, pattern2| unsafe {
+                pattern1
+                    .packed_orbit_data
+                    .byte_slice()
+                    .cmp(pattern2.packed_orbit_data.byte_slice())
+            })
+            .expect("A symmetry group always contains the identity.")
+    }
+
+    /// Returns whether some symmetry maps `pattern1` to `pattern2`.
+    pub fn are_symmetric(&self, pattern1: &KPattern, pattern2: &KPattern) -> bool {
+        self.symmetries
+            .iter()
+            .any(|symmetry| &self.conjugate(pattern1, symmetry) == pattern2)
+    }
+
+    fn add_generator(&mut self, generator: Symmetry) -> Result<(), SymmetryError> {
+        // Pieces with the same value (e.g. the centers of a 4x4x4) must be
+        // mapped to positions with the same value, so that conjugating a
+        // pattern does not depend on which one of them we pick.
+        for ((orbit, default_orbit), symmetry_orbit) in self
+            .kpuzzle
+            .orbit_info_iter()
+            .zip(&self.default_orbits)
+            .zip(&generator.orbits)
+        {
+            let mut piece_mapping = HashMap::<u8, u8>::new();
+            for (i, &source) in symmetry_orbit.source.iter().enumerate() {
+                let piece = default_orbit.pieces[source as usize];
+                let mapped_piece = default_orbit.pieces[i];
+                if *piece_mapping.entry(piece).or_insert(mapped_piece) != mapped_piece {
+                    return Err(format!(
+                        "Symmetry does not map identical pieces consistently in orbit: {}",
+                        orbit.name
+                    )
+                    .into());
+                }
+            }
+        }
+        self.generators.push(generator);
+        Ok(())
+    }
+
+    fn generate(&mut self) -> Result<(), SymmetryError> {
+        let identity = Symmetry::identity(&self.kpuzzle);
+        let mut seen = HashSet::from([identity.clone()]);
+        let mut queue = VecDeque::from([identity]);
+        let mut symmetries = vec![];
+        while let Some(symmetry) = queue.pop_front() {
+            for generator in &self.generators {
+                let next = symmetry.then(generator, &self.kpuzzle);
+                if seen.insert(next.clone()) {
+                    if seen.len() > MAX_NUM_SYMMETRIES {
+                        return Err(format!(
+                            "The symmetry group has more than {} elements (are all the moves whole-puzzle rotations?)",
+                            MAX_NUM_SYMMETRIES
+                        )
+                        .into());
+                    }
+                    queue.push_back(next);
+                }
+            }
+            symmetries.push(symmetry);
+        }
+        self.symmetries = symmetries;
+        Ok(())
+    }
+
+    fn conjugate(&self, pattern: &KPattern, symmetry: &Symmetry) -> KPattern {
+        let mut conjugated = pattern.clone();
+        for ((orbit, default_orbit), symmetry_orbit) in self
+            .kpuzzle
+            .orbit_info_iter()
+            .zip(&self.default_orbits)
+            .zip(&symmetry.orbits)
+        {
+            let mut target = vec![0; orbit.num_pieces as usize];
+            for (i, &source) in symmetry_orbit.source.iter().enumerate() {
+                target[source as usize] = i;
+            }
+            for i in 0..orbit.num_pieces {
+                let source = symmetry_orbit.source[i as usize];
+                let piece = pattern.get_piece(orbit, source);
+                let orientation_with_mod = pattern.get_orientation_with_mod(orbit, source);
+                // Pieces that are not in the default pattern (e.g. masked pieces) are left as-is.
+                let Some(&piece_position) = default_orbit.piece_positions.get(&piece) else {
+                    conjugated.set_piece(orbit, i, piece);
+                    conjugated.set_orientation_with_mod(orbit, i, orientation_with_mod);
+                    continue;
+                };
+                // The position that the symmetry moves this piece's default position to.
+                let mapped_position = target[piece_position as usize];
+                let default_orientation = &default_orbit.orientations[mapped_position];
+                let orientation_mod = match default_orientation.orientation_mod {
+                    0 => orbit.num_orientations,
+                    orientation_mod => orientation_mod,
+                } as i32;
+                let twist = orientation_with_mod.orientation as i32
+                    - default_orbit.orientations[piece_position as usize].orientation as i32;
+                let twist = if symmetry.mirrored { -twist } else { twist };
+                let orientation = symmetry_orbit.offset[i as usize] as i32
+                    - symmetry_orbit.offset[mapped_position] as i32
+                    + default_orientation.orientation as i32
+                    + twist;
+                conjugated.set_piece(orbit, i, default_orbit.pieces[mapped_position]);
+                conjugated.set_orientation_with_mod(
+                    orbit,
+                    i,
+                    &OrientationWithMod {
+                        orientation: orientation.rem_euclid(orientation_mod) as u8,
+                        orientation_mod: default_orientation.orientation_mod,
+                    },
+                );
+            }
+        }
+        conjugated
+    }
+}
+
+// Finds a mirrored symmetry `F` of the orbit such that applying `g` followed
+// by `F` is the same as applying `F` followed by `h` for every pair `(g, h)`.
+fn find_mirror_orbit(
+    orbit: &KPuzzleOrbitInfo,
+    transformation_pairs: &[(KTransformation, KTransformation)],
+) -> Result<SymmetryOrbit, SymmetryError> {
+    let num_pieces = orbit.num_pieces as usize;
+    let n = orbit.num_orientations as i32;
+    let mut source: Vec<Option<u8>> = vec![None; num_pieces];
+    let mut offset = vec![0; num_pieces];
+    for start in 0..num_pieces {
+        if source[start].is_some() {
+            continue;
+        }
+        let mut found = false;
+        'candidates: for start_source in 0..orbit.num_pieces {
+            if source.contains(&Some(start_source)) {
+                continue;
+            }
+            for start_offset in 0..orbit.num_orientations {
+                let mut candidate_source = source.clone();
+                let mut candidate_offset = offset.clone();
+                candidate_source[start] = Some(start_source);
+                candidate_offset[start] = start_offset;
+                let mut queue = VecDeque::from([start as u8]);
+                let mut consistent = true;
+                'propagation: while let Some(i) = queue.pop_front() {
+                    let i_source = candidate_source[i as usize].expect("Missing source");
+                    for (g, h) in transformation_pairs {
+                        let next = h.get_permutation_idx(orbit, i);
+                        let next_source = g.get_permutation_idx(orbit, i_source);
+                        let next_offset = (candidate_offset[i as usize] as i32
+                            - g.get_orientation_delta(orbit, i_source) as i32
+                            - h.get_orientation_delta(orbit, i) as i32)
+                            .rem_euclid(n) as u8;
+                        match candidate_source[next as usize] {
+                            Some(existing) => {
+                                if existing != next_source
+                                    || candidate_offset[next as usize] != next_offset
+                                {
+                                    consistent = false;
+                                    break 'propagation;
+                                }
+                            }
+                            None => {
+                                if candidate_source.contains(&Some(next_source)) {
+                                    consistent = false;
+                                    break 'propagation;
+                                }
+                                candidate_source[next as usize] = Some(next_source);
+                                candidate_offset[next as usize] = next_offset;
+                                queue.push_back(next);
+                            }
+                        }
+                    }
+                }
+                if consistent {
+                    source = candidate_source;
+                    offset = candidate_offset;
+                    found = true;
+                    break 'candidates;
+                }
+            }
+        }
+        if !found {
+            return Err(
+                format!("Could not find a mirror symmetry for orbit: {}", orbit.name).into(),
+            );
+        }
+    }
+    Ok(SymmetryOrbit {
+        source: source
+            .into_iter()
+            .map(|i| i.expect("Missing source"))
+            .collect(),
+        offset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alg::{Alg, Move},
+        kpuzzle::{KPattern, KPuzzle},
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle, cube4x4x4_kpuzzle, square1_kpuzzle},
+    };
+
+    use super::KPuzzleSymmetries;
+
+    fn pattern(kpuzzle: &KPuzzle, alg: &str) -> KPattern {
+        kpuzzle
+            .default_pattern()
+            .apply_alg(&alg.parse::<Alg>().unwrap())
+            .unwrap()
+    }
+
+    fn moves(move_strs: &[&str]) -> Vec<Move> {
+        move_strs.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn conjugates_by_rotations() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let symmetries = KPuzzleSymmetries::from_rotation_moves(kpuzzle, &moves(&["y"])).unwrap();
+        assert_eq!(symmetries.num_symmetries(), 4);
+        let symmetric_patterns = symmetries.symmetric_patterns(&pattern(kpuzzle, "R U"));
+        assert_eq!(symmetric_patterns[0], pattern(kpuzzle, "R U"));
+        for alg in ["F U", "L U", "B U"] {
+            assert!(symmetric_patterns.contains(&pattern(kpuzzle, alg)));
+        }
+        assert!(!symmetries.are_symmetric(&pattern(kpuzzle, "R"), &pattern(kpuzzle, "U")));
+
+        let rotations = KPuzzleSymmetries::new_rotations(kpuzzle)
+            .unwrap()
+            .rotation_transformations();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], kpuzzle.identity_transformation());
+        let y = kpuzzle
+            .transformation_from_move(&"y".parse().unwrap())
+            .unwrap();
+        assert!(rotations.contains(&y));
+    }
+
+    #[test]
+    fn canonical_patterns() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let symmetries = KPuzzleSymmetries::new_rotations(kpuzzle).unwrap();
+        let canonical = symmetries.canonical_pattern(&pattern(kpuzzle, "R U R' U'"));
+        for alg in ["R U R' U'", "U F U' F'", "F R F' R'", "D B D' B'"] {
+            assert_eq!(
+                symmetries.canonical_pattern(&pattern(kpuzzle, alg)),
+                canonical
+            );
+        }
+        assert_ne!(
+            symmetries.canonical_pattern(&pattern(kpuzzle, "R U' R' U")),
+            canonical
+        );
+        assert_eq!(
+            symmetries.canonical_pattern(&kpuzzle.default_pattern()),
+            kpuzzle.default_pattern()
+        );
+    }
+
+    #[test]
+    fn mirror() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let rotations = KPuzzleSymmetries::new_rotations(kpuzzle).unwrap();
+        let symmetries = rotations
+            .clone()
+            .with_mirror(&[
+                ("R".parse().unwrap(), "L'".parse().unwrap()),
+                ("U".parse().unwrap(), "U'".parse().unwrap()),
+                ("F".parse().unwrap(), "F'".parse().unwrap()),
+            ])
+            .unwrap();
+        assert_eq!(symmetries.num_symmetries(), 48);
+        assert_eq!(symmetries.rotation_transformations().len(), 24);
+        let sune = pattern(kpuzzle, "R U R' U R U2' R'");
+        let left_sune = pattern(kpuzzle, "L' U' L U' L' U2 L");
+        let antisune = pattern(kpuzzle, "R U2 R' U' R U' R'");
+        // A left Sune solves an Antisune.
+        assert!(rotations.are_symmetric(&left_sune, &antisune));
+        assert!(!rotations.are_symmetric(&sune, &left_sune));
+        assert!(symmetries.are_symmetric(&sune, &left_sune));
+        assert!(!symmetries.are_symmetric(&sune, &pattern(kpuzzle, "R U R'")));
+        assert_eq!(
+            symmetries.canonical_pattern(&sune),
+            symmetries.canonical_pattern(&left_sune)
+        );
+
+        assert!(KPuzzleSymmetries::new_rotations(kpuzzle)
+            .unwrap()
+            .with_mirror(&[("R".parse().unwrap(), "U".parse().unwrap())])
+            .is_err());
+    }
+
+    #[test]
+    fn identical_pieces() {
+        let kpuzzle = cube4x4x4_kpuzzle();
+        let symmetries = KPuzzleSymmetries::new_rotations(kpuzzle).unwrap();
+        assert_eq!(symmetries.num_symmetries(), 24);
+        assert!(symmetries.are_symmetric(&pattern(kpuzzle, "r U"), &pattern(kpuzzle, "f U")));
+        assert!(!symmetries.are_symmetric(&pattern(kpuzzle, "r U"), &pattern(kpuzzle, "R U")));
+    }
+
+    #[test]
+    fn invalid_symmetries() {
+        assert!(KPuzzleSymmetries::new_rotations(square1_kpuzzle()).is_err());
+        assert!(
+            KPuzzleSymmetries::from_rotation_moves(cube3x3x3_kpuzzle(), &moves(&["R", "U"]))
+                .is_err()
+        );
+        assert!(
+            KPuzzleSymmetries::from_rotation_moves(cube3x3x3_kpuzzle(), &moves(&["Q"])).is_err()
+        );
+    }
+}
@@ -4,3 +4,8 @@ pub mod alg;
 pub mod experimental_twizzle_link;
 pub mod kpuzzle;
 pub mod puzzles;
+pub mod reconstruction;
+pub mod scramble;
+pub mod search;
+pub mod smart_cube;
+pub mod twizzle_link;
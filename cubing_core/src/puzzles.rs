@@ -1,2 +1,40 @@
 mod lib;
 pub use lib::*;
+
+mod cube_nxnxn;
+pub use cube_nxnxn::{cube_nxnxn_kpuzzle, cube_nxnxn_kpuzzle_definition, MAX_NXNXN_SIZE};
+
+mod registry;
+pub use registry::{
+    available_puzzles, kpuzzle_by_id, register_kpuzzle, PuzzleInfo, PuzzleRegistryError,
+};
+
+mod puzzle_geometry;
+pub use puzzle_geometry::{puzzle_geometry_kpuzzle, puzzle_geometry_kpuzzle_definition};
+
+mod stickering;
+pub use stickering::{available_stickerings, stickering_mask};
+
+mod facelets;
+pub use facelets::{
+    cube2x2x2_pattern_from_facelets, cube2x2x2_pattern_to_facelets,
+    cube3x3x3_pattern_from_facelets, cube3x3x3_pattern_to_facelets, InvalidFaceletsError,
+};
+
+mod blind_tracing;
+pub use blind_tracing::{
+    trace_blind_memo, BlindMemo, BlindTarget, BlindTracingError, BlindTracingOptions, LetterScheme,
+    MisorientedPiece, OrbitMemo,
+};
+
+mod cfop;
+pub use cfop::{
+    segment_cfop_solve, CfopSegment, CfopSegmentation, CfopSegmentationError, CfopStep,
+    CfopStepDetector, CubeFace,
+};
+
+mod net;
+pub use net::{render_net, NetStyle};
+
+mod svg;
+pub use svg::{render_net_svg, render_top_view_svg, TopViewOptions};
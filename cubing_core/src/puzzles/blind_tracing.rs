@@ -0,0 +1,466 @@
+use std::fmt::Display;
+
+use crate::kpuzzle::{permutation_parity, KPattern, KPuzzleOrbitInfo};
+
+const SPEFFZ_LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWX";
+
+// Letter schemes list the stickers face by face (in the order `ULFRBD`),
+// going clockwise around each face starting from the top left (with `B`
+// above `U`, `U` above `L F R B`, and `F` above `D`). This lists the
+// `(position, sticker)` of each of these stickers for the Reid order pieces:
+//
+// - Corners: UFR URB UBL ULF . DRF DFL DLB DBR
+// - Edges: UF UR UB UL . DF DR DB DL . FR FL BR BL
+const CORNER_STICKERS: [(u8, u8); 24] = [
+    (2, 0),
+    (1, 0),
+    (0, 0),
+    (3, 0),
+    (2, 2),
+    (3, 1),
+    (5, 2),
+    (6, 1),
+    (3, 2),
+    (0, 1),
+    (4, 2),
+    (5, 1),
+    (0, 2),
+    (1, 1),
+    (7, 2),
+    (4, 1),
+    (1, 2),
+    (2, 1),
+    (6, 2),
+    (7, 1),
+    (5, 0),
+    (4, 0),
+    (7, 0),
+    (6, 0),
+];
+const EDGE_STICKERS: [(u8, u8); 24] = [
+    (2, 0),
+    (1, 0),
+    (0, 0),
+    (3, 0),
+    (3, 1),
+    (9, 1),
+    (7, 1),
+    (11, 1),
+    (0, 1),
+    (8, 0),
+    (4, 1),
+    (9, 0),
+    (1, 1),
+    (10, 1),
+    (5, 1),
+    (8, 1),
+    (2, 1),
+    (11, 0),
+    (6, 1),
+    (10, 0),
+    (4, 0),
+    (5, 0),
+    (6, 0),
+    (7, 0),
+];
+
+/// An error due to an invalid pattern, buffer, or letter scheme for blindfolded tracing.
+#[derive(Debug)]
+pub struct BlindTracingError {
+    pub description: String,
+}
+
+impl From<String> for BlindTracingError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for BlindTracingError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for BlindTracingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+/// Assigns a letter to each sticker of the 3x3x3 corners and edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterScheme {
+    // Indexed by `[position][sticker]` in Reid order.
+    corners: [[char; 3]; 8],
+    edges: [[char; 2]; 12],
+}
+
+impl LetterScheme {
+    /// Creates a letter scheme from 24 corner letters and 24 edge letters.
+    ///
+    /// Each string lists the letters of the stickers face by face in the order
+    /// `ULFRBD`, going clockwise around each face from the top left (with `B`
+    /// above `U`, `U` above the side faces, and `F` above `D`). This is the
+    /// same order as the alphabet in the Speffz scheme.
+    pub fn new(corner_letters: &str, edge_letters: &str) -> Result<Self, BlindTracingError> {
+        let corner_letters: Vec<char> = corner_letters.chars().collect();
+        let edge_letters: Vec<char> = edge_letters.chars().collect();
+        if corner_letters.len() != 24 || edge_letters.len() != 24 {
+            return Err(
+                "Letter schemes must have exactly 24 corner letters and 24 edge letters.".into(),
+            );
+        }
+        let mut letter_scheme = Self {
+            corners: [[' '; 3]; 8],
+            edges: [[' '; 2]; 12],
+        };
+        for ((position, sticker), letter) in CORNER_STICKERS.iter().zip(corner_letters) {
+            letter_scheme.corners[*position as usize][*sticker as usize] = letter;
+        }
+        for ((position, sticker), letter) in EDGE_STICKERS.iter().zip(edge_letters) {
+            letter_scheme.edges[*position as usize][*sticker as usize] = letter;
+        }
+        Ok(letter_scheme)
+    }
+
+    /// The Speffz scheme, which uses the letters `A` to `X` for both corners and edges.
+    pub fn speffz() -> Self {
+        Self::new(SPEFFZ_LETTERS, SPEFFZ_LETTERS).expect("Invalid Speffz letter scheme")
+    }
+
+    /// Returns the letter of a corner sticker, by Reid order position and sticker index.
+    pub fn corner_letter(&self, position: u8, sticker: u8) -> char {
+        self.corners[position as usize][sticker as usize]
+    }
+
+    /// Returns the letter of an edge sticker, by Reid order position and sticker index.
+    pub fn edge_letter(&self, position: u8, sticker: u8) -> char {
+        self.edges[position as usize][sticker as usize]
+    }
+}
+
+impl Default for LetterScheme {
+    fn default() -> Self {
+        Self::speffz()
+    }
+}
+
+/// Options for [`trace_blind_memo`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlindTracingOptions {
+    pub letter_scheme: LetterScheme,
+    /// The Reid order position of the corner buffer (defaults to `UFR`, i.e. `0`).
+    pub corner_buffer: u8,
+    /// The Reid order position of the edge buffer (defaults to `UF`, i.e. `0`).
+    pub edge_buffer: u8,
+}
+
+/// A sticker to shoot the buffer piece to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlindTarget {
+    pub letter: char,
+    /// Whether this target starts a new cycle (because the buffer piece was already solved).
+    pub cycle_break: bool,
+}
+
+/// A non-buffer piece that is in its solved position, but twisted or flipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MisorientedPiece {
+    /// The letter of the sticker that is in the `U`/`D` position (or the `F`/`B` position for `E` slice edges).
+    pub letter: char,
+    /// The orientation of the piece (`1` is clockwise for corners).
+    pub orientation: u8,
+}
+
+/// Memo for one type of piece.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrbitMemo {
+    pub targets: Vec<BlindTarget>,
+    pub misoriented: Vec<MisorientedPiece>,
+}
+
+impl OrbitMemo {
+    /// Groups the target letters into pairs (the last "pair" has a single letter if there are an odd number of targets).
+    pub fn letter_pairs(&self) -> Vec<String> {
+        self.targets
+            .chunks(2)
+            .map(|pair| pair.iter().map(|target| target.letter).collect())
+            .collect()
+    }
+}
+
+/// Memo for solving a 3x3x3 blindfolded, one target at a time (e.g. using Old Pochmann or 3-style).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlindMemo {
+    pub edges: OrbitMemo,
+    pub corners: OrbitMemo,
+    /// Whether the pattern has odd permutation parity (i.e. an odd number of targets for each piece type).
+    pub parity: bool,
+}
+
+fn trace_orbit<const N: usize>(
+    pattern: &KPattern,
+    orbit: &KPuzzleOrbitInfo,
+    letters: &[[char; N]],
+    buffer: u8,
+) -> Result<OrbitMemo, BlindTracingError> {
+    let num_pieces = letters.len() as u8;
+    if buffer >= num_pieces {
+        return Err(format!("Invalid buffer for orbit {}: {}", orbit.name, buffer).into());
+    }
+    // The home `(position, sticker)` of the sticker currently at `(position, sticker)`.
+    let home = |position: u8, sticker: u8| -> (u8, u8) {
+        let piece = pattern.get_piece(orbit, position);
+        let orientation = pattern
+            .get_orientation_with_mod(orbit, position)
+            .orientation;
+        (piece, (sticker + orientation) % N as u8)
+    };
+    let mut seen = vec![false; num_pieces as usize];
+    for position in 0..num_pieces {
+        let piece = pattern.get_piece(orbit, position);
+        if piece >= num_pieces
+            || seen[piece as usize]
+            || pattern
+                .get_orientation_with_mod(orbit, position)
+                .orientation_mod
+                == 1
+        {
+            return Err(format!("Invalid pieces or orientations in orbit: {}", orbit.name).into());
+        }
+        seen[piece as usize] = true;
+    }
+
+    let mut visited = vec![false; num_pieces as usize];
+    visited[buffer as usize] = true;
+    let mut misoriented = vec![];
+    for position in 0..num_pieces {
+        if position != buffer && pattern.get_piece(orbit, position) == position {
+            visited[position as usize] = true;
+            let orientation = pattern
+                .get_orientation_with_mod(orbit, position)
+                .orientation;
+            if orientation != 0 {
+                misoriented.push(MisorientedPiece {
+                    letter: letters[position as usize][orientation as usize],
+                    orientation,
+                });
+            }
+        }
+    }
+
+    let mut targets = vec![];
+    let mut current = (buffer, 0);
+    // After a cycle break, the buffer piece is in the position where the cycle started.
+    let mut cycle_start = buffer;
+    loop {
+        let (piece, sticker) = home(current.0, current.1);
+        if piece == cycle_start {
+            if piece != buffer {
+                targets.push(BlindTarget {
+                    letter: letters[piece as usize][sticker as usize],
+                    cycle_break: false,
+                });
+            }
+            // The buffer piece is solved, so we need to break into a new cycle (if there are any unsolved pieces left).
+            let Some(next) = (0..num_pieces).find(|position| !visited[*position as usize]) else {
+                break;
+            };
+            visited[next as usize] = true;
+            targets.push(BlindTarget {
+                letter: letters[next as usize][0],
+                cycle_break: true,
+            });
+            cycle_start = next;
+            current = (next, 0);
+            continue;
+        }
+        visited[piece as usize] = true;
+        targets.push(BlindTarget {
+            letter: letters[piece as usize][sticker as usize],
+            cycle_break: false,
+        });
+        current = (piece, sticker);
+    }
+    Ok(OrbitMemo {
+        targets,
+        misoriented,
+    })
+}
+
+/// Traces the corner and edge cycles of a 3x3x3 pattern, for memorizing it as letters for blindfolded solving.
+///
+/// The centers must be solved (i.e. the pattern must not include any rotations).
+///
+/// ```
+/// use cubing_core::{
+///     alg::Alg,
+///     puzzles::{cube3x3x3_kpuzzle, trace_blind_memo, BlindTracingOptions},
+/// };
+///
+/// // A U perm (cycling the UF, UL, and UR edges).
+/// let pattern = cube3x3x3_kpuzzle()
+///     .default_pattern()
+///     .apply_alg(&"R U' R U R U R U' R' U' R2".parse::<Alg>().unwrap())
+///     .unwrap();
+/// let memo = trace_blind_memo(&pattern, &BlindTracingOptions::default()).unwrap();
+/// assert_eq!(memo.edges.letter_pairs(), ["DB"]);
+/// assert!(memo.corners.targets.is_empty());
+/// assert!(!memo.parity);
+/// ```
+pub fn trace_blind_memo(
+    pattern: &KPattern,
+    options: &BlindTracingOptions,
+) -> Result<BlindMemo, BlindTracingError> {
+    let kpuzzle = pattern.kpuzzle();
+    if kpuzzle.definition().name != "3x3x3" {
+        return Err(format!(
+            "Expected a 3x3x3 pattern, found a {} pattern.",
+            kpuzzle.definition().name
+        )
+        .into());
+    }
+    let lookup_orbit = |orbit_name: &str| -> Result<&KPuzzleOrbitInfo, BlindTracingError> {
+        kpuzzle
+            .lookup_orbit(&orbit_name.into())
+            .ok_or_else(|| format!("Missing orbit: {}", orbit_name).into())
+    };
+    let corners = lookup_orbit("CORNERS")?;
+    let edges = lookup_orbit("EDGES")?;
+    let centers = lookup_orbit("CENTERS")?;
+    if (0..centers.num_pieces).any(|i| pattern.get_piece(centers, i) != i) {
+        return Err("The centers must be solved for blindfolded tracing.".into());
+    }
+
+    let corner_memo = trace_orbit(
+        pattern,
+        corners,
+        &options.letter_scheme.corners,
+        options.corner_buffer,
+    )?;
+    let edge_memo = trace_orbit(
+        pattern,
+        edges,
+        &options.letter_scheme.edges,
+        options.edge_buffer,
+    )?;
+    let corner_pieces: Vec<u8> = (0..corners.num_pieces)
+        .map(|i| pattern.get_piece(corners, i))
+        .collect();
+    Ok(BlindMemo {
+        edges: edge_memo,
+        corners: corner_memo,
+        parity: permutation_parity(&corner_pieces),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alg::Alg, kpuzzle::KPattern, puzzles::cube3x3x3_kpuzzle};
+
+    use super::{
+        trace_blind_memo, BlindMemo, BlindTarget, BlindTracingOptions, LetterScheme,
+        MisorientedPiece,
+    };
+
+    fn pattern(alg: &str) -> KPattern {
+        cube3x3x3_kpuzzle()
+            .default_pattern()
+            .apply_alg(&alg.parse::<Alg>().unwrap())
+            .unwrap()
+    }
+
+    fn memo(alg: &str) -> BlindMemo {
+        trace_blind_memo(&pattern(alg), &BlindTracingOptions::default()).unwrap()
+    }
+
+    fn letters(targets: &[BlindTarget]) -> String {
+        targets.iter().map(|target| target.letter).collect()
+    }
+
+    #[test]
+    fn traces_cycles() {
+        let solved = memo("");
+        assert!(solved.edges.targets.is_empty());
+        assert!(solved.corners.targets.is_empty());
+        assert!(!solved.parity);
+
+        // Swaps the UR and UL edges, and the UFR and URB corners.
+        let t_perm = memo("R U R' U' R' F R2 U' R' U' R U R' F'");
+        assert_eq!(letters(&t_perm.corners.targets), "B");
+        assert_eq!(letters(&t_perm.edges.targets), "BDB");
+        assert!(t_perm.edges.targets[0].cycle_break);
+        assert!(!t_perm.edges.targets[1].cycle_break);
+        assert_eq!(t_perm.edges.letter_pairs(), ["BD", "B"]);
+        assert!(t_perm.parity);
+
+        let scramble = memo("R U2 B' D L2 F");
+        assert_eq!(scramble.edges.targets.len() % 2 == 1, scramble.parity);
+        assert_eq!(scramble.corners.targets.len() % 2 == 1, scramble.parity);
+    }
+
+    #[test]
+    fn traces_cycles_that_start_with_a_cycle_break() {
+        // Cycles the UR, UB and UL edges, leaving the UF buffer solved.
+        let u_perm = memo("U2 R U' R U R U R U' R' U' R2 U2");
+        assert!(u_perm.corners.targets.is_empty());
+        assert_eq!(letters(&u_perm.edges.targets), "BDAB");
+        assert!(u_perm.edges.targets[0].cycle_break);
+        assert!(!u_perm.edges.targets[3].cycle_break);
+        assert!(!u_perm.parity);
+    }
+
+    #[test]
+    fn misoriented_pieces() {
+        // Flips the UF and UB edges.
+        let flip = memo("M' U M' U M' U M' U2 M' U M' U M' U M'");
+        assert!(flip.edges.targets.is_empty());
+        assert_eq!(
+            flip.edges.misoriented,
+            [MisorientedPiece {
+                letter: 'Q',
+                orientation: 1
+            }]
+        );
+
+        let options = BlindTracingOptions {
+            edge_buffer: 1,
+            ..Default::default()
+        };
+        let flip =
+            trace_blind_memo(&pattern("M' U M' U M' U M' U2 M' U M' U M' U M'"), &options).unwrap();
+        assert_eq!(flip.edges.misoriented.len(), 2);
+    }
+
+    #[test]
+    fn letter_schemes() {
+        let options = BlindTracingOptions {
+            letter_scheme: LetterScheme::new(
+                "abcdefghijklmnopqrstuvwx",
+                "ABCDEFGHIJKLMNOPQRSTUVWX",
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        let t_perm =
+            trace_blind_memo(&pattern("R U R' U' R' F R2 U' R' U' R U R' F'"), &options).unwrap();
+        assert_eq!(letters(&t_perm.corners.targets), "b");
+        assert_eq!(letters(&t_perm.edges.targets), "BDB");
+        assert_eq!(options.letter_scheme.corner_letter(0, 0), 'c');
+        assert_eq!(options.letter_scheme.edge_letter(8, 1), 'P');
+
+        assert!(LetterScheme::new("ABC", "ABC").is_err());
+        assert!(trace_blind_memo(
+            &pattern(""),
+            &BlindTracingOptions {
+                corner_buffer: 8,
+                ..Default::default()
+            }
+        )
+        .is_err());
+        assert!(trace_blind_memo(&pattern("x"), &BlindTracingOptions::default()).is_err());
+    }
+}
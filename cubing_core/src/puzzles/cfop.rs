@@ -0,0 +1,680 @@
+use std::fmt::Display;
+
+use crate::{
+    alg::{Alg, AlgNode, LineComment, Newline},
+    kpuzzle::{
+        KPattern, KPatternMask, KPuzzle, KTransformation, KTransformationOrbitData, PieceMask,
+    },
+};
+
+use super::{cube3x3x3_kpuzzle, stickering_mask};
+
+/// A face of the 3x3x3 in its default orientation (e.g. `U` for a white cross with the standard color scheme).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    U,
+    L,
+    F,
+    R,
+    B,
+    D,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::U,
+        CubeFace::L,
+        CubeFace::F,
+        CubeFace::R,
+        CubeFace::B,
+        CubeFace::D,
+    ];
+
+    // A rotation that takes the face to D.
+    fn rotation_to_d(&self) -> &'static str {
+        match self {
+            CubeFace::U => "x2",
+            CubeFace::L => "z'",
+            CubeFace::F => "x'",
+            CubeFace::R => "z",
+            CubeFace::B => "x",
+            CubeFace::D => "",
+        }
+    }
+}
+
+impl Display for CubeFace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A CFOP step, in the order they are completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CfopStep {
+    Cross,
+    /// The number of F2L pairs that are solved (1 to 4), regardless of which slots they are in.
+    F2lPair(u8),
+    Oll,
+    Pll,
+}
+
+impl CfopStep {
+    pub const ALL: [CfopStep; 7] = [
+        CfopStep::Cross,
+        CfopStep::F2lPair(1),
+        CfopStep::F2lPair(2),
+        CfopStep::F2lPair(3),
+        CfopStep::F2lPair(4),
+        CfopStep::Oll,
+        CfopStep::Pll,
+    ];
+}
+
+impl Display for CfopStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CfopStep::Cross => write!(f, "cross"),
+            CfopStep::F2lPair(n) => write!(f, "pair {}", n),
+            CfopStep::Oll => write!(f, "OLL"),
+            CfopStep::Pll => write!(f, "PLL"),
+        }
+    }
+}
+
+// All 24 orientations of the cube.
+const ROTATIONS: [&str; 24] = [
+    "", "y", "y2", "y'", "x", "x y", "x y2", "x y'", "x2", "x2 y", "x2 y2", "x2 y'", "x'", "x' y",
+    "x' y2", "x' y'", "z", "z y", "z y2", "z y'", "z'", "z' y", "z' y2", "z' y'",
+];
+
+const AUFS: [&str; 4] = ["", "U", "U2", "U'"];
+
+// Indexed by case number (starting at 1).
+const OLL_ALGS: [&str; 57] = [
+    "R U2 R2 F R F' U2 R' F R F'",
+    "F R U R' U' F' f R U R' U' f'",
+    "f R U R' U' f' U' F R U R' U' F'",
+    "f R U R' U' f' U F R U R' U' F'",
+    "r' U2 R U R' U r",
+    "r U2 R' U' R U' r'",
+    "r U R' U R U2 r'",
+    "r' U' R U' R' U2 r",
+    "R U R' U' R' F R2 U R' U' F'",
+    "R U R' U R' F R F' R U2 R'",
+    "r U R' U R' F R F' R U2 r'",
+    "M' R' U' R U' R' U2 R U' R r'",
+    "F U R U' R2 F' R U R U' R'",
+    "R' F R U R' F' R F U' F'",
+    "r' U' r R' U' R U r' U r",
+    "r U r' R U R' U' r U' r'",
+    "R U R' U R' F R F' U2 R' F R F'",
+    "r U R' U R U2 r2 U' R U' R' U2 r",
+    "r' R U R U R' U' M' R' F R F'",
+    "r U R' U' M2 U R U' R' U' M'",
+    "R U2 R' U' R U R' U' R U' R'",
+    "R U2 R2 U' R2 U' R2 U2 R",
+    "R2 D' R U2 R' D R U2 R",
+    "r U R' U' r' F R F'",
+    "F' r U R' U' r' F R",
+    "R U2 R' U' R U' R'",
+    "R U R' U R U2 R'",
+    "r U R' U' r' R U R U' R'",
+    "R U R' U' R U' R' F' U' F R U R'",
+    "F R' F R2 U' R' U' R U R' F2",
+    "R' U' F U R U' R' F' R",
+    "L U F' U' L' U L F L'",
+    "R U R' U' R' F R F'",
+    "R U R2 U' R' F R U R U' F'",
+    "R U2 R2 F R F' R U2 R'",
+    "L' U' L U' L' U L U L F' L' F",
+    "F R' F' R U R U' R'",
+    "R U R' U R U' R' U' R' F R F'",
+    "L F' L' U' L U F U' L'",
+    "R' F R U R' U' F' U R",
+    "R U R' U R U2 R' F R U R' U' F'",
+    "R' U' R U' R' U2 R F R U R' U' F'",
+    "F' U' L' U L F",
+    "F U R U' R' F'",
+    "F R U R' U' F'",
+    "R' U' R' F R F' U R",
+    "R' U' R' F R F' R' F R F' U R",
+    "F R U R' U' R U R' U' F'",
+    "r U' r2 U r2 U r2 U' r",
+    "r' U r2 U' r2 U' r2 U r'",
+    "F U R U' R' U R U' R' F'",
+    "R U R' U R U' B U' B' R'",
+    "l' U2 L U L' U' L U L' U l",
+    "r U2 R' U' R U R' U' R U' r'",
+    "R U2 R2 U' R U' R' U2 F R F'",
+    "r' U' r U' R' U R U' R' U R r' U r",
+    "R U R' U' M' U R U' r'",
+];
+
+const PLL_ALGS: [(&str, &str); 21] = [
+    ("Aa", "x R' U R' D2 R U' R' D2 R2 x'"),
+    ("Ab", "x R2 D2 R U R' D2 R U' R x'"),
+    ("E", "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+    ("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    ("Ga", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    ("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    ("Gc", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    ("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    ("H", "M2 U M2 U2 M2 U M2"),
+    ("Ja", "x R2 F R F' R U2 r' U r U2 x'"),
+    ("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    (
+        "Na",
+        "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    ),
+    ("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    ("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    ("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    ("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    ("Ua", "M2 U M U2 M' U M2"),
+    ("Ub", "M2 U' M U2 M' U' M2"),
+    ("V", "R' U R' U' y R' F' R2 U' R' U R' F R F"),
+    ("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("Z", "M' U M2 U M2 U M' U2 M2"),
+];
+
+fn transformation_from_str(kpuzzle: &KPuzzle, alg: &str) -> KTransformation {
+    kpuzzle
+        .transformation_from_alg(&alg.parse::<Alg>().expect("Invalid built-in alg."))
+        .expect("Invalid built-in alg.")
+}
+
+// This relies on the default pattern of the 3x3x3 having each piece in its own position with orientation 0.
+fn transformation_from_pattern(pattern: &KPattern) -> KTransformation {
+    let data = pattern
+        .to_data()
+        .into_iter()
+        .map(|(orbit_name, orbit_data)| {
+            (
+                orbit_name,
+                KTransformationOrbitData {
+                    permutation: orbit_data.pieces,
+                    orientation_delta: orbit_data.orientation,
+                },
+            )
+        })
+        .collect();
+    KTransformation::try_from_data(pattern.kpuzzle(), &data)
+        .expect("A 3x3x3 pattern should be a valid transformation.")
+}
+
+/// Detects which CFOP steps are complete for a 3x3x3 pattern, for a cross on a given face.
+///
+/// Whole-cube rotations are ignored, so a pattern is treated the same as any rotation of it.
+///
+/// ```
+/// use cubing_core::{alg::Alg, puzzles::{cube3x3x3_kpuzzle, CfopStep, CfopStepDetector, CubeFace}};
+///
+/// let pattern = cube3x3x3_kpuzzle()
+///     .default_pattern()
+///     .apply_alg(&"x2 R U R'".parse::<Alg>().unwrap())
+///     .unwrap();
+/// assert_eq!(CfopStepDetector::new(CubeFace::U).progress(&pattern), Some(CfopStep::F2lPair(3)));
+/// assert_eq!(CfopStepDetector::new(CubeFace::R).progress(&pattern), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CfopStepDetector {
+    cross_face: CubeFace,
+    kpuzzle: KPuzzle,
+    rotations: Vec<KTransformation>,
+    to_cross_face: KTransformation,
+    from_cross_face: KTransformation,
+    cross: KPatternMask,
+    // FR, FL, BR, BL
+    pairs: [KPatternMask; 4],
+    oll: KPatternMask,
+}
+
+impl CfopStepDetector {
+    pub fn new(cross_face: CubeFace) -> Self {
+        let kpuzzle = cube3x3x3_kpuzzle().clone();
+        let lookup_orbit = |orbit_name: &str| {
+            kpuzzle
+                .lookup_orbit(&orbit_name.into())
+                .expect("Missing 3x3x3 orbit.")
+        };
+        let edges = lookup_orbit("EDGES");
+        let corners = lookup_orbit("CORNERS");
+        let cross = stickering_mask(&kpuzzle, "Cross").expect("Missing 3x3x3 stickering.");
+        // Reid order:
+        // UF  UR  UB  UL  . DF  DR  DB  DL  . FR  FL  BR  BL
+        // UFR URB UBL ULF . DRF DFL DLB DBR
+        let pairs = [(8, 4), (9, 5), (10, 7), (11, 6)].map(|(edge, corner)| {
+            let mut mask = cross.clone();
+            mask.set(edges, edge, PieceMask::Regular);
+            mask.set(corners, corner, PieceMask::Regular);
+            mask
+        });
+        let to_cross_face = transformation_from_str(&kpuzzle, cross_face.rotation_to_d());
+        Self {
+            cross_face,
+            rotations: ROTATIONS
+                .iter()
+                .map(|rotation| transformation_from_str(&kpuzzle, rotation))
+                .collect(),
+            from_cross_face: to_cross_face.invert(),
+            to_cross_face,
+            cross,
+            pairs,
+            oll: stickering_mask(&kpuzzle, "OLL").expect("Missing 3x3x3 stickering."),
+            kpuzzle,
+        }
+    }
+
+    pub fn cross_face(&self) -> CubeFace {
+        self.cross_face
+    }
+
+    /// The last step that is complete (or `None` if the cross is not solved).
+    ///
+    /// Panics if the pattern is not a 3x3x3 pattern.
+    pub fn progress(&self, pattern: &KPattern) -> Option<CfopStep> {
+        assert_eq!(
+            pattern.kpuzzle().definition().name,
+            self.kpuzzle.definition().name,
+            "Expected a 3x3x3 pattern."
+        );
+        self.progress_of_transformation(&transformation_from_pattern(pattern))
+    }
+
+    // Applies a rotation so that the centers are solved.
+    fn normalize(&self, transformation: &KTransformation) -> KTransformation {
+        let centers = self
+            .kpuzzle
+            .lookup_orbit(&"CENTERS".into())
+            .expect("Missing 3x3x3 orbit.");
+        self.rotations
+            .iter()
+            .map(|rotation| transformation.apply_transformation(rotation))
+            .find(|rotated| {
+                (0..centers.num_pieces).all(|i| rotated.get_permutation_idx(centers, i) == i)
+            })
+            .expect("Every 3x3x3 pattern should have a rotation with solved centers.")
+    }
+
+    // The pattern, rotated and relabeled so that the cross face is on D.
+    fn oriented(&self, transformation: &KTransformation) -> KTransformation {
+        self.from_cross_face
+            .apply_transformation(&self.normalize(transformation))
+            .apply_transformation(&self.to_cross_face)
+    }
+
+    fn pattern(&self, oriented: &KTransformation) -> KPattern {
+        self.kpuzzle
+            .default_pattern()
+            .apply_transformation(oriented)
+    }
+
+    fn progress_of_transformation(&self, transformation: &KTransformation) -> Option<CfopStep> {
+        let pattern = self.pattern(&self.oriented(transformation));
+        if !self.cross.is_solved(&pattern) {
+            return None;
+        }
+        let num_pairs = self
+            .pairs
+            .iter()
+            .filter(|mask| mask.is_solved(&pattern))
+            .count() as u8;
+        if num_pairs == 0 {
+            return Some(CfopStep::Cross);
+        }
+        if num_pairs < 4 || !self.oll.is_solved(&pattern) {
+            return Some(CfopStep::F2lPair(num_pairs));
+        }
+        if pattern != self.kpuzzle.default_pattern() {
+            return Some(CfopStep::Oll);
+        }
+        Some(CfopStep::Pll)
+    }
+
+    // The OLL case number, or `None` if the last layer is already oriented.
+    fn oll_case(&self, transformation: &KTransformation) -> Option<String> {
+        let oriented = self.oriented(transformation);
+        if self.oll.is_solved(&self.pattern(&oriented)) {
+            return None;
+        }
+        let aufs = AUFS.map(|auf| transformation_from_str(&self.kpuzzle, auf));
+        OLL_ALGS.iter().enumerate().find_map(|(i, oll_alg)| {
+            let oll_transformation = transformation_from_str(&self.kpuzzle, oll_alg);
+            aufs.iter()
+                .any(|auf| {
+                    let solved = self.normalize(
+                        &oriented
+                            .apply_transformation(auf)
+                            .apply_transformation(&oll_transformation),
+                    );
+                    self.oll.is_solved(&self.pattern(&solved))
+                })
+                .then(|| (i + 1).to_string())
+        })
+    }
+
+    // The PLL case name, or `None` if the cube can be solved with just a U move.
+    fn pll_case(&self, transformation: &KTransformation) -> Option<String> {
+        let oriented = self.oriented(transformation);
+        let aufs = AUFS.map(|auf| transformation_from_str(&self.kpuzzle, auf));
+        let default_pattern = self.kpuzzle.default_pattern();
+        let is_solved_with_auf = |transformation: &KTransformation| {
+            aufs.iter().any(|auf| {
+                self.pattern(&self.normalize(&transformation.apply_transformation(auf)))
+                    == default_pattern
+            })
+        };
+        if is_solved_with_auf(&oriented) {
+            return None;
+        }
+        PLL_ALGS.iter().find_map(|(name, pll_alg)| {
+            let pll_transformation = transformation_from_str(&self.kpuzzle, pll_alg);
+            aufs.iter()
+                .any(|auf| {
+                    is_solved_with_auf(
+                        &oriented
+                            .apply_transformation(auf)
+                            .apply_transformation(&pll_transformation),
+                    )
+                })
+                .then(|| (*name).to_owned())
+        })
+    }
+}
+
+/// Could not segment a solve (e.g. because the solution does not solve the scramble).
+#[derive(Debug)]
+pub struct CfopSegmentationError {
+    pub description: String,
+}
+
+impl From<String> for CfopSegmentationError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for CfopSegmentationError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for CfopSegmentationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfopSegment {
+    pub step: CfopStep,
+    /// Empty if the step was skipped (e.g. an OLL skip).
+    pub alg: Alg,
+    /// The OLL case number (e.g. `"27"`) or PLL case name (e.g. `"T"`), if the step is OLL or PLL and was not skipped.
+    pub case: Option<String>,
+}
+
+impl CfopSegment {
+    /// A label like `"pair 2"`, `"OLL 27"`, `"PLL T"`, or `"OLL skip"`.
+    pub fn label(&self) -> String {
+        match (&self.case, self.step) {
+            (Some(case), step) => format!("{} {}", step, case),
+            (None, CfopStep::Oll | CfopStep::Pll) if self.alg.nodes.is_empty() => {
+                format!("{} skip", self.step)
+            }
+            (None, step) => step.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfopSegmentation {
+    pub cross_face: CubeFace,
+    /// One segment for each step, in the order of [`CfopStep::ALL`].
+    pub segments: Vec<CfopSegment>,
+}
+
+impl CfopSegmentation {
+    /// The solution with one line per step, each ending with a comment with the label of the step (e.g. `// OLL 27`).
+    pub fn to_alg(&self) -> Alg {
+        let mut nodes = Vec::<AlgNode>::default();
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                nodes.push(AlgNode::NewlineNode(Newline::default()));
+            }
+            nodes.extend(segment.alg.nodes.iter().cloned());
+            nodes.push(AlgNode::LineCommentNode(
+                LineComment::try_new(&format!(" {}", segment.label()))
+                    .expect("Labels do not contain newlines."),
+            ));
+        }
+        Alg { nodes }
+    }
+}
+
+// Faces that are checked first are preferred if the F2L of several faces is completed at the same time.
+const CROSS_FACES: [CubeFace; 6] = [
+    CubeFace::D,
+    CubeFace::U,
+    CubeFace::F,
+    CubeFace::B,
+    CubeFace::R,
+    CubeFace::L,
+];
+
+// The first index (not before `start`) at which the step is complete.
+fn first_index(progress: &[Option<CfopStep>], start: usize, step: CfopStep) -> Option<usize> {
+    progress[start..]
+        .iter()
+        .position(|entry| *entry >= Some(step))
+        .map(|i| start + i)
+}
+
+/// Splits a CFOP solution of a 3x3x3 scramble into cross, F2L pairs, OLL, and PLL.
+///
+/// The cross face is the face whose F2L is completed first. Each step ends at
+/// the first node after which it is complete (after the end of the previous
+/// step), so a step that is completed by the same node as the previous one is
+/// a skip. Top-level nodes of the solution (e.g. a grouping) are not split, and
+/// comments and newlines in the solution are dropped.
+///
+/// ```
+/// use cubing_core::{alg::Alg, puzzles::{segment_cfop_solve, CubeFace}};
+///
+/// let scramble = "R U R' U' R' F R2 U' R' U' R U R' F' R U' R'".parse::<Alg>().unwrap();
+/// let solution = "R U R' R U R' U' R' F R2 U' R' U' R U R' F'".parse::<Alg>().unwrap();
+/// let segmentation = segment_cfop_solve(&scramble, &solution).unwrap();
+/// assert_eq!(segmentation.cross_face, CubeFace::D);
+/// assert_eq!(
+///     segmentation.to_alg().to_string(),
+///     "// cross\n// pair 1\n// pair 2\n// pair 3\nR U R' // pair 4\n// OLL skip\nR U R' U' R' F R2 U' R' U' R U R' F' // PLL T"
+/// );
+/// ```
+pub fn segment_cfop_solve(
+    scramble: &Alg,
+    solution: &Alg,
+) -> Result<CfopSegmentation, CfopSegmentationError> {
+    let kpuzzle = cube3x3x3_kpuzzle();
+    let mut transformation = kpuzzle
+        .transformation_from_alg(scramble)
+        .map_err(|e| format!("Invalid scramble: {}", e))?;
+    let nodes: Vec<AlgNode> = solution
+        .nodes
+        .iter()
+        .filter(|node| !matches!(node, AlgNode::NewlineNode(_) | AlgNode::LineCommentNode(_)))
+        .cloned()
+        .collect();
+    let mut transformations = vec![transformation.clone()];
+    for node in &nodes {
+        let node_transformation = kpuzzle
+            .transformation_from_alg(&Alg {
+                nodes: vec![node.clone()],
+            })
+            .map_err(|e| format!("Invalid solution: {}", e))?;
+        transformation = transformation.apply_transformation(&node_transformation);
+        transformations.push(transformation.clone());
+    }
+
+    let mut best: Option<(CfopStepDetector, Vec<Option<CfopStep>>)> = None;
+    let mut best_key = (usize::MAX, usize::MAX);
+    for cross_face in CROSS_FACES {
+        let detector = CfopStepDetector::new(cross_face);
+        let progress: Vec<Option<CfopStep>> = transformations
+            .iter()
+            .map(|transformation| detector.progress_of_transformation(transformation))
+            .collect();
+        if progress.last() != Some(&Some(CfopStep::Pll)) {
+            return Err("The solution does not solve the scramble.".into());
+        }
+        // Both are present, since the last entry is `Pll`.
+        let key = (
+            first_index(&progress, 0, CfopStep::F2lPair(4)).unwrap_or_default(),
+            first_index(&progress, 0, CfopStep::Cross).unwrap_or_default(),
+        );
+        if key < best_key {
+            best_key = key;
+            best = Some((detector, progress));
+        }
+    }
+    let (detector, progress) = best.expect("At least one cross face should be checked.");
+
+    let mut segments = vec![];
+    let mut start = 0;
+    for step in CfopStep::ALL {
+        let end = first_index(&progress, start, step).unwrap_or(nodes.len());
+        let case = match step {
+            CfopStep::Oll => detector.oll_case(&transformations[start]),
+            CfopStep::Pll => detector.pll_case(&transformations[start]),
+            _ => None,
+        };
+        segments.push(CfopSegment {
+            step,
+            alg: Alg {
+                nodes: nodes[start..end].to_vec(),
+            },
+            case,
+        });
+        start = end;
+    }
+    Ok(CfopSegmentation {
+        cross_face: detector.cross_face(),
+        segments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alg::Alg, puzzles::cube3x3x3_kpuzzle};
+
+    use super::{
+        segment_cfop_solve, CfopStep, CfopStepDetector, CubeFace, CROSS_FACES, OLL_ALGS, PLL_ALGS,
+    };
+
+    fn transformation(alg: &str) -> crate::kpuzzle::KTransformation {
+        cube3x3x3_kpuzzle()
+            .transformation_from_alg(&alg.parse::<Alg>().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn detects_crosses_on_any_face() {
+        // `R` leaves only the L cross solved (with the last layer oriented).
+        let pattern = cube3x3x3_kpuzzle()
+            .default_pattern()
+            .apply_alg(&"R".parse::<Alg>().unwrap())
+            .unwrap();
+        for cross_face in CROSS_FACES {
+            let expected = (cross_face == CubeFace::L).then_some(CfopStep::Oll);
+            assert_eq!(
+                CfopStepDetector::new(cross_face).progress(&pattern),
+                expected,
+                "{}",
+                cross_face
+            );
+        }
+        let pattern = cube3x3x3_kpuzzle()
+            .default_pattern()
+            .apply_alg(
+                &"y x' R U R' U' R' F R2 U' R' U' R U R' F' z"
+                    .parse::<Alg>()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(
+            CfopStepDetector::new(CubeFace::R).progress(&pattern),
+            Some(CfopStep::Oll)
+        );
+    }
+
+    #[test]
+    fn identifies_every_case() {
+        let detector = CfopStepDetector::new(CubeFace::D);
+        for (i, oll_alg) in OLL_ALGS.iter().enumerate() {
+            let case = transformation(&format!("({})' U", oll_alg));
+            assert_eq!(
+                detector.progress_of_transformation(&case),
+                Some(CfopStep::F2lPair(4)),
+                "OLL {}",
+                i + 1
+            );
+            assert_eq!(detector.oll_case(&case), Some((i + 1).to_string()));
+        }
+        for (name, pll_alg) in PLL_ALGS {
+            let case = transformation(&format!("U2 ({})' U'", pll_alg));
+            assert_eq!(
+                detector.progress_of_transformation(&case),
+                Some(CfopStep::Oll),
+                "PLL {}",
+                name
+            );
+            assert_eq!(detector.pll_case(&case).as_deref(), Some(name));
+        }
+        assert_eq!(detector.pll_case(&transformation("U")), None);
+    }
+
+    #[test]
+    fn segments_a_solve() {
+        // Each pair is inserted into its own slot (FR, FL, BR, BL), with the cross on D.
+        let solution = "x2 // inspection
+            F // cross
+            R U R'
+            L' U' L
+            (R' U' R)
+            L U L'
+            F R U R' U' F'
+            R U R' U' R' F R2 U' R' U' R U R' F'"
+            .parse::<Alg>()
+            .unwrap();
+        let scramble = solution.invert();
+        let segmentation = segment_cfop_solve(&scramble, &solution).unwrap();
+        assert_eq!(segmentation.cross_face, CubeFace::D);
+        let segments: Vec<(String, String)> = segmentation
+            .segments
+            .iter()
+            .map(|segment| (segment.label(), segment.alg.to_string()))
+            .collect();
+        assert_eq!(
+            segments,
+            [
+                ("cross", "x2 F"),
+                ("pair 1", "R U R'"),
+                ("pair 2", "L' U' L"),
+                ("pair 3", "(R' U' R)"),
+                ("pair 4", "L U L'"),
+                ("OLL 45", "F R U R' U' F'"),
+                ("PLL T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+            ]
+            .map(|(label, alg)| (label.to_owned(), alg.to_owned()))
+        );
+        assert_eq!(
+            segmentation.to_alg().to_string().lines().next(),
+            Some("x2 F // cross")
+        );
+
+        assert!(segment_cfop_solve(&scramble, &"R".parse::<Alg>().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,498 @@
+use std::collections::HashMap;
+
+use crate::{
+    alg::{Alg, Move, MovePrefix, QuantumMove},
+    kpuzzle::{
+        InvalidDefinitionError, KPatternData, KPatternOrbitData, KPuzzle, KPuzzleDefinition,
+        KPuzzleOrbitDefinition, KPuzzleOrbitName, KTransformationData, KTransformationOrbitData,
+    },
+};
+
+/// The largest cube supported by [`cube_nxnxn_kpuzzle`].
+pub const MAX_NXNXN_SIZE: u32 = 32;
+
+type Vector = [i32; 3];
+
+const FACES: [(&str, Vector); 6] = [
+    ("U", [0, 1, 0]),
+    ("L", [-1, 0, 0]),
+    ("F", [0, 0, 1]),
+    ("R", [1, 0, 0]),
+    ("B", [0, 0, -1]),
+    ("D", [0, -1, 0]),
+];
+const OPPOSITE_FACES: [usize; 6] = [5, 3, 4, 1, 2, 0];
+// Indices into `FACES`, in Reid order: UF UR UB UL DF DR DB DL FR FL BR BL
+const EDGE_FACES: [[usize; 2]; 12] = [
+    [0, 2],
+    [0, 3],
+    [0, 4],
+    [0, 1],
+    [5, 2],
+    [5, 3],
+    [5, 4],
+    [5, 1],
+    [2, 3],
+    [2, 1],
+    [4, 3],
+    [4, 1],
+];
+// Reid order: UFR URB UBL ULF DRF DFL DLB DBR
+const CORNER_SIGNS: [Vector; 8] = [
+    [1, 1, 1],
+    [1, 1, -1],
+    [-1, 1, -1],
+    [-1, 1, 1],
+    [1, -1, 1],
+    [-1, -1, 1],
+    [-1, -1, -1],
+    [1, -1, -1],
+];
+
+fn dot(a: Vector, b: Vector) -> i32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn scale(a: Vector, factor: i32) -> Vector {
+    a.map(|x| x * factor)
+}
+
+fn add(a: Vector, b: Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// A quarter turn of `p` around the unit vector `axis`, clockwise when looking at the `axis` side.
+fn rotate_clockwise(axis: Vector, p: Vector) -> Vector {
+    let along = scale(axis, dot(axis, p));
+    let c = cross(axis, p);
+    [along[0] - c[0], along[1] - c[1], along[2] - c[2]]
+}
+
+/// Vectors pointing "right" and "up" on a face, when looking at the face with U/D
+/// in the usual orientation (or B on top of U and F on top of D).
+fn face_frame(normal: Vector) -> (Vector, Vector) {
+    let up = if normal[1] != 0 {
+        [0, 0, -normal[1]]
+    } else {
+        [0, 1, 0]
+    };
+    (cross(up, normal), up)
+}
+
+struct CubeOrbit {
+    name: String,
+    num_orientations: u8,
+    /// For each piece: its cubie coordinates, and the location of each of its
+    /// stickers (indexed by orientation).
+    pieces: Vec<(Vector, Vec<Vector>)>,
+    default_pieces: Vec<u8>,
+    orientation_mod: Option<Vec<u8>>,
+}
+
+struct CubeGeometry {
+    n: i32,
+    orbits: Vec<CubeOrbit>,
+    sticker_lookup: HashMap<Vector, (usize, usize)>,
+}
+
+impl CubeGeometry {
+    fn new(n: i32) -> Self {
+        let max = n - 1;
+        // Sticker locations are scaled by 4 so that fixed centers can have a marker
+        // for each orientation.
+        let sticker = |cubie: Vector, normal: Vector| -> Vector {
+            [0, 1, 2].map(|i| {
+                if normal[i] != 0 {
+                    4 * n * normal[i]
+                } else {
+                    4 * cubie[i]
+                }
+            })
+        };
+        let normal = |face: usize| FACES[face].1;
+
+        let mut orbits = vec![];
+        if n % 2 == 1 {
+            let pieces = EDGE_FACES
+                .iter()
+                .map(|&[face_0, face_1]| {
+                    let cubie = scale(add(normal(face_0), normal(face_1)), max);
+                    (
+                        cubie,
+                        vec![
+                            sticker(cubie, normal(face_0)),
+                            sticker(cubie, normal(face_1)),
+                        ],
+                    )
+                })
+                .collect();
+            orbits.push(CubeOrbit {
+                name: "EDGES".to_owned(),
+                num_orientations: 2,
+                pieces,
+                default_pieces: (0..12).collect(),
+                orientation_mod: None,
+            });
+        }
+
+        let corners = CORNER_SIGNS
+            .iter()
+            .map(|&signs| {
+                let cubie = scale(signs, max);
+                let u_or_d = [0, signs[1], 0];
+                let (mut second, mut third) = ([signs[0], 0, 0], [0, 0, signs[2]]);
+                // Stickers go clockwise around the corner.
+                if dot(cross(u_or_d, second), signs) > 0 {
+                    (second, third) = (third, second);
+                }
+                (
+                    cubie,
+                    vec![
+                        sticker(cubie, u_or_d),
+                        sticker(cubie, second),
+                        sticker(cubie, third),
+                    ],
+                )
+            })
+            .collect();
+        orbits.push(CubeOrbit {
+            name: "CORNERS".to_owned(),
+            num_orientations: 3,
+            pieces: corners,
+            default_pieces: (0..8).collect(),
+            orientation_mod: None,
+        });
+
+        // Each wing orbit is identified by its distance from the middle of the edge.
+        for (wing_orbit_index, offset) in (1..max).filter(|i| (max - i) % 2 == 0).enumerate() {
+            let mut pieces = vec![];
+            for &[face_0, face_1] in &EDGE_FACES {
+                let (normal_0, normal_1) = (normal(face_0), normal(face_1));
+                let edge_direction = cross(normal_0, normal_1);
+                // Stickers are ordered so that wings never need an orientation.
+                for sign in [1, -1] {
+                    let cubie = add(
+                        scale(add(normal_0, normal_1), max),
+                        scale(edge_direction, sign * offset),
+                    );
+                    let mut stickers = vec![sticker(cubie, normal_0), sticker(cubie, normal_1)];
+                    if sign < 0 {
+                        stickers.reverse();
+                    }
+                    pieces.push((cubie, stickers));
+                }
+            }
+            orbits.push(CubeOrbit {
+                name: numbered_orbit_name("WINGS", wing_orbit_index),
+                num_orientations: 1,
+                pieces,
+                default_pieces: (0..24).collect(),
+                orientation_mod: None,
+            });
+        }
+
+        // Center orbits are identified by the coordinates of a representative piece
+        // on the face (which are unique up to quarter turns of the face).
+        let mut center_kinds = vec![];
+        for u in (-max + 1)..max {
+            for v in (-max + 1)..max {
+                if (max - u) % 2 == 0
+                    && (max - v) % 2 == 0
+                    && ((u > 0 && v >= 0) || (u, v) == (0, 0))
+                {
+                    center_kinds.push((u, v));
+                }
+            }
+        }
+        center_kinds.sort_by_key(|&(u, v)| (u * u + v * v, u, v));
+        for (center_orbit_index, (u, v)) in center_kinds.into_iter().enumerate() {
+            let name = numbered_orbit_name("CENTERS", center_orbit_index);
+            if (u, v) == (0, 0) {
+                let pieces = FACES
+                    .iter()
+                    .map(|&(_, face_normal)| {
+                        let cubie = scale(face_normal, max);
+                        let (right, up) = face_frame(face_normal);
+                        let middle = sticker(cubie, face_normal);
+                        (
+                            cubie,
+                            [up, right, scale(up, -1), scale(right, -1)]
+                                .map(|marker| add(middle, marker))
+                                .to_vec(),
+                        )
+                    })
+                    .collect();
+                orbits.push(CubeOrbit {
+                    name,
+                    num_orientations: 4,
+                    pieces,
+                    default_pieces: (0..6).collect(),
+                    orientation_mod: Some(vec![1; 6]),
+                });
+                continue;
+            }
+            let mut pieces = vec![];
+            let mut default_pieces = vec![];
+            for (face_index, &(_, face_normal)) in FACES.iter().enumerate() {
+                let (right, up) = face_frame(face_normal);
+                // Clockwise around the face, starting from the top.
+                let (mut u, mut v) = if v == 0 { (0, u) } else { (u, v) };
+                for _ in 0..4 {
+                    let cubie = add(scale(face_normal, max), add(scale(right, u), scale(up, v)));
+                    pieces.push((cubie, vec![sticker(cubie, face_normal)]));
+                    default_pieces.push(face_index as u8);
+                    (u, v) = (v, -u);
+                }
+            }
+            orbits.push(CubeOrbit {
+                name,
+                num_orientations: 1,
+                pieces,
+                default_pieces,
+                orientation_mod: None,
+            });
+        }
+
+        let mut sticker_lookup = HashMap::new();
+        for orbit in &orbits {
+            for (piece_index, (_, stickers)) in orbit.pieces.iter().enumerate() {
+                for (orientation, &location) in stickers.iter().enumerate() {
+                    sticker_lookup.insert(location, (piece_index, orientation));
+                }
+            }
+        }
+        Self {
+            n,
+            orbits,
+            sticker_lookup,
+        }
+    }
+
+    /// A clockwise quarter turn of layers `outer_layer..=inner_layer`, counting from the `face` side.
+    fn layer_transformation(
+        &self,
+        face: usize,
+        outer_layer: i32,
+        inner_layer: i32,
+    ) -> KTransformationData {
+        let axis = FACES[face].1;
+        let mut data = KTransformationData::new();
+        for orbit in &self.orbits {
+            let num_pieces = orbit.pieces.len();
+            let mut permutation: Vec<u8> = (0..num_pieces as u8).collect();
+            let mut orientation_delta = vec![0; num_pieces];
+            for (piece_index, (cubie, stickers)) in orbit.pieces.iter().enumerate() {
+                let layer = (self.n - 1 - dot(*cubie, axis)) / 2 + 1;
+                if layer < outer_layer || layer > inner_layer {
+                    continue;
+                }
+                let (new_piece_index, orientation) =
+                    self.sticker_lookup[&rotate_clockwise(axis, stickers[0])];
+                permutation[new_piece_index] = piece_index as u8;
+                orientation_delta[new_piece_index] = orientation as u8;
+            }
+            data.insert(
+                KPuzzleOrbitName(orbit.name.clone()),
+                KTransformationOrbitData {
+                    permutation,
+                    orientation_delta,
+                },
+            );
+        }
+        data
+    }
+}
+
+fn numbered_orbit_name(base: &str, index: usize) -> String {
+    match index {
+        0 => base.to_owned(),
+        _ => format!("{}{}", base, index + 1),
+    }
+}
+
+fn key_move(family: &str, prefix: Option<MovePrefix>) -> Move {
+    Move {
+        quantum: QuantumMove::new(family, prefix).into(),
+        amount: 1,
+    }
+}
+
+fn layer_move_string(face: usize, layer: i32) -> String {
+    match layer {
+        1 => FACES[face].0.to_owned(),
+        _ => format!("{}{}", layer, FACES[face].0),
+    }
+}
+
+/// Generates the definition of an NxNxN cube, with the same conventions as the
+/// built-in definitions for 3x3x3 through 7x7x7.
+///
+/// The following moves are supported for every face (using `R` as an example):
+///
+/// - Single layers: `R`, `2R`, …, `M` (for odd sizes).
+/// - Wide moves: `Rw`, `3Rw`, …, `r`, `3r`, …
+/// - Ranges of layers: `2-3Rw`, `2-3r`, …
+/// - Rotations: `x`, `y`, `z`, `Rv`, …
+///
+/// Center pieces of the same color (other than fixed centers) are
+/// interchangeable in the default pattern.
+pub fn cube_nxnxn_kpuzzle_definition(n: u32) -> Result<KPuzzleDefinition, InvalidDefinitionError> {
+    if !(2..=MAX_NXNXN_SIZE).contains(&n) {
+        return Err(format!(
+            "Unsupported cube size: {} (must be between 2 and {})",
+            n, MAX_NXNXN_SIZE
+        )
+        .into());
+    }
+    let n = n as i32;
+    let geometry = CubeGeometry::new(n);
+
+    let mut orbits = vec![];
+    let mut default_pattern = KPatternData::new();
+    for orbit in &geometry.orbits {
+        let orbit_name = KPuzzleOrbitName(orbit.name.clone());
+        orbits.push(KPuzzleOrbitDefinition {
+            orbit_name: orbit_name.clone(),
+            num_pieces: orbit.pieces.len() as u8,
+            num_orientations: orbit.num_orientations,
+        });
+        default_pattern.insert(
+            orbit_name,
+            KPatternOrbitData {
+                pieces: orbit.default_pieces.clone(),
+                orientation: vec![0; orbit.pieces.len()],
+                orientation_mod: orbit.orientation_mod.clone(),
+            },
+        );
+    }
+
+    let mut moves = HashMap::new();
+    let mut derived_moves = HashMap::new();
+    let mut derive = |r#move: Move, alg: String| {
+        derived_moves.insert(
+            r#move,
+            alg.parse::<Alg>()
+                .expect("Generated NxNxN algs should always parse."),
+        );
+    };
+
+    for (face, &(family, _)) in FACES.iter().enumerate() {
+        let opposite = OPPOSITE_FACES[face];
+        // Single layers up to the middle are defined directly, the rest are
+        // defined from the opposite side.
+        for layer in 1..n {
+            let prefix = (layer > 1).then_some(MovePrefix::from(layer as u32));
+            if layer <= n / 2 {
+                moves.insert(
+                    key_move(family, prefix),
+                    geometry.layer_transformation(face, layer, layer),
+                );
+            } else if n % 2 == 1 && layer == n / 2 + 1 {
+                let slice = match family {
+                    "U" => "E'",
+                    "L" => "M",
+                    "F" => "S",
+                    "R" => "M'",
+                    "B" => "S'",
+                    _ => "E",
+                };
+                derive(key_move(family, prefix), slice.to_owned());
+            } else {
+                derive(
+                    key_move(family, prefix),
+                    format!("{}'", layer_move_string(opposite, n + 1 - layer)),
+                );
+            }
+        }
+
+        let wide_family = format!("{}w", family);
+        let lowercase_family = family.to_lowercase();
+        for inner_layer in 2..n {
+            let layers = |outer_layer: i32| {
+                (outer_layer..=inner_layer)
+                    .map(|layer| layer_move_string(face, layer))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            if inner_layer == 2 {
+                derive(key_move(&wide_family, None), layers(1));
+                derive(key_move(&lowercase_family, None), wide_family.clone());
+            }
+            let prefix = MovePrefix::from(inner_layer as u32);
+            derive(key_move(&wide_family, Some(prefix.clone())), layers(1));
+            derive(
+                key_move(&lowercase_family, Some(prefix)),
+                format!("{}{}", inner_layer, wide_family),
+            );
+            for outer_layer in 2..inner_layer {
+                let prefix = MovePrefix::from((outer_layer as u32, inner_layer as u32));
+                derive(
+                    key_move(&wide_family, Some(prefix.clone())),
+                    layers(outer_layer),
+                );
+                derive(
+                    key_move(&lowercase_family, Some(prefix)),
+                    layers(outer_layer),
+                );
+            }
+        }
+
+        let rotation = match family {
+            "U" => "y",
+            "L" => "x'",
+            "F" => "z",
+            "R" => "x",
+            "B" => "z'",
+            _ => "y'",
+        };
+        derive(key_move(&format!("{}v", family), None), rotation.to_owned());
+    }
+
+    if n % 2 == 1 {
+        for (family, face) in [("M", 1), ("E", 5), ("S", 2)] {
+            moves.insert(
+                key_move(family, None),
+                geometry.layer_transformation(face, n / 2 + 1, n / 2 + 1),
+            );
+        }
+    }
+    for (family, face) in [("x", 3), ("y", 0), ("z", 2)] {
+        moves.insert(
+            key_move(family, None),
+            geometry.layer_transformation(face, 1, n),
+        );
+    }
+
+    Ok(KPuzzleDefinition {
+        name: format!("{}x{}x{}", n, n, n),
+        orbits,
+        default_pattern,
+        moves,
+        derived_moves: Some(derived_moves),
+    })
+}
+
+/// Generates an NxNxN cube (for `n` from 2 to [`MAX_NXNXN_SIZE`]). See
+/// [`cube_nxnxn_kpuzzle_definition`] for the supported moves.
+///
+/// ```
+/// use cubing_core::{alg::Alg, puzzles::cube_nxnxn_kpuzzle};
+///
+/// let kpuzzle = cube_nxnxn_kpuzzle(9).unwrap();
+/// let pattern = kpuzzle
+///     .default_pattern()
+///     .apply_alg(&"3Rw 2-3r' R'".parse::<Alg>().unwrap())
+///     .unwrap();
+/// assert_eq!(pattern, kpuzzle.default_pattern());
+/// ```
+pub fn cube_nxnxn_kpuzzle(n: u32) -> Result<KPuzzle, InvalidDefinitionError> {
+    KPuzzle::try_new(cube_nxnxn_kpuzzle_definition(n)?)
+}
@@ -0,0 +1,541 @@
+use std::fmt::Display;
+
+use crate::kpuzzle::{permutation_parity, KPattern, KPuzzle, KPuzzleOrbitInfo, OrientationWithMod};
+
+use super::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle};
+
+// Facelet strings list the faces in this order, reading each face row by row
+// (with `U` above `F`, `B` above `U`, and `F` above `D`).
+pub(crate) const FACES: [char; 6] = ['U', 'R', 'F', 'D', 'L', 'B'];
+
+// The facelet indices of each position, in the same sticker order as the
+// (Reid order) piece names: UFR URB UBL ULF . DRF DFL DLB DBR
+const CUBE3X3X3_CORNER_FACELETS: [[usize; 3]; 8] = [
+    [8, 20, 9],
+    [2, 11, 45],
+    [0, 47, 36],
+    [6, 38, 18],
+    [29, 15, 26],
+    [27, 24, 44],
+    [33, 42, 53],
+    [35, 51, 17],
+];
+// UF UR UB UL . DF DR DB DL . FR FL BR BL
+const CUBE3X3X3_EDGE_FACELETS: [[usize; 2]; 12] = [
+    [7, 19],
+    [5, 10],
+    [1, 46],
+    [3, 37],
+    [28, 25],
+    [32, 16],
+    [34, 52],
+    [30, 43],
+    [23, 12],
+    [21, 41],
+    [48, 14],
+    [50, 39],
+];
+// U L F R B D
+const CUBE3X3X3_CENTER_FACELETS: [usize; 6] = [4, 40, 22, 13, 49, 31];
+const CUBE2X2X2_CORNER_FACELETS: [[usize; 3]; 8] = [
+    [3, 9, 4],
+    [1, 5, 20],
+    [0, 21, 16],
+    [2, 17, 8],
+    [13, 6, 11],
+    [12, 10, 19],
+    [14, 18, 23],
+    [15, 22, 7],
+];
+
+/// An error due to a facelet string that does not describe a valid cube, or a pattern that cannot be described by one.
+#[derive(Debug)]
+pub struct InvalidFaceletsError {
+    pub description: String,
+}
+
+impl From<String> for InvalidFaceletsError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for InvalidFaceletsError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for InvalidFaceletsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+fn face_of_facelet(facelet_index: usize, facelets_per_face: usize) -> char {
+    FACES[facelet_index / facelets_per_face]
+}
+
+fn lookup_orbit<'a>(
+    kpuzzle: &'a KPuzzle,
+    puzzle_name: &str,
+    orbit_name: &str,
+) -> Result<&'a KPuzzleOrbitInfo, InvalidFaceletsError> {
+    if kpuzzle.definition().name != puzzle_name {
+        return Err(format!(
+            "Expected a {} pattern, found a {} pattern.",
+            puzzle_name,
+            kpuzzle.definition().name
+        )
+        .into());
+    }
+    kpuzzle
+        .lookup_orbit(&orbit_name.into())
+        .ok_or_else(|| format!("Missing orbit: {}", orbit_name).into())
+}
+
+fn write_orbit<const N: usize>(
+    pattern: &KPattern,
+    orbit: &KPuzzleOrbitInfo,
+    position_facelets: &[[usize; N]],
+    facelets_per_face: usize,
+    face_mapping: impl Fn(char) -> char,
+    facelets: &mut [char],
+) -> Result<(), InvalidFaceletsError> {
+    for (i, facelet_indices) in position_facelets.iter().enumerate() {
+        let piece = pattern.get_piece(orbit, i as u8) as usize;
+        let orientation_with_mod = pattern.get_orientation_with_mod(orbit, i as u8);
+        if piece >= position_facelets.len() || orientation_with_mod.orientation_mod == 1 {
+            return Err(format!("Unknown piece or orientation in orbit: {}", orbit.name).into());
+        }
+        for (j, facelet_index) in facelet_indices.iter().enumerate() {
+            let piece_facelet_index =
+                position_facelets[piece][(j + orientation_with_mod.orientation as usize) % N];
+            facelets[*facelet_index] =
+                face_mapping(face_of_facelet(piece_facelet_index, facelets_per_face));
+        }
+    }
+    Ok(())
+}
+
+fn read_orbit<const N: usize>(
+    pattern: &mut KPattern,
+    orbit: &KPuzzleOrbitInfo,
+    position_facelets: &[[usize; N]],
+    facelets_per_face: usize,
+    facelets: &[char],
+    piece_type: &str,
+) -> Result<(), InvalidFaceletsError> {
+    let mut seen = vec![false; position_facelets.len()];
+    let mut total_orientation = 0;
+    for (i, facelet_indices) in position_facelets.iter().enumerate() {
+        let colors: Vec<char> = facelet_indices.iter().map(|j| facelets[*j]).collect();
+        let found =
+            position_facelets
+                .iter()
+                .enumerate()
+                .find_map(|(piece, piece_facelet_indices)| {
+                    (0..N).find_map(|orientation| {
+                        (0..N)
+                            .all(|j| {
+                                colors[j]
+                                    == face_of_facelet(
+                                        piece_facelet_indices[(j + orientation) % N],
+                                        facelets_per_face,
+                                    )
+                            })
+                            .then_some((piece, orientation))
+                    })
+                });
+        let Some((piece, orientation)) = found else {
+            return Err(format!(
+                "Invalid {} colors: {}",
+                piece_type,
+                colors.iter().collect::<String>()
+            )
+            .into());
+        };
+        if seen[piece] {
+            return Err(format!(
+                "Duplicate {}: {}",
+                piece_type,
+                colors.iter().collect::<String>()
+            )
+            .into());
+        }
+        seen[piece] = true;
+        total_orientation += orientation;
+        pattern.set_piece(orbit, i as u8, piece as u8);
+        pattern.set_orientation_with_mod(
+            orbit,
+            i as u8,
+            &OrientationWithMod::new_using_default_orientation_mod(orientation as u8),
+        );
+    }
+    if total_orientation % N != 0 {
+        return Err(format!(
+            "Invalid {} orientation (a single {} is twisted).",
+            piece_type, piece_type
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn parse_facelets(
+    facelet_string: &str,
+    facelets_per_face: usize,
+) -> Result<Vec<char>, InvalidFaceletsError> {
+    let facelets: Vec<char> = facelet_string.chars().collect();
+    if facelets.len() != FACES.len() * facelets_per_face {
+        return Err(format!(
+            "Expected {} facelets, found {}.",
+            FACES.len() * facelets_per_face,
+            facelets.len()
+        )
+        .into());
+    }
+    if let Some(facelet) = facelets.iter().find(|facelet| !FACES.contains(facelet)) {
+        return Err(format!("Invalid facelet color: {}", facelet).into());
+    }
+    for face in FACES {
+        let count = facelets.iter().filter(|facelet| **facelet == face).count();
+        if count != facelets_per_face {
+            return Err(format!(
+                "Expected {} facelets of color {}, found {}.",
+                facelets_per_face, face, count
+            )
+            .into());
+        }
+    }
+    Ok(facelets)
+}
+
+fn orbit_permutation_parity(pattern: &KPattern, orbit: &KPuzzleOrbitInfo) -> bool {
+    let pieces: Vec<u8> = (0..orbit.num_pieces)
+        .map(|i| pattern.get_piece(orbit, i))
+        .collect();
+    permutation_parity(&pieces)
+}
+
+/// Converts a 3x3x3 pattern to a 54-character facelet string, in the format
+/// used by Kociemba's two-phase solver (and many robot solvers and color
+/// scanners).
+///
+/// The faces are listed in the order `URFDLB`, and each facelet is named by
+/// the face of the center with the same color. This means that patterns with
+/// rotated centers are described relative to their centers, and will not
+/// round-trip.
+///
+/// ```
+/// use cubing_core::{alg::Alg, puzzles::{cube3x3x3_kpuzzle, cube3x3x3_pattern_to_facelets}};
+///
+/// let pattern = cube3x3x3_kpuzzle()
+///     .default_pattern()
+///     .apply_alg(&"R".parse::<Alg>().unwrap())
+///     .unwrap();
+/// assert_eq!(
+///     cube3x3x3_pattern_to_facelets(&pattern).unwrap(),
+///     "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB"
+/// );
+/// ```
+pub fn cube3x3x3_pattern_to_facelets(pattern: &KPattern) -> Result<String, InvalidFaceletsError> {
+    let corners = lookup_orbit(pattern.kpuzzle(), "3x3x3", "CORNERS")?;
+    let edges = lookup_orbit(pattern.kpuzzle(), "3x3x3", "EDGES")?;
+    let centers = lookup_orbit(pattern.kpuzzle(), "3x3x3", "CENTERS")?;
+
+    // Maps the color of each center (i.e. its face in the default pattern) to its current face.
+    let mut face_mapping = FACES;
+    for (i, facelet_index) in CUBE3X3X3_CENTER_FACELETS.iter().enumerate() {
+        let piece = pattern.get_piece(centers, i as u8) as usize;
+        let Some(piece_facelet_index) = CUBE3X3X3_CENTER_FACELETS.get(piece) else {
+            return Err("Unknown piece in orbit: CENTERS".into());
+        };
+        face_mapping[*piece_facelet_index / 9] = face_of_facelet(*facelet_index, 9);
+    }
+    let face_mapping =
+        |face: char| face_mapping[FACES.iter().position(|f| *f == face).expect("Invalid face")];
+
+    let mut facelets = ['?'; 54];
+    for (i, facelet_index) in CUBE3X3X3_CENTER_FACELETS.iter().enumerate() {
+        let piece = pattern.get_piece(centers, i as u8) as usize;
+        facelets[*facelet_index] =
+            face_mapping(face_of_facelet(CUBE3X3X3_CENTER_FACELETS[piece], 9));
+    }
+    write_orbit(
+        pattern,
+        corners,
+        &CUBE3X3X3_CORNER_FACELETS,
+        9,
+        face_mapping,
+        &mut facelets,
+    )?;
+    write_orbit(
+        pattern,
+        edges,
+        &CUBE3X3X3_EDGE_FACELETS,
+        9,
+        face_mapping,
+        &mut facelets,
+    )?;
+    Ok(facelets.iter().collect())
+}
+
+/// Converts a 54-character facelet string (see
+/// [`cube3x3x3_pattern_to_facelets`]) to a 3x3x3 pattern.
+///
+/// Returns an error if the facelets do not describe a cube that is reachable
+/// from the solved cube (e.g. due to a mis-scanned color or a single twisted
+/// corner).
+pub fn cube3x3x3_pattern_from_facelets(
+    facelet_string: &str,
+) -> Result<KPattern, InvalidFaceletsError> {
+    let facelets = parse_facelets(facelet_string, 9)?;
+    for (facelet_index, face) in [4, 13, 22, 31, 40, 49].iter().zip(FACES) {
+        if facelets[*facelet_index] != face {
+            return Err(format!(
+                "Expected center facelet {} on the {} face, found {}.",
+                face, face, facelets[*facelet_index]
+            )
+            .into());
+        }
+    }
+
+    let kpuzzle = cube3x3x3_kpuzzle();
+    let mut pattern = kpuzzle.default_pattern();
+    let corners = lookup_orbit(kpuzzle, "3x3x3", "CORNERS")?;
+    let edges = lookup_orbit(kpuzzle, "3x3x3", "EDGES")?;
+    read_orbit(
+        &mut pattern,
+        corners,
+        &CUBE3X3X3_CORNER_FACELETS,
+        9,
+        &facelets,
+        "corner",
+    )?;
+    read_orbit(
+        &mut pattern,
+        edges,
+        &CUBE3X3X3_EDGE_FACELETS,
+        9,
+        &facelets,
+        "edge",
+    )?;
+    if orbit_permutation_parity(&pattern, corners) != orbit_permutation_parity(&pattern, edges) {
+        return Err("Invalid permutation parity (two pieces are swapped).".into());
+    }
+    Ok(pattern)
+}
+
+/// Converts a 2x2x2 pattern to a 24-character facelet string, using the same format as
+/// [`cube3x3x3_pattern_to_facelets`] (with 4 facelets per face).
+///
+/// Since the 2x2x2 has no centers, each facelet is named by its face in the default pattern.
+pub fn cube2x2x2_pattern_to_facelets(pattern: &KPattern) -> Result<String, InvalidFaceletsError> {
+    let corners = lookup_orbit(pattern.kpuzzle(), "2x2x2", "CORNERS")?;
+    let mut facelets = ['?'; 24];
+    write_orbit(
+        pattern,
+        corners,
+        &CUBE2X2X2_CORNER_FACELETS,
+        4,
+        |face| face,
+        &mut facelets,
+    )?;
+    Ok(facelets.iter().collect())
+}
+
+/// Converts a 24-character facelet string (see [`cube2x2x2_pattern_to_facelets`]) to a 2x2x2 pattern.
+pub fn cube2x2x2_pattern_from_facelets(
+    facelet_string: &str,
+) -> Result<KPattern, InvalidFaceletsError> {
+    let facelets = parse_facelets(facelet_string, 4)?;
+    let kpuzzle = cube2x2x2_kpuzzle();
+    let mut pattern = kpuzzle.default_pattern();
+    let corners = lookup_orbit(kpuzzle, "2x2x2", "CORNERS")?;
+    read_orbit(
+        &mut pattern,
+        corners,
+        &CUBE2X2X2_CORNER_FACELETS,
+        4,
+        &facelets,
+        "corner",
+    )?;
+    Ok(pattern)
+}
+
+/// The sticker of a piece position that a facelet belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FaceletSticker {
+    pub orbit_name: &'static str,
+    pub position: u8,
+    /// The index of the sticker, in the order of the (Reid order) piece name.
+    pub sticker: u8,
+    pub num_stickers: u8,
+}
+
+/// Returns the facelets of a 3x3x3 or 2x2x2 pattern (as face letters, in `URFDLB` face order)
+/// and the number of facelets along each edge of a face.
+pub(crate) fn pattern_facelets(
+    pattern: &KPattern,
+) -> Result<(Vec<char>, usize), InvalidFaceletsError> {
+    match pattern.kpuzzle().definition().name.as_str() {
+        "3x3x3" => Ok((cube3x3x3_pattern_to_facelets(pattern)?.chars().collect(), 3)),
+        "2x2x2" => Ok((cube2x2x2_pattern_to_facelets(pattern)?.chars().collect(), 2)),
+        name => Err(format!("Facelets are not supported for puzzle: {}", name).into()),
+    }
+}
+
+/// Returns the sticker of each facelet, for the facelets returned by [`pattern_facelets`].
+pub(crate) fn facelet_stickers(facelets_per_face: usize) -> Vec<FaceletSticker> {
+    fn add_orbit<const N: usize>(
+        facelet_stickers: &mut [Option<FaceletSticker>],
+        orbit_name: &'static str,
+        position_facelets: &[[usize; N]],
+    ) {
+        for (position, facelet_indices) in position_facelets.iter().enumerate() {
+            for (sticker, facelet_index) in facelet_indices.iter().enumerate() {
+                facelet_stickers[*facelet_index] = Some(FaceletSticker {
+                    orbit_name,
+                    position: position as u8,
+                    sticker: sticker as u8,
+                    num_stickers: N as u8,
+                });
+            }
+        }
+    }
+
+    let mut facelet_stickers = vec![None; FACES.len() * facelets_per_face * facelets_per_face];
+    if facelets_per_face == 3 {
+        add_orbit(&mut facelet_stickers, "CORNERS", &CUBE3X3X3_CORNER_FACELETS);
+        add_orbit(&mut facelet_stickers, "EDGES", &CUBE3X3X3_EDGE_FACELETS);
+        add_orbit(
+            &mut facelet_stickers,
+            "CENTERS",
+            &CUBE3X3X3_CENTER_FACELETS.map(|facelet_index| [facelet_index]),
+        );
+    } else {
+        add_orbit(&mut facelet_stickers, "CORNERS", &CUBE2X2X2_CORNER_FACELETS);
+    }
+    facelet_stickers
+        .into_iter()
+        .map(|facelet_sticker| facelet_sticker.expect("Missing facelet"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alg::Alg,
+        kpuzzle::KPattern,
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle},
+    };
+
+    use super::{
+        cube2x2x2_pattern_from_facelets, cube2x2x2_pattern_to_facelets,
+        cube3x3x3_pattern_from_facelets, cube3x3x3_pattern_to_facelets,
+    };
+
+    const SOLVED: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+    fn pattern3x3x3(alg: &str) -> KPattern {
+        cube3x3x3_kpuzzle()
+            .default_pattern()
+            .apply_alg(&alg.parse::<Alg>().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn cube3x3x3_facelets() {
+        assert_eq!(
+            cube3x3x3_pattern_to_facelets(&pattern3x3x3("")).unwrap(),
+            SOLVED
+        );
+        assert_eq!(
+            cube3x3x3_pattern_to_facelets(&pattern3x3x3("U")).unwrap(),
+            "UUUUUUUUUBBBRRRRRRRRRFFFFFFDDDDDDDDDFFFLLLLLLLLLBBBBBB"
+        );
+        assert_eq!(
+            cube3x3x3_pattern_to_facelets(&pattern3x3x3("F")).unwrap(),
+            "UUUUUULLLURRURRURRFFFFFFFFFRRRDDDDDDLLDLLDLLDBBBBBBBBB"
+        );
+        // Rotations are described relative to the centers.
+        assert_eq!(
+            cube3x3x3_pattern_to_facelets(&pattern3x3x3("x y")).unwrap(),
+            SOLVED
+        );
+
+        let scramble = "D2 F' R2 U' B2 L D' R2 F2 U L2 B' D F2 R' U2 B L' D'";
+        let pattern = pattern3x3x3(scramble);
+        let facelets = cube3x3x3_pattern_to_facelets(&pattern).unwrap();
+        assert_eq!(cube3x3x3_pattern_from_facelets(&facelets).unwrap(), pattern);
+        assert_eq!(
+            cube3x3x3_pattern_from_facelets(SOLVED).unwrap(),
+            cube3x3x3_kpuzzle().default_pattern()
+        );
+    }
+
+    #[test]
+    fn invalid_3x3x3_facelets() {
+        let error = |facelets: &str| {
+            cube3x3x3_pattern_from_facelets(facelets)
+                .unwrap_err()
+                .description
+        };
+        assert_eq!(error("UUU"), "Expected 54 facelets, found 3.");
+        assert_eq!(error(&SOLVED.replace('B', "X")), "Invalid facelet color: X");
+        assert_eq!(
+            error(&SOLVED.replacen('U', "R", 1)),
+            "Expected 9 facelets of color U, found 8."
+        );
+        // Swapped centers.
+        assert_eq!(
+            error("UUUUFUUUURRRRRRRRRFFFFUFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"),
+            "Expected center facelet U on the U face, found F."
+        );
+        // Swapped stickers on the UF edge.
+        assert_eq!(
+            error("UUUUUUUFURRRRRRRRRFUFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"),
+            "Invalid edge orientation (a single edge is twisted)."
+        );
+        // Rotated stickers on the UFR corner.
+        assert_eq!(
+            error("UUUUUUUURFRRRRRRRRFFUFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"),
+            "Invalid corner orientation (a single corner is twisted)."
+        );
+        // Swapped UF and UR edges.
+        assert_eq!(
+            error("UUUUUUUUURFRRRRRRRFRFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"),
+            "Invalid permutation parity (two pieces are swapped)."
+        );
+        // Two UF edges.
+        assert_eq!(
+            error("UUUUUUUUURFRRRRRRRFFFFFRFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"),
+            "Duplicate edge: UF"
+        );
+    }
+
+    #[test]
+    fn cube2x2x2_facelets() {
+        let pattern = cube2x2x2_kpuzzle()
+            .default_pattern()
+            .apply_alg(&"R U' F2 R' U2 F".parse::<Alg>().unwrap())
+            .unwrap();
+        let facelets = cube2x2x2_pattern_to_facelets(&pattern).unwrap();
+        assert_eq!(cube2x2x2_pattern_from_facelets(&facelets).unwrap(), pattern);
+        assert_eq!(
+            cube2x2x2_pattern_to_facelets(
+                &cube2x2x2_kpuzzle()
+                    .default_pattern()
+                    .apply_alg(&"R".parse::<Alg>().unwrap())
+                    .unwrap()
+            )
+            .unwrap(),
+            "UFUFRRRRFDFDDBDBLLLLUBUB"
+        );
+        assert!(cube2x2x2_pattern_from_facelets("UUUURRRRFFFFDDDDLLLLBBBR").is_err());
+        assert!(cube2x2x2_pattern_to_facelets(&pattern3x3x3("")).is_err());
+    }
+}
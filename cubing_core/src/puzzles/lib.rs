@@ -2,3 +2,12 @@ use crate::kpuzzle_from_json_file;
 
 kpuzzle_from_json_file!(pub, cube3x3x3, "./3x3x3.kpuzzle.json");
 kpuzzle_from_json_file!(pub, cube2x2x2, "./2x2x2.kpuzzle.json");
+kpuzzle_from_json_file!(pub, cube4x4x4, "./4x4x4.kpuzzle.json");
+kpuzzle_from_json_file!(pub, cube5x5x5, "./5x5x5.kpuzzle.json");
+kpuzzle_from_json_file!(pub, cube6x6x6, "./6x6x6.kpuzzle.json");
+kpuzzle_from_json_file!(pub, cube7x7x7, "./7x7x7.kpuzzle.json");
+kpuzzle_from_json_file!(pub, pyraminx, "./pyraminx.kpuzzle.json");
+kpuzzle_from_json_file!(pub, skewb, "./skewb.kpuzzle.json");
+kpuzzle_from_json_file!(pub, megaminx, "./megaminx.kpuzzle.json");
+kpuzzle_from_json_file!(pub, square1, "./square1.kpuzzle.json");
+kpuzzle_from_json_file!(pub, clock, "./clock.kpuzzle.json");
@@ -0,0 +1,142 @@
+use crate::kpuzzle::KPattern;
+
+use super::{
+    facelets::{pattern_facelets, FACES},
+    InvalidFaceletsError,
+};
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// How to draw stickers in [`render_net`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NetStyle {
+    /// Colored blocks using ANSI escape codes, for terminals.
+    #[default]
+    Ansi,
+    /// The face letter (`U`, `R`, `F`, `D`, `L`, or `B`) of each sticker's color, for plain text.
+    Letters,
+}
+
+// The ANSI background color for the stickers of each face (in the standard color scheme).
+fn ansi_background(face: char) -> &'static str {
+    match face {
+        'U' => "\x1b[47m",
+        'R' => "\x1b[41m",
+        'F' => "\x1b[42m",
+        'D' => "\x1b[43m",
+        'L' => "\x1b[48;5;208m",
+        'B' => "\x1b[44m",
+        _ => "",
+    }
+}
+
+/// Draws the unfolded net of a cube pattern as text, with `U` above `L F R B` and `D` below:
+///
+/// ```text
+///     UUU
+///     UUU
+///     UUU
+/// LLL FFF RRR BBB
+/// LLL FFF RRR BBB
+/// LLL FFF RRR BBB
+///     DDD
+///     DDD
+///     DDD
+/// ```
+///
+/// This is supported for the 3x3x3 and 2x2x2, and uses the same colors as
+/// [`cube3x3x3_pattern_to_facelets`][`super::cube3x3x3_pattern_to_facelets`] (i.e. each sticker is named by the face
+/// of the center with the same color).
+///
+/// ```
+/// use cubing_core::{
+///     alg::Alg,
+///     puzzles::{cube2x2x2_kpuzzle, render_net, NetStyle},
+/// };
+///
+/// let pattern = cube2x2x2_kpuzzle()
+///     .default_pattern()
+///     .apply_alg(&"R".parse::<Alg>().unwrap())
+///     .unwrap();
+/// assert_eq!(
+///     render_net(&pattern, NetStyle::Letters).unwrap(),
+///     "   UF
+///    UF
+/// LL FD RR UB
+/// LL FD RR UB
+///    DB
+///    DB"
+/// );
+/// ```
+pub fn render_net(pattern: &KPattern, style: NetStyle) -> Result<String, InvalidFaceletsError> {
+    let (facelets, n) = pattern_facelets(pattern)?;
+    let face = |face: char| -> &[char] {
+        let face_index = FACES.iter().position(|f| *f == face).expect("Invalid face");
+        &facelets[face_index * n * n..(face_index + 1) * n * n]
+    };
+    let cell_width = match style {
+        NetStyle::Ansi => 2,
+        NetStyle::Letters => 1,
+    };
+    let render_row = |faces: &[char], row: usize| -> String {
+        faces
+            .iter()
+            .map(|f| {
+                face(*f)[row * n..(row + 1) * n]
+                    .iter()
+                    .map(|facelet| match style {
+                        NetStyle::Ansi => format!("{}  {}", ansi_background(*facelet), ANSI_RESET),
+                        NetStyle::Letters => facelet.to_string(),
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    let indent = " ".repeat(n * cell_width + 1);
+    let mut lines = vec![];
+    for row in 0..n {
+        lines.push(format!("{}{}", indent, render_row(&['U'], row)));
+    }
+    for row in 0..n {
+        lines.push(render_row(&['L', 'F', 'R', 'B'], row));
+    }
+    for row in 0..n {
+        lines.push(format!("{}{}", indent, render_row(&['D'], row)));
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alg::Alg, puzzles::cube3x3x3_kpuzzle, puzzles::megaminx_kpuzzle};
+
+    use super::{render_net, NetStyle};
+
+    #[test]
+    fn renders_nets() {
+        let pattern = cube3x3x3_kpuzzle()
+            .default_pattern()
+            .apply_alg(&"F".parse::<Alg>().unwrap())
+            .unwrap();
+        assert_eq!(
+            render_net(&pattern, NetStyle::Letters).unwrap(),
+            "    UUU
+    UUU
+    LLL
+LLD FFF URR BBB
+LLD FFF URR BBB
+LLD FFF URR BBB
+    RRR
+    DDD
+    DDD"
+        );
+
+        let ansi = render_net(&pattern, NetStyle::Ansi).unwrap();
+        assert_eq!(ansi.lines().count(), 9);
+        assert!(ansi.starts_with("       \x1b[47m  \x1b[0m"));
+        assert!(ansi.contains("\x1b[48;5;208m  \x1b[0m"));
+
+        assert!(render_net(&megaminx_kpuzzle().default_pattern(), NetStyle::Letters).is_err());
+    }
+}
@@ -0,0 +1,880 @@
+use std::{
+    collections::{HashMap, HashSet},
+    f64::consts::PI,
+};
+
+use crate::{
+    alg::{Move, MovePrefix, QuantumMove},
+    kpuzzle::{
+        InvalidDefinitionError, KPatternData, KPatternOrbitData, KPuzzle, KPuzzleDefinition,
+        KPuzzleOrbitDefinition, KPuzzleOrbitName, KTransformationData, KTransformationOrbitData,
+    },
+};
+
+type Vector = [f64; 3];
+type Matrix = [[f64; 3]; 3];
+type Key = [i64; 3];
+
+const EPSILON: f64 = 1e-9;
+const AREA_EPSILON: f64 = 1e-12;
+const IDENTITY: Matrix = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+fn dot(a: Vector, b: Vector) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn add(a: Vector, b: Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Vector, b: Vector) -> Vector {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: Vector, factor: f64) -> Vector {
+    a.map(|x| x * factor)
+}
+
+fn normalize(a: Vector) -> Vector {
+    scale(a, 1.0 / dot(a, a).sqrt())
+}
+
+fn apply(m: &Matrix, p: Vector) -> Vector {
+    m.map(|row| dot(row, p))
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut product = [[0.0; 3]; 3];
+    for (i, row) in product.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    product
+}
+
+fn transpose(m: &Matrix) -> Matrix {
+    let mut transposed = [[0.0; 3]; 3];
+    for (i, row) in m.iter().enumerate() {
+        for (j, entry) in row.iter().enumerate() {
+            transposed[j][i] = *entry;
+        }
+    }
+    transposed
+}
+
+/// A rotation by `angle` around the unit vector `axis`, counterclockwise when looking at the `axis` side.
+fn rotation(axis: Vector, angle: f64) -> Matrix {
+    let [x, y, z] = axis;
+    let (s, c) = angle.sin_cos();
+    let t = 1.0 - c;
+    [
+        [c + x * x * t, x * y * t - z * s, x * z * t + y * s],
+        [y * x * t + z * s, c + y * y * t, y * z * t - x * s],
+        [z * x * t - y * s, z * y * t + x * s, c + z * z * t],
+    ]
+}
+
+fn key(p: Vector) -> Key {
+    p.map(|x| (x * 1e6).round() as i64)
+}
+
+fn matrix_key(m: &Matrix) -> [Key; 3] {
+    m.map(key)
+}
+
+fn polygon_area_and_centroid(polygon: &[Vector]) -> (f64, Vector) {
+    let mut area = 0.0;
+    let mut weighted = [0.0; 3];
+    for i in 1..polygon.len().saturating_sub(1) {
+        let a = polygon[0];
+        let b = polygon[i];
+        let c = polygon[i + 1];
+        let cross_product = cross(sub(b, a), sub(c, a));
+        let triangle_area = dot(cross_product, cross_product).sqrt() / 2.0;
+        area += triangle_area;
+        weighted = add(weighted, scale(add(add(a, b), c), triangle_area / 3.0));
+    }
+    if area <= AREA_EPSILON {
+        return (area, [0.0; 3]);
+    }
+    (area, scale(weighted, 1.0 / area))
+}
+
+/// Splits a convex polygon by the plane `normal · p = distance`.
+fn split_polygon(polygon: Vec<Vector>, normal: Vector, distance: f64) -> Vec<Vec<Vector>> {
+    let sides: Vec<f64> = polygon.iter().map(|p| dot(*p, normal) - distance).collect();
+    if sides.iter().all(|s| *s > -EPSILON) || sides.iter().all(|s| *s < EPSILON) {
+        return vec![polygon];
+    }
+    let mut above = vec![];
+    let mut below = vec![];
+    for i in 0..polygon.len() {
+        let j = (i + 1) % polygon.len();
+        let (p, s) = (polygon[i], sides[i]);
+        let (q, t) = (polygon[j], sides[j]);
+        if s > -EPSILON {
+            above.push(p);
+        }
+        if s < EPSILON {
+            below.push(p);
+        }
+        if (s > EPSILON && t < -EPSILON) || (s < -EPSILON && t > EPSILON) {
+            let intersection = add(p, scale(sub(q, p), s / (s - t)));
+            above.push(intersection);
+            below.push(intersection);
+        }
+    }
+    [above, below]
+        .into_iter()
+        .filter(|part| polygon_area_and_centroid(part).0 > AREA_EPSILON)
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Tetrahedron,
+    Cube,
+    Octahedron,
+    Dodecahedron,
+}
+
+impl Shape {
+    /// Face names and (unit) normals. Every face is at distance 1 from the center.
+    fn faces(&self) -> Vec<(&'static str, Vector)> {
+        let third = 1.0 / 3.0;
+        let h = 8f64.sqrt() / 3.0;
+        let w = 3f64.sqrt() / 2.0 * h;
+        match self {
+            Shape::Tetrahedron => vec![
+                ("F", [0.0, third, h]),
+                ("L", [-w, third, -h / 2.0]),
+                ("R", [w, third, -h / 2.0]),
+                ("D", [0.0, -1.0, 0.0]),
+            ],
+            Shape::Cube => vec![
+                ("U", [0.0, 1.0, 0.0]),
+                ("D", [0.0, -1.0, 0.0]),
+                ("F", [0.0, 0.0, 1.0]),
+                ("B", [0.0, 0.0, -1.0]),
+                ("R", [1.0, 0.0, 0.0]),
+                ("L", [-1.0, 0.0, 0.0]),
+            ],
+            Shape::Octahedron => vec![
+                ("U", [0.0, 1.0, 0.0]),
+                ("F", [0.0, -third, h]),
+                ("R", [w, third, h / 2.0]),
+                ("L", [-w, third, h / 2.0]),
+                ("B", [0.0, third, -h]),
+                ("BR", [w, -third, -h / 2.0]),
+                ("BL", [-w, -third, -h / 2.0]),
+                ("D", [0.0, -1.0, 0.0]),
+            ],
+            Shape::Dodecahedron => {
+                let (sin, cos) = (2.0 / 5f64.sqrt(), 1.0 / 5f64.sqrt());
+                let ring = |name, azimuth: f64, height: f64| {
+                    let (s, c) = azimuth.to_radians().sin_cos();
+                    (name, [sin * s, height, sin * c])
+                };
+                vec![
+                    ("U", [0.0, 1.0, 0.0]),
+                    ring("F", 0.0, cos),
+                    ring("L", 288.0, cos),
+                    ring("BL", 216.0, cos),
+                    ring("BR", 144.0, cos),
+                    ring("R", 72.0, cos),
+                    ring("FR", 36.0, -cos),
+                    ring("FL", 324.0, -cos),
+                    ring("DL", 252.0, -cos),
+                    ring("B", 180.0, -cos),
+                    ring("DR", 108.0, -cos),
+                    ("D", [0.0, -1.0, 0.0]),
+                ]
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CutType {
+    Face,
+    Vertex,
+    Edge,
+}
+
+const ORBIT_KINDS: [(CutType, &str); 3] = [
+    (CutType::Edge, "EDGES"),
+    (CutType::Vertex, "CORNERS"),
+    (CutType::Face, "CENTERS"),
+];
+
+/// An axis through the center of a face, a vertex, or the middle of an edge.
+struct Axis {
+    name: String,
+    direction: Vector,
+    order: usize,
+}
+
+struct Polyhedron {
+    face_polygons: Vec<Vec<Vector>>,
+    face_axes: Vec<Axis>,
+    vertex_axes: Vec<Axis>,
+    edge_axes: Vec<Axis>,
+}
+
+impl Polyhedron {
+    fn new(shape: Shape) -> Self {
+        let faces = shape.faces();
+        let normals: Vec<Vector> = faces.iter().map(|(_, normal)| normalize(*normal)).collect();
+
+        // Every vertex is the intersection of (at least) 3 face planes.
+        let mut vertices: Vec<Vector> = vec![];
+        for i in 0..normals.len() {
+            for j in (i + 1)..normals.len() {
+                for k in (j + 1)..normals.len() {
+                    let (a, b, c) = (normals[i], normals[j], normals[k]);
+                    let determinant = dot(a, cross(b, c));
+                    if determinant.abs() < EPSILON {
+                        continue;
+                    }
+                    let vertex = scale(
+                        add(add(cross(b, c), cross(c, a)), cross(a, b)),
+                        1.0 / determinant,
+                    );
+                    if normals.iter().all(|n| dot(*n, vertex) < 1.0 + EPSILON)
+                        && !vertices.iter().any(|v| key(*v) == key(vertex))
+                    {
+                        vertices.push(vertex);
+                    }
+                }
+            }
+        }
+        let on_face =
+            |face: usize, vertex: Vector| (dot(normals[face], vertex) - 1.0).abs() < EPSILON;
+        let face_names = |indices: Vec<usize>| {
+            indices
+                .into_iter()
+                .map(|face| faces[face].0)
+                .collect::<String>()
+        };
+
+        let mut face_polygons = vec![];
+        let mut face_axes = vec![];
+        for (face, (name, _)) in faces.iter().enumerate() {
+            let normal = normals[face];
+            let mut polygon: Vec<Vector> = vertices
+                .iter()
+                .filter(|v| on_face(face, **v))
+                .cloned()
+                .collect();
+            let reference = sub(polygon[0], normal);
+            let other = cross(normal, reference);
+            polygon.sort_by(|p, q| {
+                let angle = |v: Vector| {
+                    let v = sub(v, normal);
+                    dot(v, other).atan2(dot(v, reference))
+                };
+                angle(*p).total_cmp(&angle(*q))
+            });
+            face_axes.push(Axis {
+                name: (*name).to_owned(),
+                direction: normal,
+                order: polygon.len(),
+            });
+            face_polygons.push(polygon);
+        }
+
+        let vertex_axes = vertices
+            .iter()
+            .map(|vertex| {
+                let adjacent: Vec<usize> = (0..faces.len())
+                    .filter(|face| on_face(*face, *vertex))
+                    .collect();
+                Axis {
+                    order: adjacent.len(),
+                    name: face_names(adjacent),
+                    direction: normalize(*vertex),
+                }
+            })
+            .collect();
+
+        let mut edge_axes = vec![];
+        for i in 0..faces.len() {
+            for j in (i + 1)..faces.len() {
+                let shared: Vec<Vector> = vertices
+                    .iter()
+                    .filter(|v| on_face(i, **v) && on_face(j, **v))
+                    .cloned()
+                    .collect();
+                if shared.len() == 2 {
+                    edge_axes.push(Axis {
+                        name: face_names(vec![i, j]),
+                        direction: normalize(add(shared[0], shared[1])),
+                        order: 2,
+                    });
+                }
+            }
+        }
+
+        Self {
+            face_polygons,
+            face_axes,
+            vertex_axes,
+            edge_axes,
+        }
+    }
+
+    fn axes(&self, cut_type: CutType) -> &[Axis] {
+        match cut_type {
+            CutType::Face => &self.face_axes,
+            CutType::Vertex => &self.vertex_axes,
+            CutType::Edge => &self.edge_axes,
+        }
+    }
+
+    /// All rotations of the polyhedron onto itself.
+    fn symmetries(&self) -> Vec<Matrix> {
+        let generators: Vec<Matrix> = self
+            .face_axes
+            .iter()
+            .chain(self.vertex_axes.iter())
+            .map(|axis| rotation(axis.direction, 2.0 * PI / axis.order as f64))
+            .collect();
+        let mut seen = HashSet::from([matrix_key(&IDENTITY)]);
+        let mut symmetries = vec![IDENTITY];
+        let mut i = 0;
+        while i < symmetries.len() {
+            for generator in &generators {
+                let product = multiply(generator, &symmetries[i]);
+                if seen.insert(matrix_key(&product)) {
+                    symmetries.push(product);
+                }
+            }
+            i += 1;
+        }
+        symmetries
+    }
+}
+
+struct Description {
+    shape: Shape,
+    cuts: Vec<(CutType, f64)>,
+}
+
+impl TryFrom<&str> for Description {
+    type Error = InvalidDefinitionError;
+
+    fn try_from(description: &str) -> Result<Self, Self::Error> {
+        let mut tokens = description.split_whitespace();
+        let shape = match tokens.next() {
+            Some("t") => Shape::Tetrahedron,
+            Some("c") => Shape::Cube,
+            Some("o") => Shape::Octahedron,
+            Some("d") => Shape::Dodecahedron,
+            Some(shape) => {
+                return Err(format!("Unsupported puzzle geometry shape: {}", shape).into())
+            }
+            None => return Err("Empty puzzle geometry description".into()),
+        };
+        let mut cuts = vec![];
+        while let Some(cut_type) = tokens.next() {
+            let cut_type = match cut_type {
+                "f" => CutType::Face,
+                "v" => CutType::Vertex,
+                "e" => CutType::Edge,
+                _ => return Err(format!("Invalid puzzle geometry cut type: {}", cut_type).into()),
+            };
+            let distance = tokens
+                .next()
+                .and_then(|distance| distance.parse::<f64>().ok())
+                .filter(|distance| distance.is_finite())
+                .ok_or_else(|| {
+                    InvalidDefinitionError::from("Expected a distance after each cut type")
+                })?;
+            cuts.push((cut_type, distance));
+        }
+        if cuts.is_empty() {
+            return Err("Puzzle geometry description has no cuts".into());
+        }
+        Ok(Self { shape, cuts })
+    }
+}
+
+struct Sticker {
+    face: usize,
+    centroid: Vector,
+    piece: usize,
+}
+
+struct PieceMove {
+    family: String,
+    layer: Option<u32>,
+    rotation: Matrix,
+    /// `(from, to)` for each moved piece.
+    piece_mapping: Vec<(usize, usize)>,
+}
+
+struct Geometry {
+    stickers: Vec<Sticker>,
+    sticker_lookup: HashMap<Key, usize>,
+    pieces: Vec<Vec<usize>>,
+}
+
+impl Geometry {
+    fn new(polyhedron: &Polyhedron, planes: &[(Vector, f64)]) -> Self {
+        let mut stickers = vec![];
+        let mut sticker_lookup = HashMap::new();
+        let mut pieces: Vec<Vec<usize>> = vec![];
+        let mut piece_lookup: HashMap<Vec<i8>, usize> = HashMap::new();
+        for (face, polygon) in polyhedron.face_polygons.iter().enumerate() {
+            let mut polygons = vec![polygon.clone()];
+            for (normal, distance) in planes {
+                polygons = polygons
+                    .into_iter()
+                    .flat_map(|polygon| split_polygon(polygon, *normal, *distance))
+                    .collect();
+            }
+            for polygon in polygons {
+                let centroid = polygon_area_and_centroid(&polygon).1;
+                let region: Vec<i8> = planes
+                    .iter()
+                    .map(|(normal, distance)| {
+                        let side = dot(centroid, *normal) - distance;
+                        (side > EPSILON) as i8 - (side < -EPSILON) as i8
+                    })
+                    .collect();
+                let piece = *piece_lookup.entry(region).or_insert_with(|| {
+                    pieces.push(vec![]);
+                    pieces.len() - 1
+                });
+                pieces[piece].push(stickers.len());
+                sticker_lookup.insert(key(centroid), stickers.len());
+                stickers.push(Sticker {
+                    face,
+                    centroid,
+                    piece,
+                });
+            }
+        }
+        Self {
+            stickers,
+            sticker_lookup,
+            pieces,
+        }
+    }
+
+    fn piece_centroid(&self, piece: usize) -> Vector {
+        let stickers = &self.pieces[piece];
+        let sum = stickers.iter().fold([0.0; 3], |sum, sticker| {
+            add(sum, self.stickers[*sticker].centroid)
+        });
+        scale(sum, 1.0 / stickers.len() as f64)
+    }
+
+    fn rotate_sticker(&self, sticker: usize, rotation: &Matrix) -> Option<usize> {
+        self.sticker_lookup
+            .get(&key(apply(rotation, self.stickers[sticker].centroid)))
+            .cloned()
+    }
+
+    /// The piece that `piece` lands on after rotating it, if the rotation maps it onto a single piece.
+    fn rotate_piece(&self, piece: usize, rotation: &Matrix) -> Option<usize> {
+        let mut rotated = None;
+        for sticker in &self.pieces[piece] {
+            let new_piece = self.stickers[self.rotate_sticker(*sticker, rotation)?].piece;
+            if rotated.is_some_and(|rotated| rotated != new_piece) {
+                return None;
+            }
+            rotated = Some(new_piece);
+        }
+        rotated.filter(|rotated| self.pieces[*rotated].len() == self.pieces[piece].len())
+    }
+
+    fn rotate_pieces(
+        &self,
+        pieces: &[usize],
+        rotation: &Matrix,
+        family: &str,
+    ) -> Result<Vec<(usize, usize)>, InvalidDefinitionError> {
+        pieces
+            .iter()
+            .map(|piece| match self.rotate_piece(*piece, rotation) {
+                Some(new_piece) if pieces.contains(&new_piece) => Ok((*piece, new_piece)),
+                _ => Err(format!("Move would jumble the puzzle: {}", family).into()),
+            })
+            .collect()
+    }
+}
+
+struct GeometryOrbit {
+    name: String,
+    pieces: Vec<usize>,
+    /// For each piece: a symmetry that takes the first piece of the orbit to it.
+    frames: Vec<Matrix>,
+    /// Powers of a (clockwise) generator of the rotations that keep the first piece in place.
+    orientation_lookup: HashMap<[Key; 3], u8>,
+    default_pieces: Vec<u8>,
+    orientation_mod: Option<Vec<u8>>,
+}
+
+fn find_root(parents: &mut [usize], piece: usize) -> usize {
+    let mut root = piece;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[piece] = root;
+    root
+}
+
+fn key_move(family: &str, layer: Option<u32>) -> Move {
+    Move {
+        quantum: QuantumMove::new(family, layer.map(MovePrefix::from)).into(),
+        amount: 1,
+    }
+}
+
+/// Generates a puzzle definition from a puzzle geometry description in the
+/// same format as cubing.js, e.g. `"c f 0.333"` for a 3x3x3 cube, `"o f
+/// 0.333"` for the Face-Turning Octahedron, or `"t v 0 v 1 v 2"` for the Master
+/// Pyraminx.
+///
+/// The description consists of a shape followed by any number of cuts:
+///
+/// - Shapes: `t` (tetrahedron), `c` (cube), `o` (octahedron), `d` (dodecahedron).
+/// - Cuts: `f`, `v`, or `e` (planes perpendicular to every face, vertex, or edge
+///   axis) followed by the distance of the planes from the center. Faces are
+///   at distance 1.
+///
+/// Faces are named like the faces of the corresponding WCA puzzle (e.g. `U`,
+/// `F`, `BR`, …). Vertex and edge axes are named by concatenating the names of
+/// the faces they touch (e.g. `UFR` or `UF` on the cube). For every axis of a cut
+/// type in the description, this defines:
+///
+/// - A move for each layer counting from that side: `UFR`, `2UFR`, …
+/// - A rotation of the entire puzzle: `UFRv`.
+///
+/// Pieces that look the same in the solved state are interchangeable in the default pattern.
+pub fn puzzle_geometry_kpuzzle_definition(
+    description: &str,
+) -> Result<KPuzzleDefinition, InvalidDefinitionError> {
+    let Description { shape, cuts } = description.try_into()?;
+    let polyhedron = Polyhedron::new(shape);
+    let symmetries = polyhedron.symmetries();
+
+    let mut planes: Vec<(Vector, f64)> = vec![];
+    let mut cut_types: Vec<CutType> = vec![];
+    for (cut_type, distance) in &cuts {
+        if !cut_types.contains(cut_type) {
+            cut_types.push(*cut_type);
+        }
+        for axis in polyhedron.axes(*cut_type) {
+            let plane = (axis.direction, *distance);
+            if !planes.iter().any(|(normal, distance)| {
+                let same = key(*normal) == key(plane.0) && (distance - plane.1).abs() < EPSILON;
+                let opposite = key(*normal) == key(scale(plane.0, -1.0))
+                    && (distance + plane.1).abs() < EPSILON;
+                same || opposite
+            }) {
+                planes.push(plane);
+            }
+        }
+    }
+    let geometry = Geometry::new(&polyhedron, &planes);
+    // A piece that contains the center (with stickers on every side) can't be
+    // turned by itself, so we leave it out like the core of a cube.
+    let is_core = |piece: usize| {
+        let center = geometry.piece_centroid(piece);
+        dot(center, center) < EPSILON
+    };
+    let movable_pieces: Vec<usize> = (0..geometry.pieces.len())
+        .filter(|piece| !is_core(*piece))
+        .collect();
+
+    let mut families = HashSet::new();
+    let mut piece_moves = vec![];
+    for cut_type in cut_types {
+        for axis in polyhedron.axes(cut_type) {
+            if polyhedron
+                .face_axes
+                .iter()
+                .any(|face| face.name == axis.name)
+                && cut_type != CutType::Face
+                || !families.insert(axis.name.clone())
+            {
+                return Err(format!("Ambiguous puzzle geometry axis name: {}", axis.name).into());
+            }
+            let mut cut_distances: Vec<f64> = planes
+                .iter()
+                .filter_map(|(normal, distance)| {
+                    let alignment = dot(*normal, axis.direction);
+                    if alignment > 1.0 - EPSILON {
+                        Some(*distance)
+                    } else if alignment < -1.0 + EPSILON {
+                        Some(-distance)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            cut_distances.sort_by(|a, b| b.total_cmp(a));
+            cut_distances.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+
+            let mut layers: Vec<Vec<usize>> = vec![vec![]; cut_distances.len() + 1];
+            for piece in 0..geometry.pieces.len() {
+                let height = dot(geometry.piece_centroid(piece), axis.direction);
+                let layer = cut_distances
+                    .iter()
+                    .filter(|distance| height < **distance)
+                    .count();
+                layers[layer].push(piece);
+            }
+            layers.retain(|layer| !layer.is_empty());
+
+            let rotation = rotation(axis.direction, -2.0 * PI / axis.order as f64);
+            if layers.len() > 1 {
+                for (i, layer) in layers.iter().enumerate() {
+                    if layer.iter().any(|piece| is_core(*piece)) {
+                        continue;
+                    }
+                    piece_moves.push(PieceMove {
+                        family: axis.name.clone(),
+                        layer: (i > 0).then_some(i as u32 + 1),
+                        rotation,
+                        piece_mapping: geometry.rotate_pieces(layer, &rotation, &axis.name)?,
+                    });
+                }
+            }
+            let family = format!("{}v", axis.name);
+            piece_moves.push(PieceMove {
+                piece_mapping: geometry.rotate_pieces(&movable_pieces, &rotation, &family)?,
+                family,
+                layer: None,
+                rotation,
+            });
+        }
+    }
+
+    // Pieces that can reach each other using moves form an orbit.
+    let mut parents: Vec<usize> = (0..geometry.pieces.len()).collect();
+    for piece_move in &piece_moves {
+        for (from, to) in &piece_move.piece_mapping {
+            let (from, to) = (find_root(&mut parents, *from), find_root(&mut parents, *to));
+            parents[from.max(to)] = from.min(to);
+        }
+    }
+    let mut orbit_pieces: Vec<Vec<usize>> = vec![];
+    for piece in movable_pieces {
+        let root = find_root(&mut parents, piece);
+        if root == piece {
+            orbit_pieces.push(vec![]);
+        }
+        let position = orbit_pieces
+            .iter()
+            .position(|pieces| pieces.first().is_none_or(|first| *first == root))
+            .expect("Every orbit root should come before the rest of its orbit.");
+        orbit_pieces[position].push(piece);
+    }
+
+    let mut orbits = vec![];
+    for pieces in orbit_pieces {
+        let first = pieces[0];
+        let center = geometry.piece_centroid(first);
+        let mut frames: Vec<Option<Matrix>> = vec![None; pieces.len()];
+        let mut stabilizer = vec![];
+        for symmetry in &symmetries {
+            let Some(image) = geometry.rotate_piece(first, symmetry) else {
+                continue;
+            };
+            if image == first {
+                stabilizer.push(*symmetry);
+            }
+            if let Some(index) = pieces.iter().position(|piece| *piece == image) {
+                frames[index].get_or_insert(*symmetry);
+            }
+        }
+        let frames: Vec<Matrix> = frames
+            .into_iter()
+            .map(|frame| frame.expect("Every piece in an orbit should be a rotation of the first."))
+            .collect();
+
+        let num_orientations = stabilizer.len();
+        if pieces.len() > u8::MAX as usize || num_orientations > u8::MAX as usize {
+            return Err("Puzzle geometry has too many pieces".into());
+        }
+        let generator = rotation(normalize(center), -2.0 * PI / num_orientations as f64);
+        let mut orientation_lookup = HashMap::new();
+        let mut power = IDENTITY;
+        for orientation in 0..num_orientations {
+            orientation_lookup.insert(matrix_key(&power), orientation as u8);
+            power = multiply(&generator, &power);
+        }
+
+        let mut first_stickers = geometry.pieces[first].clone();
+        first_stickers.sort_by_key(|sticker| geometry.stickers[*sticker].face);
+        let num_visible_orientations = (1..num_orientations)
+            .find(|num_visible_orientations| {
+                let power = (0..*num_visible_orientations)
+                    .fold(IDENTITY, |power, _| multiply(&generator, &power));
+                num_orientations % num_visible_orientations == 0
+                    && first_stickers
+                        .iter()
+                        .all(|sticker| geometry.rotate_sticker(*sticker, &power) == Some(*sticker))
+            })
+            .unwrap_or(num_orientations);
+
+        let mut appearances: Vec<Vec<usize>> = vec![];
+        let mut default_pieces = vec![];
+        for frame in &frames {
+            let appearance: Vec<usize> = first_stickers
+                .iter()
+                .map(|sticker| {
+                    let sticker = geometry
+                        .rotate_sticker(*sticker, frame)
+                        .expect("Symmetries should map stickers onto stickers.");
+                    geometry.stickers[sticker].face
+                })
+                .collect();
+            let piece = match appearances.iter().position(|other| *other == appearance) {
+                Some(piece) => piece,
+                None => {
+                    appearances.push(appearance);
+                    appearances.len() - 1
+                }
+            };
+            default_pieces.push(piece as u8);
+        }
+
+        // Orbits are named after the kind of axis that the pieces are closest to.
+        let direction = normalize(center);
+        let closest = |cut_type: CutType| {
+            polyhedron
+                .axes(cut_type)
+                .iter()
+                .map(|axis| dot(axis.direction, direction))
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        let kind = (0..ORBIT_KINDS.len())
+            .max_by(|a, b| closest(ORBIT_KINDS[*a].0).total_cmp(&closest(ORBIT_KINDS[*b].0)))
+            .expect("There should be at least one kind of orbit.");
+        orbits.push((
+            kind,
+            GeometryOrbit {
+                name: String::new(),
+                orientation_mod: (num_visible_orientations < num_orientations)
+                    .then(|| vec![num_visible_orientations as u8; pieces.len()]),
+                pieces,
+                frames,
+                orientation_lookup,
+                default_pieces,
+            },
+        ));
+    }
+    orbits.sort_by_key(|(kind, orbit)| (*kind, orbit.pieces[0]));
+    let mut kind_counts = [0; ORBIT_KINDS.len()];
+    let orbits: Vec<GeometryOrbit> = orbits
+        .into_iter()
+        .map(|(kind, mut orbit)| {
+            let base = ORBIT_KINDS[kind].1;
+            kind_counts[kind] += 1;
+            orbit.name = match kind_counts[kind] {
+                1 => base.to_owned(),
+                count => format!("{}{}", base, count),
+            };
+            orbit
+        })
+        .collect();
+
+    let mut piece_locations = vec![(0, 0); geometry.pieces.len()];
+    for (orbit_index, orbit) in orbits.iter().enumerate() {
+        for (index, piece) in orbit.pieces.iter().enumerate() {
+            piece_locations[*piece] = (orbit_index, index);
+        }
+    }
+
+    let mut orbit_definitions = vec![];
+    let mut default_pattern = KPatternData::new();
+    for orbit in &orbits {
+        let orbit_name = KPuzzleOrbitName(orbit.name.clone());
+        orbit_definitions.push(KPuzzleOrbitDefinition {
+            orbit_name: orbit_name.clone(),
+            num_pieces: orbit.pieces.len() as u8,
+            num_orientations: orbit.orientation_lookup.len() as u8,
+        });
+        default_pattern.insert(
+            orbit_name,
+            KPatternOrbitData {
+                pieces: orbit.default_pieces.clone(),
+                orientation: vec![0; orbit.pieces.len()],
+                orientation_mod: orbit.orientation_mod.clone(),
+            },
+        );
+    }
+
+    let mut moves = HashMap::new();
+    for piece_move in piece_moves {
+        let mut transformation: Vec<KTransformationOrbitData> = orbits
+            .iter()
+            .map(|orbit| KTransformationOrbitData {
+                permutation: (0..orbit.pieces.len() as u8).collect(),
+                orientation_delta: vec![0; orbit.pieces.len()],
+            })
+            .collect();
+        for (from, to) in piece_move.piece_mapping {
+            let (orbit_index, from_index) = piece_locations[from];
+            let (_, to_index) = piece_locations[to];
+            let orbit = &orbits[orbit_index];
+            let relative = multiply(
+                &transpose(&orbit.frames[to_index]),
+                &multiply(&piece_move.rotation, &orbit.frames[from_index]),
+            );
+            let orbit_transformation = &mut transformation[orbit_index];
+            orbit_transformation.permutation[to_index] = from_index as u8;
+            orbit_transformation.orientation_delta[to_index] = *orbit
+                .orientation_lookup
+                .get(&matrix_key(&relative))
+                .expect("Moves should only reorient pieces by symmetries of the piece.");
+        }
+        let data: KTransformationData = orbits
+            .iter()
+            .zip(transformation)
+            .map(|(orbit, orbit_transformation)| {
+                (KPuzzleOrbitName(orbit.name.clone()), orbit_transformation)
+            })
+            .collect();
+        moves.insert(key_move(&piece_move.family, piece_move.layer), data);
+    }
+
+    Ok(KPuzzleDefinition {
+        name: description.split_whitespace().collect::<Vec<_>>().join(" "),
+        orbits: orbit_definitions,
+        default_pattern,
+        moves,
+        derived_moves: None,
+    })
+}
+
+/// Generates a puzzle from a puzzle geometry description. See
+/// [`puzzle_geometry_kpuzzle_definition`] for the description format and the
+/// supported moves.
+///
+/// ```
+/// use cubing_core::{alg::Alg, puzzles::puzzle_geometry_kpuzzle};
+///
+/// let kpuzzle = puzzle_geometry_kpuzzle("o f 0.333").unwrap();
+/// let pattern = kpuzzle
+///     .default_pattern()
+///     .apply_alg(&"(R U R' U')3".parse::<Alg>().unwrap())
+///     .unwrap();
+/// assert_ne!(pattern, kpuzzle.default_pattern());
+/// ```
+pub fn puzzle_geometry_kpuzzle(description: &str) -> Result<KPuzzle, InvalidDefinitionError> {
+    KPuzzle::try_new(puzzle_geometry_kpuzzle_definition(description)?)
+}
@@ -0,0 +1,225 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{OnceLock, RwLock},
+};
+
+use crate::kpuzzle::KPuzzle;
+
+use super::{
+    clock_kpuzzle, cube2x2x2_kpuzzle, cube3x3x3_kpuzzle, cube4x4x4_kpuzzle, cube5x5x5_kpuzzle,
+    cube6x6x6_kpuzzle, cube7x7x7_kpuzzle, cube_nxnxn_kpuzzle, megaminx_kpuzzle, pyraminx_kpuzzle,
+    skewb_kpuzzle, square1_kpuzzle,
+};
+
+type BuiltInPuzzle = (&'static str, &'static str, fn() -> &'static KPuzzle);
+
+// Sorted by puzzle ID.
+const BUILT_IN_PUZZLES: [BuiltInPuzzle; 11] = [
+    ("2x2x2", "2×2×2 Cube", cube2x2x2_kpuzzle),
+    ("3x3x3", "3×3×3 Cube", cube3x3x3_kpuzzle),
+    ("4x4x4", "4×4×4 Cube", cube4x4x4_kpuzzle),
+    ("5x5x5", "5×5×5 Cube", cube5x5x5_kpuzzle),
+    ("6x6x6", "6×6×6 Cube", cube6x6x6_kpuzzle),
+    ("7x7x7", "7×7×7 Cube", cube7x7x7_kpuzzle),
+    ("clock", "Clock", clock_kpuzzle),
+    ("megaminx", "Megaminx", megaminx_kpuzzle),
+    ("pyraminx", "Pyraminx", pyraminx_kpuzzle),
+    ("skewb", "Skewb", skewb_kpuzzle),
+    ("square1", "Square-1", square1_kpuzzle),
+];
+
+/// An error due to registering a puzzle with an ID that is already taken by a built-in puzzle.
+#[derive(Debug)]
+pub struct PuzzleRegistryError {
+    pub description: String,
+}
+
+impl From<String> for PuzzleRegistryError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for PuzzleRegistryError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for PuzzleRegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+/// A puzzle that can be looked up using [`kpuzzle_by_id`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleInfo {
+    pub id: String,
+    /// A human-readable name, e.g. `"3×3×3 Cube"`.
+    pub name: String,
+    pub num_orbits: usize,
+}
+
+struct RegisteredPuzzle {
+    name: String,
+    kpuzzle: KPuzzle,
+}
+
+fn registered_puzzles() -> &'static RwLock<HashMap<String, RegisteredPuzzle>> {
+    static REGISTERED_PUZZLES: OnceLock<RwLock<HashMap<String, RegisteredPuzzle>>> =
+        OnceLock::new();
+    REGISTERED_PUZZLES.get_or_init(Default::default)
+}
+
+fn nxnxn_size(puzzle_id: &str) -> Option<u32> {
+    let mut parts = puzzle_id.split('x');
+    let n = parts.next()?;
+    if n.starts_with('0') || !parts.all(|part| part == n) || puzzle_id.matches('x').count() != 2 {
+        return None;
+    }
+    n.parse().ok()
+}
+
+/// Looks up a puzzle by its cubing.js puzzle ID (e.g. `"3x3x3"`, `"pyraminx"`, or `"square1"`).
+///
+/// Built-in puzzles are only loaded the first time they are looked up. Besides
+/// the built-in puzzles and puzzles added using [`register_kpuzzle`], this
+/// also generates larger cubes (e.g. `"9x9x9"`) using
+/// [`cube_nxnxn_kpuzzle`][`super::cube_nxnxn_kpuzzle`].
+pub fn kpuzzle_by_id(puzzle_id: &str) -> Option<KPuzzle> {
+    if let Some((_, _, kpuzzle)) = BUILT_IN_PUZZLES.iter().find(|(id, _, _)| *id == puzzle_id) {
+        return Some(kpuzzle().clone());
+    }
+    if let Some(registered) = registered_puzzles()
+        .read()
+        .expect("The puzzle registry should not be poisoned.")
+        .get(puzzle_id)
+    {
+        return Some(registered.kpuzzle.clone());
+    }
+    let kpuzzle = cube_nxnxn_kpuzzle(nxnxn_size(puzzle_id)?).ok()?;
+    let name = format!("{} Cube", puzzle_id.replace('x', "×"));
+    registered_puzzles()
+        .write()
+        .expect("The puzzle registry should not be poisoned.")
+        .entry(puzzle_id.to_owned())
+        .or_insert(RegisteredPuzzle { name, kpuzzle });
+    kpuzzle_by_id(puzzle_id)
+}
+
+/// Registers a custom puzzle, so that it can be looked up using [`kpuzzle_by_id`].
+///
+/// Registering a puzzle ID again replaces the previous puzzle. Built-in puzzle IDs cannot be replaced.
+pub fn register_kpuzzle(
+    puzzle_id: &str,
+    name: &str,
+    kpuzzle: KPuzzle,
+) -> Result<(), PuzzleRegistryError> {
+    if BUILT_IN_PUZZLES.iter().any(|(id, _, _)| *id == puzzle_id) {
+        return Err(format!("Cannot replace built-in puzzle: {}", puzzle_id).into());
+    }
+    registered_puzzles()
+        .write()
+        .expect("The puzzle registry should not be poisoned.")
+        .insert(
+            puzzle_id.to_owned(),
+            RegisteredPuzzle {
+                name: name.to_owned(),
+                kpuzzle,
+            },
+        );
+    Ok(())
+}
+
+/// Lists the built-in puzzles, followed by any registered (or previously generated) puzzles, sorted by ID.
+///
+/// Note that this loads every built-in puzzle.
+pub fn available_puzzles() -> Vec<PuzzleInfo> {
+    let mut puzzles: Vec<PuzzleInfo> = BUILT_IN_PUZZLES
+        .iter()
+        .map(|(id, name, kpuzzle)| PuzzleInfo {
+            id: (*id).to_owned(),
+            name: (*name).to_owned(),
+            num_orbits: kpuzzle().definition().orbits.len(),
+        })
+        .collect();
+    let mut registered: Vec<PuzzleInfo> = registered_puzzles()
+        .read()
+        .expect("The puzzle registry should not be poisoned.")
+        .iter()
+        .map(|(id, registered)| PuzzleInfo {
+            id: id.clone(),
+            name: registered.name.clone(),
+            num_orbits: registered.kpuzzle.definition().orbits.len(),
+        })
+        .collect();
+    registered.sort_by(|a, b| a.id.cmp(&b.id));
+    puzzles.extend(registered);
+    puzzles
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{alg::Alg, kpuzzle::KPuzzle};
+
+    use super::{available_puzzles, kpuzzle_by_id, register_kpuzzle, PuzzleInfo};
+
+    #[test]
+    fn looks_up_built_in_puzzles() {
+        let kpuzzle = kpuzzle_by_id("3x3x3").unwrap();
+        assert_eq!(kpuzzle.definition().name, "3x3x3");
+        assert_eq!(
+            kpuzzle_by_id("square1").unwrap().definition().name,
+            "square1"
+        );
+        assert!(kpuzzle_by_id("3x3").is_none());
+        assert!(kpuzzle_by_id("3x3x3x3").is_none());
+        assert!(kpuzzle_by_id("03x03x03").is_none());
+        assert!(kpuzzle_by_id("unknown").is_none());
+
+        let puzzles = available_puzzles();
+        assert_eq!(
+            puzzles[1],
+            PuzzleInfo {
+                id: "3x3x3".to_owned(),
+                name: "3×3×3 Cube".to_owned(),
+                num_orbits: 3
+            }
+        );
+        assert!(puzzles.iter().any(|puzzle| puzzle.id == "megaminx"));
+    }
+
+    #[test]
+    fn generates_big_cubes() {
+        let kpuzzle = kpuzzle_by_id("9x9x9").unwrap();
+        assert_eq!(kpuzzle.definition().name, "9x9x9");
+        assert!(kpuzzle_by_id("99x99x99").is_none());
+        assert!(available_puzzles()
+            .iter()
+            .any(|puzzle| puzzle.id == "9x9x9" && puzzle.name == "9×9×9 Cube"));
+    }
+
+    #[test]
+    fn registers_custom_puzzles() {
+        let kpuzzle =
+            KPuzzle::try_from_json(include_bytes!("../kpuzzle/example.kpuzzle.json")).unwrap();
+        register_kpuzzle("example-puzzle", "Example Puzzle", kpuzzle.clone()).unwrap();
+        let looked_up = kpuzzle_by_id("example-puzzle").unwrap();
+        assert_eq!(looked_up.definition().name, kpuzzle.definition().name);
+        assert!(available_puzzles().contains(&PuzzleInfo {
+            id: "example-puzzle".to_owned(),
+            name: "Example Puzzle".to_owned(),
+            num_orbits: kpuzzle.definition().orbits.len()
+        }));
+
+        assert!(register_kpuzzle("3x3x3", "Not a 3x3x3", kpuzzle).is_err());
+        assert!(kpuzzle_by_id("3x3x3")
+            .unwrap()
+            .transformation_from_alg(&"R".parse::<Alg>().unwrap())
+            .is_ok());
+    }
+}
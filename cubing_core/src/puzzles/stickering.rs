@@ -0,0 +1,214 @@
+use crate::kpuzzle::{KPatternMask, KPuzzle, PieceMask};
+
+use PieceMask::{Ignored as I, OrientationOnly as O, PermutationOnly as P, Regular as R};
+
+// For the 3x3x3 (in Reid order):
+//
+// - Edges: UF UR UB UL . DF DR DB DL . FR FL BR BL
+// - Corners: UFR URB UBL ULF . DRF DFL DLB DBR
+// - Centers: U L F R B D
+type Cube3x3x3Stickering = (
+    &'static str,
+    [PieceMask; 12],
+    [PieceMask; 8],
+    [PieceMask; 6],
+);
+
+const fn last_layer(
+    name: &'static str,
+    edges: PieceMask,
+    corners: PieceMask,
+    f2l: PieceMask,
+) -> Cube3x3x3Stickering {
+    (
+        name,
+        [
+            edges, edges, edges, edges, f2l, f2l, f2l, f2l, f2l, f2l, f2l, f2l,
+        ],
+        [corners, corners, corners, corners, f2l, f2l, f2l, f2l],
+        [R; 6],
+    )
+}
+
+const CUBE3X3X3_STICKERINGS: [Cube3x3x3Stickering; 16] = [
+    last_layer("full", R, R, R),
+    last_layer("F2L", I, I, R),
+    (
+        "Cross",
+        [I, I, I, I, R, R, R, R, I, I, I, I],
+        [I; 8],
+        [R; 6],
+    ),
+    last_layer("LL", R, R, I),
+    last_layer("OLL", O, O, R),
+    last_layer("PLL", P, P, R),
+    last_layer("EOLL", O, I, R),
+    last_layer("OCLL", I, O, R),
+    last_layer("COLL", O, R, R),
+    last_layer("CLL", I, R, R),
+    last_layer("CPLL", I, P, R),
+    last_layer("EPLL", P, R, R),
+    last_layer("ELL", R, I, R),
+    ("EO", [O; 12], [I; 8], [R; 6]),
+    (
+        "EOline",
+        [O, O, O, O, R, O, R, O, O, O, O, O],
+        [I; 8],
+        [R; 6],
+    ),
+    (
+        "EOcross",
+        [O, O, O, O, R, R, R, R, O, O, O, O],
+        [I; 8],
+        [R; 6],
+    ),
+];
+
+// For the 2x2x2: UFR URB UBL ULF . DRF DFL DLB DBR
+const CUBE2X2X2_STICKERINGS: [(&str, [PieceMask; 8]); 4] = [
+    ("full", [R; 8]),
+    ("LL", [R, R, R, R, I, I, I, I]),
+    ("OLL", [O, O, O, O, R, R, R, R]),
+    ("PLL", [P, P, P, P, R, R, R, R]),
+];
+
+fn orbit_masks(kpuzzle: &KPuzzle, stickering: &str) -> Option<Vec<(&'static str, Vec<PieceMask>)>> {
+    match kpuzzle.definition().name.as_str() {
+        "3x3x3" => CUBE3X3X3_STICKERINGS
+            .iter()
+            .find(|(name, _, _, _)| *name == stickering)
+            .map(|(_, edges, corners, centers)| {
+                vec![
+                    ("EDGES", edges.to_vec()),
+                    ("CORNERS", corners.to_vec()),
+                    ("CENTERS", centers.to_vec()),
+                ]
+            }),
+        "2x2x2" => CUBE2X2X2_STICKERINGS
+            .iter()
+            .find(|(name, _)| *name == stickering)
+            .map(|(_, corners)| vec![("CORNERS", corners.to_vec())]),
+        _ => (stickering == "full").then(Vec::new),
+    }
+}
+
+/// Lists the stickering names (matching cubing.js) that [`stickering_mask`] supports for the given puzzle.
+///
+/// Every puzzle supports `"full"`. The 3x3x3 and 2x2x2 additionally support common stickerings like `"F2L"`, `"OLL"`, and `"PLL"`.
+pub fn available_stickerings(kpuzzle: &KPuzzle) -> Vec<&'static str> {
+    match kpuzzle.definition().name.as_str() {
+        "3x3x3" => CUBE3X3X3_STICKERINGS
+            .iter()
+            .map(|(name, _, _, _)| *name)
+            .collect(),
+        "2x2x2" => CUBE2X2X2_STICKERINGS
+            .iter()
+            .map(|(name, _)| *name)
+            .collect(),
+        _ => vec!["full"],
+    }
+}
+
+/// Returns a mask for a named stickering (e.g. `"OLL"`), matching the
+/// stickering names used by cubing.js (and
+/// [`TwizzleStickering`][`crate::twizzle_link::TwizzleStickering`]).
+///
+/// Pieces that are dimmed in cubing.js are ignored by the mask, and pieces that only show some of their stickers
+/// are compared by orientation or permutation only. For example, `"OLL"` compares the F2L pieces fully and the last
+/// layer pieces by orientation only:
+///
+/// ```
+/// use cubing_core::{alg::Alg, puzzles::{cube3x3x3_kpuzzle, stickering_mask}};
+///
+/// let kpuzzle = cube3x3x3_kpuzzle();
+/// let oll = stickering_mask(kpuzzle, "OLL").unwrap();
+/// let t_perm = kpuzzle
+///     .default_pattern()
+///     .apply_alg(&"R U R' U' R' F R2 U' R' U' R U R' F'".parse::<Alg>().unwrap())
+///     .unwrap();
+/// assert!(oll.is_solved(&t_perm));
+/// assert!(!stickering_mask(kpuzzle, "PLL").unwrap().is_solved(&t_perm));
+/// ```
+pub fn stickering_mask(kpuzzle: &KPuzzle, stickering: &str) -> Option<KPatternMask> {
+    let mut mask = KPatternMask::new(kpuzzle);
+    for (orbit_name, piece_masks) in orbit_masks(kpuzzle, stickering)? {
+        let orbit = kpuzzle.lookup_orbit(&orbit_name.into())?;
+        for (i, piece_mask) in piece_masks.into_iter().enumerate() {
+            mask.set(orbit, i as u8, piece_mask);
+        }
+    }
+    Some(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alg::Alg,
+        kpuzzle::{KPattern, KPuzzle},
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle, megaminx_kpuzzle},
+    };
+
+    use super::{available_stickerings, stickering_mask};
+
+    fn is_solved(kpuzzle: &KPuzzle, stickering: &str, alg: &str) -> bool {
+        let pattern: KPattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&alg.parse::<Alg>().unwrap())
+            .unwrap();
+        stickering_mask(kpuzzle, stickering)
+            .unwrap()
+            .is_solved(&pattern)
+    }
+
+    #[test]
+    fn cube3x3x3_stickerings() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let sune = "R U R' U R U2' R'";
+        assert!(!is_solved(kpuzzle, "full", sune));
+        assert!(is_solved(kpuzzle, "F2L", sune));
+        assert!(!is_solved(kpuzzle, "OLL", sune));
+        assert!(is_solved(kpuzzle, "EOLL", sune));
+        assert!(!is_solved(kpuzzle, "OCLL", sune));
+
+        let u_perm = "R U' R U R U R U' R' U' R2";
+        assert!(is_solved(kpuzzle, "OLL", u_perm));
+        assert!(is_solved(kpuzzle, "CLL", u_perm));
+        assert!(!is_solved(kpuzzle, "EPLL", u_perm));
+        assert!(!is_solved(kpuzzle, "PLL", u_perm));
+        assert!(!is_solved(kpuzzle, "ELL", u_perm));
+
+        // ELL ignores the last layer corners (unlike "full").
+        let a_perm = "R' F R' B2 R F' R' B2 R2";
+        assert!(is_solved(kpuzzle, "ELL", a_perm));
+        assert!(!is_solved(kpuzzle, "full", a_perm));
+        assert!(!is_solved(kpuzzle, "ELL", "R U R' U'"));
+
+        assert!(is_solved(kpuzzle, "Cross", "R U R' U'"));
+        assert!(!is_solved(kpuzzle, "Cross", "R U R' U' D"));
+        assert!(is_solved(kpuzzle, "EO", "R U D L"));
+        assert!(!is_solved(kpuzzle, "EO", "F"));
+        assert!(is_solved(kpuzzle, "EOline", "R U L2 U'"));
+        assert!(!is_solved(kpuzzle, "EOline", "D"));
+        assert!(is_solved(kpuzzle, "LL", "D"));
+        assert!(!is_solved(kpuzzle, "LL", "U"));
+        assert!(is_solved(kpuzzle, "OLL", "U"));
+        assert!(!is_solved(kpuzzle, "PLL", "U"));
+
+        assert!(stickering_mask(kpuzzle, "oll").is_none());
+        assert_eq!(available_stickerings(kpuzzle).len(), 16);
+    }
+
+    #[test]
+    fn other_puzzles() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        assert!(is_solved(kpuzzle, "LL", "D"));
+        assert!(is_solved(kpuzzle, "OLL", "U"));
+        assert!(!is_solved(kpuzzle, "PLL", "U"));
+        assert!(!is_solved(kpuzzle, "OLL", "R U R' U R U2' R'"));
+
+        let kpuzzle = megaminx_kpuzzle();
+        assert_eq!(available_stickerings(kpuzzle), ["full"]);
+        assert!(!is_solved(kpuzzle, "full", "R"));
+        assert!(stickering_mask(kpuzzle, "OLL").is_none());
+    }
+}
@@ -0,0 +1,379 @@
+use std::fmt::Write;
+
+use crate::kpuzzle::{KPattern, KPatternMask, PieceMask};
+
+use super::{
+    facelets::{facelet_stickers, pattern_facelets, FaceletSticker, FACES},
+    InvalidFaceletsError,
+};
+
+const STICKER_SIZE: usize = 20;
+const SIDE_STICKER_WIDTH: usize = 8;
+const GAP: usize = 4;
+const MASKED_COLOR: &str = "#808080";
+const ARROW_MARKER: &str = r##"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="8" refY="5" markerWidth="4" markerHeight="4" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="#000000"/></marker></defs>"##;
+
+// The fill color for the stickers of each face (in the standard color scheme).
+fn face_color(face: char) -> &'static str {
+    match face {
+        'U' => "#FFFFFF",
+        'R' => "#FF0000",
+        'F' => "#00D800",
+        'D' => "#FFFF00",
+        'L' => "#FF8000",
+        'B' => "#0000FF",
+        _ => MASKED_COLOR,
+    }
+}
+
+struct Stickers {
+    facelets: Vec<char>,
+    facelet_stickers: Vec<FaceletSticker>,
+    n: usize,
+}
+
+impl Stickers {
+    fn new(pattern: &KPattern) -> Result<Self, InvalidFaceletsError> {
+        let (facelets, n) = pattern_facelets(pattern)?;
+        Ok(Self {
+            facelets,
+            facelet_stickers: facelet_stickers(n),
+            n,
+        })
+    }
+
+    fn facelet_index(&self, face: char, row: usize, column: usize) -> usize {
+        let face_index = FACES.iter().position(|f| *f == face).expect("Invalid face");
+        face_index * self.n * self.n + row * self.n + column
+    }
+
+    // Returns the color of a facelet. If a mask is given, masked stickers are gray:
+    //
+    // - `OrientationOnly` positions only show the primary (i.e. `U`/`D`, or `F`/`B` for `E` slice edges) sticker of the piece.
+    // - `PermutationOnly` positions only show the other stickers of the piece.
+    fn color(
+        &self,
+        pattern: &KPattern,
+        mask: Option<&KPatternMask>,
+        facelet_index: usize,
+    ) -> &'static str {
+        let color = face_color(self.facelets[facelet_index]);
+        let Some(mask) = mask else {
+            return color;
+        };
+        let facelet_sticker = &self.facelet_stickers[facelet_index];
+        let Some(orbit) = mask
+            .kpuzzle()
+            .lookup_orbit(&facelet_sticker.orbit_name.into())
+        else {
+            return color;
+        };
+        let orientation = pattern
+            .get_orientation_with_mod(orbit, facelet_sticker.position)
+            .orientation;
+        let is_primary =
+            (facelet_sticker.sticker + orientation).is_multiple_of(facelet_sticker.num_stickers);
+        let visible = match mask.get(orbit, facelet_sticker.position) {
+            PieceMask::Regular => true,
+            PieceMask::Ignored => false,
+            PieceMask::OrientationOnly => is_primary,
+            PieceMask::PermutationOnly => !is_primary,
+        };
+        if visible {
+            color
+        } else {
+            MASKED_COLOR
+        }
+    }
+}
+
+fn svg_header(width: usize, height: usize) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width, height, width, height
+    )
+}
+
+fn write_rect(svg: &mut String, x: usize, y: usize, width: usize, height: usize, fill: &str) {
+    write!(
+        svg,
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="#000000" stroke-width="1"/>"##,
+        x, y, width, height, fill
+    )
+    .expect("Could not write SVG");
+}
+
+/// Draws the unfolded net of a 3x3x3 or 2x2x2 pattern as an SVG (with `U`
+/// above `L F R B` and `D` below, like [`render_net`][`super::render_net`]).
+///
+/// If a mask (e.g. from [`stickering_mask`][`super::stickering_mask`]) is
+/// given, the stickers that it hides are drawn in gray.
+pub fn render_net_svg(
+    pattern: &KPattern,
+    mask: Option<&KPatternMask>,
+) -> Result<String, InvalidFaceletsError> {
+    let stickers = Stickers::new(pattern)?;
+    let face_size = stickers.n * STICKER_SIZE;
+    let mut svg = svg_header(4 * face_size + 5 * GAP, 3 * face_size + 4 * GAP);
+    for (face, column, row) in [
+        ('U', 1, 0),
+        ('L', 0, 1),
+        ('F', 1, 1),
+        ('R', 2, 1),
+        ('B', 3, 1),
+        ('D', 1, 2),
+    ] {
+        let face_x = GAP + column * (face_size + GAP);
+        let face_y = GAP + row * (face_size + GAP);
+        for i in 0..stickers.n {
+            for j in 0..stickers.n {
+                let facelet_index = stickers.facelet_index(face, i, j);
+                write_rect(
+                    &mut svg,
+                    face_x + j * STICKER_SIZE,
+                    face_y + i * STICKER_SIZE,
+                    STICKER_SIZE,
+                    STICKER_SIZE,
+                    stickers.color(pattern, mask, facelet_index),
+                );
+            }
+        }
+    }
+    svg.push_str("</svg>");
+    Ok(svg)
+}
+
+/// Options for [`render_top_view_svg`].
+#[derive(Debug, Clone, Default)]
+pub struct TopViewOptions {
+    /// Stickers hidden by the mask are drawn in gray.
+    pub mask: Option<KPatternMask>,
+    /// Draws an arrow from each `U` layer piece to the position where it needs to go (e.g. for PLL diagrams).
+    pub arrows: bool,
+}
+
+/// Draws a "top view" of the `U` face of a 3x3x3 or 2x2x2 pattern as an SVG,
+/// including the top row of stickers of each side face (e.g. for last layer
+/// diagrams).
+///
+/// ```
+/// use cubing_core::{
+///     alg::Alg,
+///     puzzles::{cube3x3x3_kpuzzle, render_top_view_svg, stickering_mask, TopViewOptions},
+/// };
+///
+/// let kpuzzle = cube3x3x3_kpuzzle();
+/// let t_perm = kpuzzle
+///     .default_pattern()
+///     .apply_alg(&"R U R' U' R' F R2 U' R' U' R U R' F'".parse::<Alg>().unwrap())
+///     .unwrap();
+/// let svg = render_top_view_svg(
+///     &t_perm,
+///     &TopViewOptions {
+///         mask: stickering_mask(kpuzzle, "PLL"),
+///         arrows: true,
+///     },
+/// )
+/// .unwrap();
+/// assert!(svg.starts_with("<svg"));
+/// assert_eq!(svg.matches("<line").count(), 4);
+/// ```
+pub fn render_top_view_svg(
+    pattern: &KPattern,
+    options: &TopViewOptions,
+) -> Result<String, InvalidFaceletsError> {
+    let stickers = Stickers::new(pattern)?;
+    let n = stickers.n;
+    let mask = options.mask.as_ref();
+    let face_size = n * STICKER_SIZE;
+    let origin = 2 * GAP + SIDE_STICKER_WIDTH;
+    let far_side = origin + face_size + GAP;
+    let size = 2 * origin + face_size;
+    let mut svg = svg_header(size, size);
+    if options.arrows {
+        svg.push_str(ARROW_MARKER);
+    }
+    for i in 0..n {
+        for j in 0..n {
+            let facelet_index = stickers.facelet_index('U', i, j);
+            write_rect(
+                &mut svg,
+                origin + j * STICKER_SIZE,
+                origin + i * STICKER_SIZE,
+                STICKER_SIZE,
+                STICKER_SIZE,
+                stickers.color(pattern, mask, facelet_index),
+            );
+        }
+    }
+    // The top row of each side face, as seen from above.
+    for k in 0..n {
+        for (face, column, x, y, width, height) in [
+            (
+                'B',
+                n - 1 - k,
+                origin + k * STICKER_SIZE,
+                GAP,
+                STICKER_SIZE,
+                SIDE_STICKER_WIDTH,
+            ),
+            (
+                'F',
+                k,
+                origin + k * STICKER_SIZE,
+                far_side,
+                STICKER_SIZE,
+                SIDE_STICKER_WIDTH,
+            ),
+            (
+                'L',
+                k,
+                GAP,
+                origin + k * STICKER_SIZE,
+                SIDE_STICKER_WIDTH,
+                STICKER_SIZE,
+            ),
+            (
+                'R',
+                n - 1 - k,
+                far_side,
+                origin + k * STICKER_SIZE,
+                SIDE_STICKER_WIDTH,
+                STICKER_SIZE,
+            ),
+        ] {
+            let facelet_index = stickers.facelet_index(face, 0, column);
+            write_rect(
+                &mut svg,
+                x,
+                y,
+                width,
+                height,
+                stickers.color(pattern, mask, facelet_index),
+            );
+        }
+    }
+    if options.arrows {
+        write_arrows(&mut svg, pattern, &stickers, origin);
+    }
+    svg.push_str("</svg>");
+    Ok(svg)
+}
+
+fn write_arrows(svg: &mut String, pattern: &KPattern, stickers: &Stickers, origin: usize) {
+    let n = stickers.n;
+    // The center of the primary `U` facelet of each position, by orbit name and position.
+    let mut u_facelet_centers = vec![];
+    for i in 0..n {
+        for j in 0..n {
+            let facelet_sticker = &stickers.facelet_stickers[stickers.facelet_index('U', i, j)];
+            if facelet_sticker.sticker == 0 {
+                let x = (origin + j * STICKER_SIZE) as f64 + STICKER_SIZE as f64 / 2.0;
+                let y = (origin + i * STICKER_SIZE) as f64 + STICKER_SIZE as f64 / 2.0;
+                u_facelet_centers.push((facelet_sticker, (x, y)));
+            }
+        }
+    }
+    let center = |orbit_name: &str, position: u8| {
+        u_facelet_centers
+            .iter()
+            .find(|(facelet_sticker, _)| {
+                facelet_sticker.orbit_name == orbit_name && facelet_sticker.position == position
+            })
+            .map(|(_, center)| *center)
+    };
+    for (facelet_sticker, (x1, y1)) in &u_facelet_centers {
+        if facelet_sticker.orbit_name == "CENTERS" {
+            continue;
+        }
+        let Some(orbit) = pattern
+            .kpuzzle()
+            .lookup_orbit(&facelet_sticker.orbit_name.into())
+        else {
+            continue;
+        };
+        let piece = pattern.get_piece(orbit, facelet_sticker.position);
+        if piece == facelet_sticker.position {
+            continue;
+        }
+        let Some((x2, y2)) = center(facelet_sticker.orbit_name, piece) else {
+            continue;
+        };
+        // Shorten the arrow so that it does not cover the stickers at either end.
+        let (dx, dy) = ((x2 - x1) * 0.2, (y2 - y1) * 0.2);
+        write!(
+            svg,
+            r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#000000" stroke-width="2" marker-end="url(#arrowhead)"/>"##,
+            x1 + dx,
+            y1 + dy,
+            x2 - dx,
+            y2 - dy
+        )
+        .expect("Could not write SVG");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alg::Alg,
+        kpuzzle::KPattern,
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle, stickering_mask},
+    };
+
+    use super::{render_net_svg, render_top_view_svg, TopViewOptions};
+
+    fn pattern(alg: &str) -> KPattern {
+        cube3x3x3_kpuzzle()
+            .default_pattern()
+            .apply_alg(&alg.parse::<Alg>().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn renders_nets() {
+        let svg = render_net_svg(&pattern("R U R' U'"), None).unwrap();
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="260" height="196" viewBox="0 0 260 196">"#
+        ));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 54);
+        assert!(!svg.contains("#808080"));
+
+        let mask = stickering_mask(cube3x3x3_kpuzzle(), "F2L").unwrap();
+        let svg = render_net_svg(&pattern(""), Some(&mask)).unwrap();
+        // The `U` layer pieces, except for the center.
+        assert_eq!(svg.matches("#808080").count(), 20);
+
+        let svg = render_net_svg(&cube2x2x2_kpuzzle().default_pattern(), None).unwrap();
+        assert_eq!(svg.matches("<rect").count(), 24);
+    }
+
+    #[test]
+    fn renders_top_views() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let svg = render_top_view_svg(&pattern(""), &TopViewOptions::default()).unwrap();
+        assert_eq!(svg.matches("<rect").count(), 21);
+        assert_eq!(svg.matches("#FFFFFF").count(), 9);
+        assert!(!svg.contains("<line"));
+
+        // Sune twists three corners, so three `U` stickers are in the side stickers.
+        // All the other side stickers are gray.
+        let options = TopViewOptions {
+            mask: stickering_mask(kpuzzle, "OLL"),
+            arrows: false,
+        };
+        let svg = render_top_view_svg(&pattern("R U R' U R U2' R'"), &options).unwrap();
+        assert_eq!(svg.matches("#FFFFFF").count(), 9);
+        assert_eq!(svg.matches("#808080").count(), 12);
+
+        // A U perm cycles three edges.
+        let options = TopViewOptions {
+            mask: None,
+            arrows: true,
+        };
+        let svg = render_top_view_svg(&pattern("R U' R U R U R U' R' U' R2"), &options).unwrap();
+        assert_eq!(svg.matches("<line").count(), 3);
+        assert!(svg.contains(r#"marker-end="url(#arrowhead)""#));
+    }
+}
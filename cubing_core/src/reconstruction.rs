@@ -0,0 +1,280 @@
+//! Reconstructions of timed solves.
+//!
+//! ```
+//! use cubing_core::{alg::Alg, reconstruction::Reconstruction};
+//!
+//! let alg = "y // inspection\nD R' F D2 // cross\n[R, U] // pair".parse::<Alg>().unwrap();
+//! let reconstruction = Reconstruction::try_new(alg, vec![0, 600, 800, 1000, 1200, 2000, 2250, 2500, 2750]).unwrap();
+//!
+//! let steps = reconstruction.steps();
+//! assert_eq!(steps[1].label.as_deref(), Some("cross"));
+//! assert_eq!(steps[1].duration_ms, 1200);
+//! assert_eq!(steps[2].tps(), Some(4.0 / 1.55));
+//!
+//! let json = serde_json::to_string(&reconstruction).unwrap();
+//! assert_eq!(serde_json::from_str::<Reconstruction>(&json).unwrap(), reconstruction);
+//! ```
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::alg::{Alg, AlgNode};
+
+/// An invalid [`Reconstruction`], e.g. with the wrong number of timestamps.
+#[derive(Debug)]
+pub struct ReconstructionError {
+    pub description: String,
+}
+
+impl From<String> for ReconstructionError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for ReconstructionError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for ReconstructionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+// The JSON representation, before validation.
+#[derive(Deserialize)]
+struct ReconstructionData {
+    alg: Alg,
+    timestamps: Vec<u64>,
+}
+
+impl TryFrom<ReconstructionData> for Reconstruction {
+    type Error = ReconstructionError;
+
+    fn try_from(data: ReconstructionData) -> Result<Self, Self::Error> {
+        Reconstruction::try_new(data.alg, data.timestamps)
+    }
+}
+
+/// An alg for a solve (usually with a comment labeling each line, e.g. `// cross`),
+/// with a timestamp for each move.
+///
+/// Timestamps are in milliseconds since the start of the solve, with one
+/// timestamp per move of the expanded alg (see [`Alg::expand`]). Each Square-1
+/// tuple (e.g. `(3, -4)`) counts as a single move.
+///
+/// This serializes to JSON as `{"alg": "…", "timestamps": […]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ReconstructionData")]
+pub struct Reconstruction {
+    alg: Alg,
+    timestamps: Vec<u64>,
+}
+
+/// A line of a [`Reconstruction`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconstructionStep {
+    /// The text of the comment at the end of the line (trimmed), if there is one.
+    pub label: Option<String>,
+    /// The moves of the line, without the comment.
+    pub alg: Alg,
+    pub num_moves: usize,
+    /// The end of the previous step (or `0` for the first step).
+    pub start_ms: u64,
+    /// The timestamp of the last move of the step.
+    pub end_ms: u64,
+    /// Includes recognition time (i.e. the time since the end of the previous step).
+    pub duration_ms: u64,
+}
+
+impl ReconstructionStep {
+    /// Turns per second, or `None` if the step took no time.
+    pub fn tps(&self) -> Option<f64> {
+        tps(self.num_moves, self.duration_ms)
+    }
+}
+
+fn tps(num_moves: usize, duration_ms: u64) -> Option<f64> {
+    if duration_ms == 0 {
+        return None;
+    }
+    Some(num_moves as f64 * 1000.0 / duration_ms as f64)
+}
+
+// The number of moves in the expanded alg, computed without expanding it (so
+// that e.g. `((R)1000)1000` from untrusted JSON does not use lots of memory).
+// Saturates instead of overflowing, since no list of timestamps is that long.
+fn num_timed_moves(alg: &Alg) -> usize {
+    alg.nodes
+        .iter()
+        .map(|node| match node {
+            AlgNode::MoveNode(_) => 1,
+            AlgNode::GroupingNode(grouping) if grouping.square1_tuple().is_some() => 1,
+            AlgNode::GroupingNode(grouping) => num_timed_moves(&grouping.alg)
+                .saturating_mul(grouping.amount.unsigned_abs() as usize),
+            AlgNode::CommutatorNode(commutator) => num_timed_moves(&commutator.a)
+                .saturating_add(num_timed_moves(&commutator.b))
+                .saturating_mul(2),
+            AlgNode::ConjugateNode(conjugate) => num_timed_moves(&conjugate.a)
+                .saturating_mul(2)
+                .saturating_add(num_timed_moves(&conjugate.b)),
+            AlgNode::PauseNode(_) | AlgNode::NewlineNode(_) | AlgNode::LineCommentNode(_) => 0,
+        })
+        .fold(0, usize::saturating_add)
+}
+
+impl Reconstruction {
+    /// Returns an error if the number of timestamps does not match the number
+    /// of moves, or if the timestamps are not in order.
+    pub fn try_new(alg: Alg, timestamps: Vec<u64>) -> Result<Self, ReconstructionError> {
+        let num_moves = num_timed_moves(&alg);
+        if timestamps.len() != num_moves {
+            return Err(format!(
+                "Expected {} timestamps (one per move), found {}.",
+                num_moves,
+                timestamps.len()
+            )
+            .into());
+        }
+        if timestamps.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err("Timestamps must be in increasing order.".into());
+        }
+        Ok(Self { alg, timestamps })
+    }
+
+    pub fn alg(&self) -> &Alg {
+        &self.alg
+    }
+
+    pub fn timestamps(&self) -> &[u64] {
+        &self.timestamps
+    }
+
+    /// The timestamp of the last move.
+    pub fn duration_ms(&self) -> u64 {
+        self.timestamps.last().copied().unwrap_or(0)
+    }
+
+    /// Turns per second for the whole solve, or `None` if it took no time.
+    pub fn tps(&self) -> Option<f64> {
+        tps(self.timestamps.len(), self.duration_ms())
+    }
+
+    /// Splits the reconstruction into lines, skipping lines without any moves.
+    pub fn steps(&self) -> Vec<ReconstructionStep> {
+        let mut steps = Vec::<ReconstructionStep>::default();
+        let mut line = Alg::default();
+        let mut move_index = 0;
+        let mut previous_end_ms = 0;
+        let mut finish_line = |line: &mut Alg, label: Option<String>| {
+            let line = std::mem::take(line);
+            let num_moves = num_timed_moves(&line);
+            if num_moves == 0 {
+                return;
+            }
+            move_index += num_moves;
+            let end_ms = self.timestamps[move_index - 1];
+            steps.push(ReconstructionStep {
+                label,
+                alg: line,
+                num_moves,
+                start_ms: previous_end_ms,
+                end_ms,
+                duration_ms: end_ms - previous_end_ms,
+            });
+            previous_end_ms = end_ms;
+        };
+        for node in &self.alg.nodes {
+            match node {
+                AlgNode::NewlineNode(_) => finish_line(&mut line, None),
+                AlgNode::LineCommentNode(line_comment) => {
+                    finish_line(&mut line, Some(line_comment.text().trim().to_owned()))
+                }
+                node => line.nodes.push(node.clone()),
+            }
+        }
+        finish_line(&mut line, None);
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alg::Alg;
+
+    use super::Reconstruction;
+
+    #[test]
+    fn computes_steps() {
+        let alg = "x2 // inspection\n\n(R U R' U')2 // sexy\n// comment only\nF (3, -4) /\nB"
+            .parse::<Alg>()
+            .unwrap();
+        let timestamps = (0..13).map(|i| i * 100).collect::<Vec<u64>>();
+        let reconstruction = Reconstruction::try_new(alg, timestamps).unwrap();
+        let steps = reconstruction.steps();
+        let summary: Vec<(Option<&str>, usize, u64, u64)> = steps
+            .iter()
+            .map(|step| {
+                (
+                    step.label.as_deref(),
+                    step.num_moves,
+                    step.start_ms,
+                    step.duration_ms,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("inspection"), 1, 0, 0),
+                (Some("sexy"), 8, 0, 800),
+                (None, 3, 800, 300),
+                (None, 1, 1100, 100),
+            ]
+        );
+        assert_eq!(steps[0].tps(), None);
+        assert_eq!(steps[1].tps(), Some(10.0));
+        assert_eq!(steps[2].alg.to_string(), "F (3, -4) /");
+        assert_eq!(reconstruction.duration_ms(), 1200);
+        assert_eq!(reconstruction.tps(), Some(13.0 / 1.2));
+    }
+
+    #[test]
+    fn validates_timestamps() {
+        let alg = "R U // pair".parse::<Alg>().unwrap();
+        assert!(Reconstruction::try_new(alg.clone(), vec![100]).is_err());
+        assert!(Reconstruction::try_new(alg.clone(), vec![200, 100]).is_err());
+        assert!(Reconstruction::try_new(alg, vec![100, 100]).is_ok());
+
+        let json = r#"{"alg":"R U // pair","timestamps":[100,250]}"#;
+        let reconstruction = serde_json::from_str::<Reconstruction>(json).unwrap();
+        assert_eq!(reconstruction.steps()[0].end_ms, 250);
+        assert_eq!(serde_json::to_string(&reconstruction).unwrap(), json);
+        assert!(serde_json::from_str::<Reconstruction>(
+            r#"{"alg":"R U // pair","timestamps":[100]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn counts_moves_without_expanding() {
+        let alg = "[R, U]2 [F: (3, -4) /]' (R2)0".parse::<Alg>().unwrap();
+        let timestamps = (0..12).collect::<Vec<u64>>();
+        assert!(Reconstruction::try_new(alg, timestamps).is_ok());
+
+        // These would take far too much time and memory to expand.
+        let json = r#"{"alg":"(((R)1000000)1000000)1000000","timestamps":[0]}"#;
+        let error = serde_json::from_str::<Reconstruction>(json).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Expected 1000000000000000000 timestamps"));
+        let json = r#"{"alg":"((((R)1000000)1000000)1000000)1000000","timestamps":[0]}"#;
+        assert!(serde_json::from_str::<Reconstruction>(json).is_err());
+    }
+}
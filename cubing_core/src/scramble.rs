@@ -0,0 +1,11 @@
+mod random_pattern;
+pub use random_pattern::{random_pattern, RandomPatternGenerator};
+
+mod random_moves;
+pub use random_moves::{all_move_amounts, RandomMovesScrambler};
+
+mod event_scramble;
+pub use event_scramble::{
+    random_scramble_for_event, random_scramble_for_event_with_options, EventScrambleOptions,
+    ScrambleError,
+};
@@ -0,0 +1,415 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+use crate::{
+    alg::{Alg, AlgNode, Move, Newline},
+    puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle},
+    search::{Cube2x2x2Solver, SearchError, TwoPhaseOptions, TwoPhaseSolver},
+};
+
+use super::RandomPatternGenerator;
+
+/// An error due to an unsupported event or invalid [`EventScrambleOptions`].
+#[derive(Debug)]
+pub struct ScrambleError {
+    pub description: String,
+}
+
+impl From<String> for ScrambleError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for ScrambleError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl From<SearchError> for ScrambleError {
+    fn from(search_error: SearchError) -> Self {
+        Self {
+            description: search_error.description,
+        }
+    }
+}
+
+impl Display for ScrambleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+/// Options for [`random_scramble_for_event_with_options`]. Fields that are
+/// `None` use the standard value for the event.
+///
+/// The solution length options only apply to random-state events (`222` and `333`).
+#[derive(Debug, Clone, Default)]
+pub struct EventScrambleOptions {
+    /// Random patterns that can be solved in fewer moves than this are rejected.
+    ///
+    /// Defaults to 4 moves for `222` and 2 moves for `333`.
+    pub min_solution_length: Option<usize>,
+    /// The maximum number of moves in the scramble.
+    ///
+    /// Defaults to 11 moves for `222` and 21 moves for `333`. The `333` solver
+    /// takes much longer to find scrambles with fewer than 21 moves.
+    pub max_length: Option<usize>,
+    /// Give up if no scramble has been found after this amount of time. This
+    /// does not include the time to build the solver tables.
+    ///
+    /// Defaults to 10 seconds.
+    pub timeout: Option<Duration>,
+}
+
+struct EventScrambleParameters {
+    min_solution_length: usize,
+    max_length: usize,
+    timeout: Duration,
+}
+
+impl EventScrambleParameters {
+    fn try_new(
+        options: &EventScrambleOptions,
+        default_min_solution_length: usize,
+        default_max_length: usize,
+        gods_number: usize,
+    ) -> Result<Self, ScrambleError> {
+        let parameters = Self {
+            min_solution_length: options
+                .min_solution_length
+                .unwrap_or(default_min_solution_length),
+            max_length: options.max_length.unwrap_or(default_max_length),
+            timeout: options.timeout.unwrap_or(Duration::from_secs(10)),
+        };
+        if parameters.min_solution_length > gods_number {
+            return Err(format!(
+                "The minimum solution length ({}) is larger than the maximum distance of any pattern ({}).",
+                parameters.min_solution_length, gods_number
+            )
+            .into());
+        }
+        if parameters.min_solution_length > parameters.max_length {
+            return Err(format!(
+                "The minimum solution length ({}) is larger than the maximum scramble length ({}).",
+                parameters.min_solution_length, parameters.max_length
+            )
+            .into());
+        }
+        Ok(parameters)
+    }
+
+    // Call this after the solver is built, so that building its tables does not use up the timeout.
+    fn deadline(&self) -> Instant {
+        Instant::now() + self.timeout
+    }
+
+    fn check_deadline(&self, deadline: Instant) -> Result<(), ScrambleError> {
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "Timed out before finding a scramble with at most {} moves.",
+                self.max_length
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+// Scrambles are written with `R2` rather than `R2'`, as in WCA scrambles.
+fn invert_solution(solution: &Alg) -> Alg {
+    Alg {
+        nodes: solution
+            .invert()
+            .nodes
+            .into_iter()
+            .map(|node| match node {
+                AlgNode::MoveNode(r#move) if r#move.amount == -2 => AlgNode::MoveNode(Move {
+                    quantum: r#move.quantum,
+                    amount: 2,
+                }),
+                node => node,
+            })
+            .collect(),
+    }
+}
+
+fn face_moves(families: &[&str]) -> Vec<Move> {
+    families
+        .iter()
+        .map(|family| {
+            family
+                .parse::<Move>()
+                .expect("Face moves should always parse.")
+        })
+        .collect()
+}
+
+/// Generates a scramble for the given WCA event ID, in the style of WCA
+/// scrambles for the event. `222` and `333` are random-state scrambles (using
+/// the standard filters and length for the event), while `minx` and `clock`
+/// are random-move scrambles.
+///
+/// Supported events:
+///
+/// - `222`: an optimal solution (in reverse) for a uniformly random pattern,
+///   using the moves `U`, `R`, and `F`.
+/// - `333`: a two-phase solution (in reverse) for a uniformly random pattern,
+///   using face moves.
+/// - `minx`: a random-move scramble in the WCA format: 7 lines of 10
+///   alternating `R++`/`R--` and `D++`/`D--` moves, each followed by `U` or `U'`.
+/// - `clock`: a random-move scramble in the WCA format (e.g. `UR4- DR3+ … ALL2+ y2 U1- … ALL5+`),
+///   turning each dial group once.
+///
+/// Pass a seeded RNG (e.g. [`rand::rngs::StdRng::seed_from_u64(…)`][`rand::SeedableRng::seed_from_u64`]) for reproducible scrambles.
+pub fn random_scramble_for_event<R: Rng + ?Sized>(
+    event_id: &str,
+    rng: &mut R,
+) -> Result<Alg, ScrambleError> {
+    random_scramble_for_event_with_options(event_id, &EventScrambleOptions::default(), rng)
+}
+
+/// Like [`random_scramble_for_event`], but with custom filters and length.
+pub fn random_scramble_for_event_with_options<R: Rng + ?Sized>(
+    event_id: &str,
+    options: &EventScrambleOptions,
+    rng: &mut R,
+) -> Result<Alg, ScrambleError> {
+    match event_id {
+        "222" => random_scramble_2x2x2(&EventScrambleParameters::try_new(options, 4, 11, 11)?, rng),
+        "333" => random_scramble_3x3x3(&EventScrambleParameters::try_new(options, 2, 21, 20)?, rng),
+        "minx" => {
+            check_no_length_options(event_id, options)?;
+            Ok(random_scramble_megaminx(rng))
+        }
+        "clock" => {
+            check_no_length_options(event_id, options)?;
+            Ok(random_scramble_clock(rng))
+        }
+        _ => Err(format!("Unsupported event: {}", event_id).into()),
+    }
+}
+
+fn check_no_length_options(
+    event_id: &str,
+    options: &EventScrambleOptions,
+) -> Result<(), ScrambleError> {
+    if options.min_solution_length.is_some() || options.max_length.is_some() {
+        return Err(format!("Scrambles for `{}` have a fixed length.", event_id).into());
+    }
+    Ok(())
+}
+
+fn parse_move(s: &str) -> Move {
+    s.parse::<Move>()
+        .expect("Scramble moves should always parse.")
+}
+
+fn random_scramble_megaminx<R: Rng + ?Sized>(rng: &mut R) -> Alg {
+    let mut nodes = Vec::<AlgNode>::default();
+    for line in 0..7 {
+        if line > 0 {
+            nodes.push(Newline {}.into());
+        }
+        for i in 0..10 {
+            let family = if i % 2 == 0 { "R" } else { "D" };
+            let suffix = if rng.random() { "++" } else { "--" };
+            nodes.push(parse_move(&format!("{}{}", family, suffix)).into());
+        }
+        nodes.push(parse_move(if rng.random() { "U" } else { "U'" }).into());
+    }
+    Alg { nodes }
+}
+
+fn random_scramble_clock<R: Rng + ?Sized>(rng: &mut R) -> Alg {
+    let mut nodes = Vec::<AlgNode>::default();
+    let mut push_dial_move = |nodes: &mut Vec<AlgNode>, family: &str| {
+        let mut r#move = parse_move(&format!("{}1+", family));
+        r#move.amount = rng.random_range(-5..=6);
+        nodes.push(r#move.into());
+    };
+    for family in ["UR", "DR", "DL", "UL", "U", "R", "D", "L", "ALL"] {
+        push_dial_move(&mut nodes, family);
+    }
+    nodes.push(parse_move("y2").into());
+    for family in ["U", "R", "D", "L", "ALL"] {
+        push_dial_move(&mut nodes, family);
+    }
+    Alg { nodes }
+}
+
+fn random_scramble_2x2x2<R: Rng + ?Sized>(
+    parameters: &EventScrambleParameters,
+    rng: &mut R,
+) -> Result<Alg, ScrambleError> {
+    let generator =
+        RandomPatternGenerator::try_new(cube2x2x2_kpuzzle(), &face_moves(&["U", "R", "F"]))
+            .expect("Face moves should be valid for the 2x2x2.");
+    let solver = Cube2x2x2Solver::new();
+    let deadline = parameters.deadline();
+    loop {
+        parameters.check_deadline(deadline)?;
+        let solution = solver.solve(&generator.random_pattern(rng))?;
+        // Since the solution is optimal, this filters by both the minimum and maximum length.
+        let length = solution.nodes.len();
+        if (parameters.min_solution_length..=parameters.max_length).contains(&length) {
+            return Ok(invert_solution(&solution));
+        }
+    }
+}
+
+fn random_scramble_3x3x3<R: Rng + ?Sized>(
+    parameters: &EventScrambleParameters,
+    rng: &mut R,
+) -> Result<Alg, ScrambleError> {
+    let generator = RandomPatternGenerator::try_new(
+        cube3x3x3_kpuzzle(),
+        &face_moves(&["U", "L", "F", "R", "B", "D"]),
+    )
+    .expect("Face moves should be valid for the 3x3x3.");
+    let solver = TwoPhaseSolver::new();
+    let deadline = parameters.deadline();
+    loop {
+        parameters.check_deadline(deadline)?;
+        let pattern = generator.random_pattern(rng);
+        if parameters.min_solution_length > 0
+            && solver.is_solvable_within(&pattern, parameters.min_solution_length - 1)?
+        {
+            continue;
+        }
+        if let Some(solution) = solver.solve(
+            &pattern,
+            &TwoPhaseOptions {
+                max_length: parameters.max_length,
+                timeout: Some(deadline.saturating_duration_since(Instant::now())),
+            },
+        )? {
+            return Ok(invert_solution(&solution));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        alg::AlgNode,
+        puzzles::{clock_kpuzzle, cube2x2x2_kpuzzle, cube3x3x3_kpuzzle, megaminx_kpuzzle},
+    };
+
+    use super::{
+        random_scramble_for_event, random_scramble_for_event_with_options, EventScrambleOptions,
+    };
+
+    #[test]
+    fn scrambles_2x2x2() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..5 {
+            let scramble = random_scramble_for_event("222", &mut rng).unwrap();
+            assert!((4..=11).contains(&scramble.nodes.len()));
+            assert_ne!(
+                kpuzzle.default_pattern().apply_alg(&scramble).unwrap(),
+                kpuzzle.default_pattern()
+            );
+        }
+
+        let options = EventScrambleOptions {
+            min_solution_length: Some(10),
+            ..Default::default()
+        };
+        let scramble = random_scramble_for_event_with_options("222", &options, &mut rng).unwrap();
+        assert!(scramble.nodes.len() >= 10);
+    }
+
+    #[test]
+    fn scrambles_3x3x3() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let scramble = random_scramble_for_event("333", &mut StdRng::seed_from_u64(5)).unwrap();
+        assert!(scramble.nodes.len() <= 21);
+        assert!(scramble
+            .nodes
+            .iter()
+            .all(|node| matches!(node, AlgNode::MoveNode(r#move) if r#move.amount != -2)));
+        assert_ne!(
+            kpuzzle.default_pattern().apply_alg(&scramble).unwrap(),
+            kpuzzle.default_pattern()
+        );
+        assert_eq!(
+            scramble,
+            random_scramble_for_event("333", &mut StdRng::seed_from_u64(5)).unwrap()
+        );
+    }
+
+    #[test]
+    fn scrambles_megaminx_and_clock() {
+        let kpuzzle = megaminx_kpuzzle();
+        let scramble = random_scramble_for_event("minx", &mut StdRng::seed_from_u64(7)).unwrap();
+        let scramble_str = scramble.to_string();
+        let lines: Vec<&str> = scramble_str.lines().collect();
+        assert_eq!(lines.len(), 7);
+        for line in lines {
+            let moves: Vec<&str> = line.split(' ').collect();
+            assert_eq!(moves.len(), 11);
+            assert!(["R++", "R--"].contains(&moves[0]));
+            assert!(["D++", "D--"].contains(&moves[9]));
+            assert!(["U", "U'"].contains(&moves[10]));
+        }
+        assert_ne!(
+            kpuzzle.default_pattern().apply_alg(&scramble).unwrap(),
+            kpuzzle.default_pattern()
+        );
+
+        let kpuzzle = clock_kpuzzle();
+        let scramble = random_scramble_for_event("clock", &mut StdRng::seed_from_u64(7)).unwrap();
+        assert_eq!(scramble.nodes.len(), 15);
+        assert!(scramble.to_string().contains(" ALL"));
+        assert!(scramble.to_string().contains(" y2 U"));
+        kpuzzle.default_pattern().apply_alg(&scramble).unwrap();
+
+        let options = EventScrambleOptions {
+            max_length: Some(20),
+            ..Default::default()
+        };
+        assert!(random_scramble_for_event_with_options(
+            "minx",
+            &options,
+            &mut StdRng::seed_from_u64(7)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let mut rng = StdRng::seed_from_u64(6);
+        assert!(random_scramble_for_event("444", &mut rng).is_err());
+        let options = EventScrambleOptions {
+            min_solution_length: Some(12),
+            max_length: Some(11),
+            ..Default::default()
+        };
+        assert!(random_scramble_for_event_with_options("222", &options, &mut rng).is_err());
+        let options = EventScrambleOptions {
+            min_solution_length: Some(12),
+            max_length: Some(20),
+            ..Default::default()
+        };
+        assert!(random_scramble_for_event_with_options("222", &options, &mut rng).is_err());
+        let options = EventScrambleOptions {
+            max_length: Some(16),
+            timeout: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        assert!(random_scramble_for_event_with_options("333", &options, &mut rng).is_err());
+    }
+}
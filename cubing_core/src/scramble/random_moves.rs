@@ -0,0 +1,369 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use rand::Rng;
+
+use crate::{
+    alg::{Alg, AlgNode, Amount, Move, QuantumMove},
+    kpuzzle::{InvalidAlgError, KPuzzle, KTransformation},
+};
+
+#[derive(Debug)]
+struct QuantumMoveInfo {
+    quantum: Arc<QuantumMove>,
+    amounts: Vec<Amount>,
+}
+
+/// Generates random move sequences that are canonical: no move can be
+/// combined with a previous move (e.g. `R R'`), even after commuting moves
+/// past each other (e.g. `R L R'`).
+///
+/// Each move is chosen by picking uniformly among the quantum moves that keep
+/// the sequence canonical, and then uniformly among its amounts. Runs of commuting moves (like `R L` and `L R`)
+/// are only generated in the order that their quantum moves were first passed
+/// to the constructor, so that each such run is generated in only one way.
+/// Moves that would leave no canonical way to continue the sequence (e.g. a
+/// Pyraminx tip after all the later tips) are avoided where possible.
+///
+/// Pass a seeded RNG (e.g. [`rand::rngs::StdRng::seed_from_u64(…)`][`rand::SeedableRng::seed_from_u64`]) for reproducible scrambles.
+#[derive(Debug)]
+pub struct RandomMovesScrambler {
+    kpuzzle: KPuzzle,
+    quantum_move_infos: Vec<QuantumMoveInfo>,
+    // Indexed by `[quantum_move_index][quantum_move_index]`.
+    commutes: Vec<Vec<bool>>,
+    // Commuting runs that cannot be continued indefinitely.
+    dead_end_runs: HashSet<Vec<usize>>,
+}
+
+impl RandomMovesScrambler {
+    /// Constructs a scrambler that chooses from the given moves (e.g. `R`, `R2`, `R'`, `U`, …).
+    pub fn try_new(kpuzzle: impl Into<KPuzzle>, moves: &[Move]) -> Result<Self, InvalidAlgError> {
+        let kpuzzle: KPuzzle = kpuzzle.into();
+        let mut quantum_move_infos = Vec::<QuantumMoveInfo>::default();
+        for r#move in moves {
+            kpuzzle.transformation_from_move(r#move)?;
+            match quantum_move_infos
+                .iter_mut()
+                .find(|info| info.quantum == r#move.quantum)
+            {
+                Some(info) => {
+                    if !info.amounts.contains(&r#move.amount) {
+                        info.amounts.push(r#move.amount)
+                    }
+                }
+                None => quantum_move_infos.push(QuantumMoveInfo {
+                    quantum: r#move.quantum.clone(),
+                    amounts: vec![r#move.amount],
+                }),
+            }
+        }
+
+        let transformations = quantum_move_infos
+            .iter()
+            .map(|info| {
+                kpuzzle.transformation_from_move(&Move {
+                    quantum: info.quantum.clone(),
+                    amount: 1,
+                })
+            })
+            .collect::<Result<Vec<KTransformation>, InvalidAlgError>>()?;
+        let commutes = transformations
+            .iter()
+            .map(|t1| {
+                transformations
+                    .iter()
+                    .map(|t2| t1.apply_transformation(t2) == t2.apply_transformation(t1))
+                    .collect()
+            })
+            .collect();
+
+        let mut scrambler = Self {
+            kpuzzle,
+            quantum_move_infos,
+            commutes,
+            dead_end_runs: HashSet::default(),
+        };
+        scrambler.dead_end_runs = scrambler.find_dead_end_runs();
+        Ok(scrambler)
+    }
+
+    fn next_commuting_run(&self, commuting_run: &[usize], quantum_move_index: usize) -> Vec<usize> {
+        if commuting_run
+            .iter()
+            .all(|&previous| self.commutes[previous][quantum_move_index])
+        {
+            [commuting_run, &[quantum_move_index]].concat()
+        } else {
+            vec![quantum_move_index]
+        }
+    }
+
+    // Finds all the reachable commuting runs, and then repeatedly removes runs
+    // that cannot be followed by a remaining run. Whatever is removed cannot
+    // be continued indefinitely.
+    fn find_dead_end_runs(&self) -> HashSet<Vec<usize>> {
+        let mut successors = HashMap::<Vec<usize>, Vec<Vec<usize>>>::default();
+        let mut queue = vec![vec![]];
+        while let Some(commuting_run) = queue.pop() {
+            if successors.contains_key(&commuting_run) {
+                continue;
+            }
+            let next_runs: Vec<Vec<usize>> = (0..self.quantum_move_infos.len())
+                .filter(|&candidate| self.can_follow(&commuting_run, candidate))
+                .map(|candidate| self.next_commuting_run(&commuting_run, candidate))
+                .collect();
+            queue.extend(next_runs.iter().cloned());
+            successors.insert(commuting_run, next_runs);
+        }
+        let mut dead_end_runs = HashSet::<Vec<usize>>::default();
+        loop {
+            let newly_dead: Vec<Vec<usize>> = successors
+                .iter()
+                .filter(|(commuting_run, next_runs)| {
+                    !dead_end_runs.contains(*commuting_run)
+                        && next_runs
+                            .iter()
+                            .all(|next_run| dead_end_runs.contains(next_run))
+                })
+                .map(|(commuting_run, _)| commuting_run.clone())
+                .collect();
+            if newly_dead.is_empty() {
+                return dead_end_runs;
+            }
+            dead_end_runs.extend(newly_dead);
+        }
+    }
+
+    /// Constructs a scrambler that chooses from every non-trivial amount of each of the given quantum moves.
+    ///
+    /// For example, passing `R` and `U` for the 3x3x3 uses the moves `R`, `R2`, `R'`, `U`, `U2`, and `U'`.
+    /// Amounts that cannot be written in notation are skipped (e.g. Megaminx `R++` only uses `R++` and `R--`).
+    pub fn try_new_with_all_amounts(
+        kpuzzle: impl Into<KPuzzle>,
+        quantum_moves: &[QuantumMove],
+    ) -> Result<Self, InvalidAlgError> {
+        let kpuzzle: KPuzzle = kpuzzle.into();
+        let mut moves = Vec::<Move>::default();
+        for quantum_move in quantum_moves {
+            let quantum = Arc::new(quantum_move.clone());
+            for amount in all_move_amounts(&kpuzzle, &quantum)? {
+                if !is_writable_amount(&quantum, amount) {
+                    continue;
+                }
+                moves.push(Move {
+                    quantum: quantum.clone(),
+                    amount,
+                });
+            }
+        }
+        Self::try_new(kpuzzle, &moves)
+    }
+
+    pub fn kpuzzle(&self) -> &KPuzzle {
+        &self.kpuzzle
+    }
+
+    pub fn random_moves<R: Rng + ?Sized>(&self, num_moves: usize, rng: &mut R) -> Alg {
+        let mut nodes = Vec::<AlgNode>::with_capacity(num_moves);
+        // The quantum moves (by index) of the current run of mutually commuting moves.
+        let mut commuting_run = Vec::<usize>::default();
+        for move_number in 0..num_moves {
+            let mut candidates: Vec<usize> = (0..self.quantum_move_infos.len())
+                .filter(|&candidate| self.can_follow(&commuting_run, candidate))
+                .collect();
+            if candidates.is_empty() {
+                break;
+            }
+            if move_number + 1 < num_moves {
+                let continuable_candidates: Vec<usize> = candidates
+                    .iter()
+                    .copied()
+                    .filter(|&candidate| {
+                        !self
+                            .dead_end_runs
+                            .contains(&self.next_commuting_run(&commuting_run, candidate))
+                    })
+                    .collect();
+                if !continuable_candidates.is_empty() {
+                    candidates = continuable_candidates;
+                }
+            }
+            let quantum_move_index = candidates[rng.random_range(0..candidates.len())];
+            let info = &self.quantum_move_infos[quantum_move_index];
+            let amount = info.amounts[rng.random_range(0..info.amounts.len())];
+            nodes.push(
+                Move {
+                    quantum: info.quantum.clone(),
+                    amount,
+                }
+                .into(),
+            );
+            commuting_run = self.next_commuting_run(&commuting_run, quantum_move_index);
+        }
+        Alg { nodes }
+    }
+
+    fn can_follow(&self, commuting_run: &[usize], candidate: usize) -> bool {
+        if commuting_run.contains(&candidate) {
+            return false;
+        }
+        let commutes_with_run = commuting_run
+            .iter()
+            .all(|&previous| self.commutes[previous][candidate]);
+        if !commutes_with_run {
+            return true;
+        }
+        // Only allow a run of commuting moves in increasing order.
+        match commuting_run.last() {
+            Some(&last) => candidate > last,
+            None => true,
+        }
+    }
+}
+
+// `R++`/`R--` (Megaminx) and `/` (Square-1) can't be written with any other amount.
+fn is_writable_amount(quantum: &QuantumMove, amount: Amount) -> bool {
+    if quantum.family.ends_with("_PLUSPLUS_") {
+        amount.abs() == 1
+    } else if quantum.family == "_SLASH_" {
+        amount == 1
+    } else {
+        true
+    }
+}
+
+/// Returns every amount of the quantum move with a distinct non-identity
+/// transformation, preferring positive amounts and the smallest absolute
+/// value (e.g. `1`, `2`, `-1` for `R` on the 3x3x3).
+pub fn all_move_amounts(
+    kpuzzle: &KPuzzle,
+    quantum: &Arc<QuantumMove>,
+) -> Result<Vec<Amount>, InvalidAlgError> {
+    let transformation = kpuzzle.transformation_from_move(&Move {
+        quantum: quantum.clone(),
+        amount: 1,
+    })?;
+    let identity = kpuzzle.identity_transformation();
+    let mut order: Amount = 1;
+    let mut current = transformation.clone();
+    while current != identity {
+        current = current.apply_transformation(&transformation);
+        order += 1;
+    }
+    let mut amounts = Vec::<Amount>::default();
+    for amount in 1..=(order / 2) {
+        amounts.push(amount);
+    }
+    for amount in ((order / 2 + 1)..order).rev() {
+        amounts.push(amount - order);
+    }
+    Ok(amounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        alg::{AlgNode, QuantumMove},
+        puzzles::{cube3x3x3_kpuzzle, megaminx_kpuzzle, pyraminx_kpuzzle},
+    };
+
+    use super::{all_move_amounts, RandomMovesScrambler};
+
+    #[test]
+    fn generates_canonical_sequences() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let quantum_moves: Vec<QuantumMove> = ["U", "L", "F", "R", "B", "D"]
+            .iter()
+            .map(|family| QuantumMove::new(*family, None))
+            .collect();
+        let scrambler =
+            RandomMovesScrambler::try_new_with_all_amounts(kpuzzle, &quantum_moves).unwrap();
+        let alg = scrambler.random_moves(1000, &mut StdRng::seed_from_u64(7));
+        assert_eq!(alg.nodes.len(), 1000);
+
+        let families: Vec<String> = alg
+            .nodes
+            .iter()
+            .map(|node| match node {
+                AlgNode::MoveNode(r#move) => r#move.quantum.family.clone(),
+                _ => panic!("Expected only moves"),
+            })
+            .collect();
+        for window in families.windows(2) {
+            assert_ne!(window[0], window[1]);
+            // `U` before `D`, `L` before `R`, and `F` before `B`.
+            assert_ne!((window[0].as_str(), window[1].as_str()), ("D", "U"));
+            assert_ne!((window[0].as_str(), window[1].as_str()), ("R", "L"));
+            assert_ne!((window[0].as_str(), window[1].as_str()), ("B", "F"));
+        }
+
+        assert_eq!(
+            alg,
+            scrambler.random_moves(1000, &mut StdRng::seed_from_u64(7))
+        );
+    }
+
+    #[test]
+    fn avoids_dead_ends() {
+        // Each tip commutes with every move, so a tip can only be followed by a later tip.
+        let quantum_moves: Vec<QuantumMove> = ["U", "L", "R", "B", "u", "l", "r", "b"]
+            .iter()
+            .map(|family| QuantumMove::new(*family, None))
+            .collect();
+        let scrambler =
+            RandomMovesScrambler::try_new_with_all_amounts(pyraminx_kpuzzle(), &quantum_moves)
+                .unwrap();
+        for seed in 0..20 {
+            let alg = scrambler.random_moves(15, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(alg.nodes.len(), 15);
+        }
+
+        let scrambler = RandomMovesScrambler::try_new_with_all_amounts(
+            cube3x3x3_kpuzzle(),
+            &[QuantumMove::new("R", None)],
+        )
+        .unwrap();
+        assert_eq!(
+            scrambler
+                .random_moves(5, &mut StdRng::seed_from_u64(0))
+                .nodes
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn computes_move_amounts() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        assert_eq!(
+            all_move_amounts(kpuzzle, &QuantumMove::new("R", None).into()).unwrap(),
+            vec![1, 2, -1]
+        );
+        assert!(all_move_amounts(kpuzzle, &QuantumMove::new("Q", None).into()).is_err());
+    }
+
+    #[test]
+    fn skips_unwritable_amounts() {
+        let quantum_moves: Vec<QuantumMove> = ["R_PLUSPLUS_", "D_PLUSPLUS_", "U"]
+            .iter()
+            .map(|family| QuantumMove::new(*family, None))
+            .collect();
+        let scrambler =
+            RandomMovesScrambler::try_new_with_all_amounts(megaminx_kpuzzle(), &quantum_moves)
+                .unwrap();
+        let alg = scrambler.random_moves(50, &mut StdRng::seed_from_u64(8));
+        assert!(alg.nodes.iter().all(|node| match node {
+            AlgNode::MoveNode(r#move) => {
+                r#move.quantum.family == "U" || r#move.amount.abs() == 1
+            }
+            _ => false,
+        }));
+        // Every move can be printed.
+        assert_eq!(alg.to_string().split(' ').count(), alg.nodes.len());
+    }
+}
@@ -0,0 +1,385 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    alg::Move,
+    kpuzzle::{
+        permutation_parity, InvalidAlgError, KPattern, KPuzzle, KTransformation, OrientationWithMod,
+    },
+};
+
+/// A set of positions in an orbit that the generator moves can move pieces between.
+#[derive(Debug)]
+struct PositionComponent {
+    orbit_index: usize,
+    positions: Vec<u8>,
+    /// Whether the pieces in this component can be arranged with either
+    /// parity, independent of other components (e.g. because some pieces
+    /// are identical).
+    has_free_parity: bool,
+    /// The sum of orientations in this component is invariant modulo this value.
+    /// A value of 1 means that orientations are unconstrained.
+    orientation_sum_modulus: u8,
+}
+
+/// Generates uniformly random patterns that are reachable using a given set
+/// of generator moves.
+///
+/// Reachability is determined using invariants derived from the transformations
+/// of the generator moves:
+///
+/// - Pieces only move between positions that the generators connect.
+/// - Permutation parities (including parities shared between orbits, like the
+///   corners and edges of a 3x3x3) are restricted to the combinations that the
+///   generators can produce.
+/// - Orientation sums are restricted to the values that the generators can produce.
+///
+/// These invariants characterize the reachable patterns for most twisty
+/// puzzles (e.g. the 2x2x2 and 3x3x3 using face moves), but not for puzzles
+/// with more subtle constraints. In particular, whole-puzzle rotations should
+/// usually be excluded from the generators, since the generator will treat
+/// pieces like 3x3x3 centers as freely permutable.
+///
+/// Pass a seeded RNG (e.g. [`rand::rngs::StdRng::seed_from_u64(…)`][`rand::SeedableRng::seed_from_u64`]) for reproducible patterns.
+#[derive(Debug)]
+pub struct RandomPatternGenerator {
+    kpuzzle: KPuzzle,
+    components: Vec<PositionComponent>,
+    /// Basis vectors for the parity combinations that the generators can
+    /// produce, indexed by the components with constrained parity.
+    parity_basis: Vec<Vec<bool>>,
+    constrained_parity_component_indices: Vec<usize>,
+}
+
+impl RandomPatternGenerator {
+    pub fn try_new(
+        kpuzzle: impl Into<KPuzzle>,
+        generator_moves: &[Move],
+    ) -> Result<Self, InvalidAlgError> {
+        let kpuzzle: KPuzzle = kpuzzle.into();
+        let generators = generator_moves
+            .iter()
+            .map(|r#move| kpuzzle.transformation_from_move(r#move))
+            .collect::<Result<Vec<KTransformation>, InvalidAlgError>>()?;
+        let default_pattern = kpuzzle.default_pattern();
+
+        let mut components = Vec::<PositionComponent>::default();
+        for (orbit_index, orbit_info) in kpuzzle.orbit_info_iter().enumerate() {
+            let num_pieces = orbit_info.num_pieces as usize;
+
+            // Union-find over the positions in the orbit.
+            let mut parents: Vec<usize> = (0..num_pieces).collect();
+            for generator in &generators {
+                for i in 0..orbit_info.num_pieces {
+                    let from = generator.get_permutation_idx(orbit_info, i) as usize;
+                    let (root_a, root_b) =
+                        (find(&mut parents, i as usize), find(&mut parents, from));
+                    parents[root_a] = root_b;
+                }
+            }
+
+            let mut positions_by_root = vec![Vec::<u8>::default(); num_pieces];
+            for i in 0..num_pieces {
+                let root = find(&mut parents, i);
+                positions_by_root[root].push(i as u8);
+            }
+            for positions in positions_by_root {
+                if positions.is_empty() {
+                    continue;
+                }
+                let mut pieces: Vec<u8> = positions
+                    .iter()
+                    .map(|&i| default_pattern.get_piece(orbit_info, i))
+                    .collect();
+                pieces.sort();
+                let has_identical_pieces = pieces.windows(2).any(|w| w[0] == w[1]);
+
+                let mut orientation_sum_modulus = orbit_info.num_orientations;
+                for generator in &generators {
+                    let delta_sum: u32 = positions
+                        .iter()
+                        .map(|&i| generator.get_orientation_delta(orbit_info, i) as u32)
+                        .sum();
+                    orientation_sum_modulus = gcd(
+                        orientation_sum_modulus,
+                        (delta_sum % orbit_info.num_orientations as u32) as u8,
+                    );
+                }
+
+                components.push(PositionComponent {
+                    orbit_index,
+                    has_free_parity: has_identical_pieces || positions.len() < 2,
+                    positions,
+                    orientation_sum_modulus,
+                });
+            }
+        }
+
+        let constrained_parity_component_indices: Vec<usize> = components
+            .iter()
+            .enumerate()
+            .filter(|(_, component)| !component.has_free_parity)
+            .map(|(i, _)| i)
+            .collect();
+        let mut parity_basis = Vec::<Vec<bool>>::default();
+        for generator in &generators {
+            let parity_vector: Vec<bool> = constrained_parity_component_indices
+                .iter()
+                .map(|&component_index| {
+                    let component = &components[component_index];
+                    let orbit_info = &kpuzzle.data.ordered_orbit_info[component.orbit_index];
+                    // Re-index the permutation within the component.
+                    let local_permutation: Vec<u8> = component
+                        .positions
+                        .iter()
+                        .map(|&i| {
+                            let from = generator.get_permutation_idx(orbit_info, i);
+                            component.positions.iter().position(|&p| p == from).unwrap() as u8
+                        })
+                        .collect();
+                    permutation_parity(&local_permutation)
+                })
+                .collect();
+            add_to_basis(&mut parity_basis, parity_vector);
+        }
+
+        Ok(Self {
+            kpuzzle,
+            components,
+            parity_basis,
+            constrained_parity_component_indices,
+        })
+    }
+
+    pub fn kpuzzle(&self) -> &KPuzzle {
+        &self.kpuzzle
+    }
+
+    pub fn random_pattern<R: Rng + ?Sized>(&self, rng: &mut R) -> KPattern {
+        let default_pattern = self.kpuzzle.default_pattern();
+        let mut pattern = default_pattern.clone();
+
+        // Pick a uniformly random achievable combination of parities.
+        let mut target_parities = vec![false; self.components.len()];
+        for basis_vector in &self.parity_basis {
+            if rng.random_bool(0.5) {
+                for (i, &component_index) in
+                    self.constrained_parity_component_indices.iter().enumerate()
+                {
+                    target_parities[component_index] ^= basis_vector[i];
+                }
+            }
+        }
+
+        for (component, target_parity) in self.components.iter().zip(target_parities) {
+            let orbit_info = &self.kpuzzle.data.ordered_orbit_info[component.orbit_index];
+            let num_positions = component.positions.len();
+
+            let mut local_permutation: Vec<u8> = (0..num_positions as u8).collect();
+            local_permutation.shuffle(rng);
+            if !component.has_free_parity && permutation_parity(&local_permutation) != target_parity
+            {
+                local_permutation.swap(0, 1);
+            }
+
+            let mut orientation_sum: u32 = 0;
+            let mut target_orientation_sum: u32 = 0;
+            let mut shared_orientation_mod: Option<u8> = None;
+            let mut orientation_mods_are_shared = true;
+            for (i, &position) in component.positions.iter().enumerate() {
+                let from_position = component.positions[local_permutation[i] as usize];
+                let from_orientation_with_mod =
+                    default_pattern.get_orientation_with_mod(orbit_info, from_position);
+                let orientation_mod = from_orientation_with_mod.orientation_mod;
+                let effective_mod = match orientation_mod {
+                    0 => orbit_info.num_orientations,
+                    orientation_mod => orientation_mod,
+                };
+                match shared_orientation_mod {
+                    None => shared_orientation_mod = Some(effective_mod),
+                    Some(shared) => orientation_mods_are_shared &= shared == effective_mod,
+                }
+                target_orientation_sum += from_orientation_with_mod.orientation as u32;
+
+                let orientation = rng.random_range(0..effective_mod);
+                orientation_sum += orientation as u32;
+                pattern.set_piece(
+                    orbit_info,
+                    position,
+                    default_pattern.get_piece(orbit_info, from_position),
+                );
+                pattern.set_orientation_with_mod(
+                    orbit_info,
+                    position,
+                    &OrientationWithMod {
+                        orientation,
+                        orientation_mod,
+                    },
+                );
+            }
+
+            // Adjust the last orientation to satisfy the orientation sum invariant.
+            // We can only express the invariant if all pieces have the same orientation mod.
+            let modulus = match shared_orientation_mod {
+                Some(shared) if orientation_mods_are_shared => {
+                    gcd(shared, component.orientation_sum_modulus)
+                }
+                _ => 1,
+            };
+            if modulus > 1 {
+                let modulus = modulus as u32;
+                let last_position = component.positions[num_positions - 1];
+                let last = pattern.get_orientation_with_mod(orbit_info, last_position);
+                let correction =
+                    (target_orientation_sum + modulus - orientation_sum % modulus) % modulus;
+                let effective_mod = shared_orientation_mod.unwrap() as u32;
+                pattern.set_orientation_with_mod(
+                    orbit_info,
+                    last_position,
+                    &OrientationWithMod {
+                        orientation: ((last.orientation as u32 + correction) % effective_mod) as u8,
+                        orientation_mod: last.orientation_mod,
+                    },
+                );
+            }
+        }
+        pattern
+    }
+}
+
+/// Generates a uniformly random pattern for the given puzzle that is reachable using the generator moves.
+///
+/// The generators should not include whole-puzzle rotations (or moves like the
+/// 3x3x3 `M` that are equivalent to a rotation combined with face moves), since
+/// these move reference pieces like 3x3x3 centers. See [`RandomPatternGenerator`] for other caveats.
+///
+/// ```
+/// use cubing_core::{alg::Move, puzzles::cube3x3x3_kpuzzle, scramble::random_pattern};
+///
+/// let face_moves: Vec<Move> = ["U", "L", "F", "R", "B", "D"].iter().map(|m| m.parse().unwrap()).collect();
+/// let pattern = random_pattern(cube3x3x3_kpuzzle(), &face_moves, &mut rand::rng()).unwrap();
+/// ```
+pub fn random_pattern<R: Rng + ?Sized>(
+    kpuzzle: impl Into<KPuzzle>,
+    generator_moves: &[Move],
+    rng: &mut R,
+) -> Result<KPattern, InvalidAlgError> {
+    Ok(RandomPatternGenerator::try_new(kpuzzle, generator_moves)?.random_pattern(rng))
+}
+
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+fn gcd(a: u8, b: u8) -> u8 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Adds `vector` to a GF(2) basis in row echelon form, if it is linearly independent of the existing basis vectors.
+fn add_to_basis(basis: &mut Vec<Vec<bool>>, mut vector: Vec<bool>) {
+    for basis_vector in basis.iter() {
+        let pivot = basis_vector.iter().position(|&b| b).unwrap();
+        if vector[pivot] {
+            for (v, b) in vector.iter_mut().zip(basis_vector) {
+                *v ^= b;
+            }
+        }
+    }
+    if let Some(pivot) = vector.iter().position(|&b| b) {
+        // Keep the basis reduced so that later pivots are eliminated from earlier vectors.
+        for basis_vector in basis.iter_mut() {
+            if basis_vector[pivot] {
+                for (b, v) in basis_vector.iter_mut().zip(&vector) {
+                    *b ^= v;
+                }
+            }
+        }
+        basis.push(vector);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        alg::Move,
+        kpuzzle::{permutation_parity, KPattern},
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle},
+    };
+
+    use super::{random_pattern, RandomPatternGenerator};
+
+    fn moves(s: &str) -> Vec<Move> {
+        s.split(' ').map(|m| m.parse().unwrap()).collect()
+    }
+
+    fn orbit_pieces_and_orientation_sum(pattern: &KPattern, orbit_index: usize) -> (Vec<u8>, u32) {
+        let orbit_info = &pattern.kpuzzle().data.ordered_orbit_info[orbit_index];
+        let pieces = (0..orbit_info.num_pieces)
+            .map(|i| pattern.get_piece(orbit_info, i))
+            .collect();
+        let orientation_sum = (0..orbit_info.num_pieces)
+            .map(|i| pattern.get_orientation_with_mod(orbit_info, i).orientation as u32)
+            .sum();
+        (pieces, orientation_sum)
+    }
+
+    #[test]
+    fn random_3x3x3_patterns_satisfy_invariants() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let generator = RandomPatternGenerator::try_new(kpuzzle, &moves("U L F R B D")).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut seen_odd_parity = false;
+        for _ in 0..100 {
+            let pattern = generator.random_pattern(&mut rng);
+            let (edges, edge_orientation_sum) = orbit_pieces_and_orientation_sum(&pattern, 0);
+            let (corners, corner_orientation_sum) = orbit_pieces_and_orientation_sum(&pattern, 1);
+            let (centers, _) = orbit_pieces_and_orientation_sum(&pattern, 2);
+            assert_eq!(permutation_parity(&edges), permutation_parity(&corners));
+            seen_odd_parity |= permutation_parity(&edges);
+            assert_eq!(edge_orientation_sum % 2, 0);
+            assert_eq!(corner_orientation_sum % 3, 0);
+            assert_eq!(centers, vec![0, 1, 2, 3, 4, 5]);
+        }
+        assert!(seen_odd_parity);
+    }
+
+    #[test]
+    fn random_2x2x2_patterns_keep_fixed_corner() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let generator = RandomPatternGenerator::try_new(kpuzzle, &moves("U R F")).unwrap();
+        let corners = &kpuzzle.data.ordered_orbit_info[0];
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..100 {
+            let pattern = generator.random_pattern(&mut rng);
+            assert_eq!(pattern.get_piece(corners, 6), 6);
+            assert_eq!(pattern.get_orientation_with_mod(corners, 6).orientation, 0);
+        }
+    }
+
+    #[test]
+    fn seeded_patterns_are_reproducible() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let face_moves = moves("U L F R B D");
+        let pattern1 = random_pattern(kpuzzle, &face_moves, &mut StdRng::seed_from_u64(5)).unwrap();
+        let pattern2 = random_pattern(kpuzzle, &face_moves, &mut StdRng::seed_from_u64(5)).unwrap();
+        let pattern3 = random_pattern(kpuzzle, &face_moves, &mut StdRng::seed_from_u64(6)).unwrap();
+        assert_eq!(pattern1, pattern2);
+        assert_ne!(pattern1, pattern3);
+        // Face moves never move the centers.
+        let centers = &kpuzzle.data.ordered_orbit_info[2];
+        for i in 0..centers.num_pieces {
+            assert_eq!(pattern1.get_piece(centers, i), i);
+        }
+        assert!(random_pattern(kpuzzle, &moves("U Q"), &mut StdRng::seed_from_u64(5)).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt::Display;
+
+use crate::kpuzzle::KPuzzle;
+
+mod cube2x2x2;
+pub use cube2x2x2::Cube2x2x2Solver;
+
+mod iddfs;
+pub use iddfs::{IddfsOptions, IddfsSolver};
+
+mod two_phase;
+pub use two_phase::{TwoPhaseOptions, TwoPhaseSolver};
+
+/// An error due to a pattern that a solver cannot handle (e.g. because it is not reachable using the solver's moves).
+#[derive(Debug)]
+pub struct SearchError {
+    pub description: String,
+}
+
+impl From<String> for SearchError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for SearchError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+/// Builds a table of the distance from `start_index` to every index, using breadth-first search.
+///
+/// Each index is interpreted as `first * second_move_table.len() + second`, where
+/// the coordinates `first` and `second` are transformed by the given move tables.
+fn build_pruning_table<const NUM_MOVES: usize>(
+    first_move_table: &[[u16; NUM_MOVES]],
+    second_move_table: &[[u16; NUM_MOVES]],
+    start_index: usize,
+) -> Vec<u8> {
+    const UNKNOWN: u8 = u8::MAX;
+    let second_size = second_move_table.len();
+    let size = first_move_table.len() * second_size;
+    let mut table = vec![UNKNOWN; size];
+    table[start_index] = 0;
+    let mut num_filled = 1;
+    let mut depth = 0;
+    while num_filled < size {
+        let mut num_filled_at_depth = 0;
+        for index in 0..size {
+            if table[index] != depth {
+                continue;
+            }
+            let (first, second) = (index / second_size, index % second_size);
+            for move_index in 0..NUM_MOVES {
+                let next = first_move_table[first][move_index] as usize * second_size
+                    + second_move_table[second][move_index] as usize;
+                if table[next] == UNKNOWN {
+                    table[next] = depth + 1;
+                    num_filled_at_depth += 1;
+                }
+            }
+        }
+        if num_filled_at_depth == 0 {
+            break;
+        }
+        num_filled += num_filled_at_depth;
+        depth += 1;
+    }
+    table
+}
+
+/// Whether the puzzles have the same orbits (in the same order), so that a
+/// solver for one can read patterns for the other.
+fn has_same_orbits(kpuzzle: &KPuzzle, expected: &KPuzzle) -> bool {
+    let orbits = &kpuzzle.data.ordered_orbit_info;
+    let expected_orbits = &expected.data.ordered_orbit_info;
+    orbits.len() == expected_orbits.len()
+        && orbits
+            .iter()
+            .zip(expected_orbits)
+            .all(|(orbit, expected_orbit)| {
+                orbit.name == expected_orbit.name
+                    && orbit.num_pieces == expected_orbit.num_pieces
+                    && orbit.num_orientations == expected_orbit.num_orientations
+            })
+}
@@ -0,0 +1,359 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::{
+    alg::{Alg, AlgNode, Amount, Move, QuantumMove},
+    kpuzzle::{KPattern, KPatternRanker, KPatternRankerOrbit, KTransformation, OrientationRanking},
+    puzzles::cube2x2x2_kpuzzle,
+};
+
+use super::{build_pruning_table, has_same_orbits, SearchError};
+
+// These moves keep the `DLB` corner fixed.
+const FACES: [&str; 3] = ["U", "R", "F"];
+const AMOUNTS: [Amount; 3] = [1, 2, -1];
+const NUM_MOVES: usize = FACES.len() * AMOUNTS.len();
+
+/// The position (and piece) of the `DLB` corner, in Reid order.
+const FIXED_CORNER: u8 = 6;
+
+fn face(move_index: usize) -> usize {
+    move_index / AMOUNTS.len()
+}
+
+fn move_for_index(move_index: usize) -> Move {
+    Move {
+        quantum: QuantumMove::new(FACES[face(move_index)], None).into(),
+        amount: AMOUNTS[move_index % AMOUNTS.len()],
+    }
+}
+
+/// Ranks the corners other than `DLB`. With both `rank_permutation` and
+/// `orientation` enabled, the rank is `permutation * 729 + orientation`.
+fn corner_ranker(rank_permutation: bool, orientation: OrientationRanking) -> KPatternRanker {
+    KPatternRanker::try_new(
+        cube2x2x2_kpuzzle(),
+        vec![KPatternRankerOrbit {
+            positions: Some(vec![0, 1, 2, 3, 4, 5, 7]),
+            rank_permutation,
+            orientation,
+            ..KPatternRankerOrbit::new("CORNERS")
+        }],
+    )
+    .expect("The 2x2x2 corners should be rankable.")
+}
+
+fn build_move_table(
+    ranker: &KPatternRanker,
+    moves: &[KTransformation; NUM_MOVES],
+) -> Vec<[u16; NUM_MOVES]> {
+    (0..ranker.num_ranks())
+        .map(|rank| {
+            let pattern = ranker
+                .unrank(rank)
+                .expect("Ranks in range should be valid.");
+            moves.each_ref().map(|transformation| {
+                ranker
+                    .rank(&pattern.apply_transformation(transformation))
+                    .expect("Moves should keep the `DLB` corner fixed.") as u16
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct Cube2x2x2Tables {
+    ranker: KPatternRanker,
+    /// The 24 whole-puzzle rotations, used to bring the `DLB` corner to its solved position.
+    rotations: Vec<(Alg, KTransformation)>,
+    permutation_move: Vec<[u16; NUM_MOVES]>,
+    orientation_move: Vec<[u16; NUM_MOVES]>,
+    /// The optimal solution length for every rank of `ranker`.
+    distances: Vec<u8>,
+}
+
+impl Cube2x2x2Tables {
+    fn new() -> Self {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let moves: [KTransformation; NUM_MOVES] = std::array::from_fn(|move_index| {
+            kpuzzle
+                .transformation_from_move(&move_for_index(move_index))
+                .expect("Face moves should be valid for the 2x2x2.")
+        });
+
+        let mut rotations = Vec::<(Alg, KTransformation)>::default();
+        for first in ["", "x", "x2", "x'", "z", "z'"] {
+            for second in ["", "y", "y2", "y'"] {
+                let alg = format!("{} {}", first, second)
+                    .trim()
+                    .parse::<Alg>()
+                    .expect("Rotations should always parse.");
+                let transformation = kpuzzle
+                    .transformation_from_alg(&alg)
+                    .expect("Rotations should be valid for the 2x2x2.");
+                rotations.push((alg, transformation));
+            }
+        }
+
+        let permutation_move =
+            build_move_table(&corner_ranker(true, OrientationRanking::Ignore), &moves);
+        let orientation_move = build_move_table(
+            &corner_ranker(false, OrientationRanking::ConstrainedSum),
+            &moves,
+        );
+        let distances = build_pruning_table(&permutation_move, &orientation_move, 0);
+        Self {
+            ranker: corner_ranker(true, OrientationRanking::ConstrainedSum),
+            rotations,
+            permutation_move,
+            orientation_move,
+            distances,
+        }
+    }
+
+    fn next_rank(&self, rank: usize, move_index: usize) -> usize {
+        let num_orientations = self.orientation_move.len();
+        let (permutation, orientation) = (rank / num_orientations, rank % num_orientations);
+        self.permutation_move[permutation][move_index] as usize * num_orientations
+            + self.orientation_move[orientation][move_index] as usize
+    }
+}
+
+/// Finds optimal solutions (in HTM) for patterns of [`cube2x2x2_kpuzzle`],
+/// using a table of the distance of every pattern from the solved pattern.
+///
+/// Solutions use the moves `U`, `R`, and `F`, preceded by a whole-puzzle
+/// rotation if the `DLB` corner of the pattern is not solved.
+///
+/// ```
+/// use cubing_core::{alg::Alg, puzzles::cube2x2x2_kpuzzle, search::Cube2x2x2Solver};
+///
+/// let kpuzzle = cube2x2x2_kpuzzle();
+/// let pattern = kpuzzle
+///     .default_pattern()
+///     .apply_alg(&"R U R' F2".parse::<Alg>().unwrap())
+///     .unwrap();
+///
+/// let solver = Cube2x2x2Solver::new();
+/// assert_eq!(solver.distance(&pattern).unwrap(), 4);
+/// assert_eq!(solver.solve(&pattern).unwrap(), "F2 R U' R'".parse::<Alg>().unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cube2x2x2Solver {
+    tables: Arc<Cube2x2x2Tables>,
+}
+
+impl Default for Cube2x2x2Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cube2x2x2Solver {
+    /// Constructs a solver using tables that are shared by all solvers. The
+    /// tables are generated the first time they are needed.
+    pub fn new() -> Self {
+        static TABLES: OnceLock<Arc<Cube2x2x2Tables>> = OnceLock::new();
+        Self {
+            tables: TABLES
+                .get_or_init(|| Arc::new(Cube2x2x2Tables::new()))
+                .clone(),
+        }
+    }
+
+    /// Returns the number of moves in an optimal solution (not counting rotations).
+    pub fn distance(&self, pattern: &KPattern) -> Result<usize, SearchError> {
+        let (_, rank) = self.rotation_and_rank(pattern)?;
+        Ok(self.tables.distances[rank] as usize)
+    }
+
+    /// Returns an optimal solution for the pattern.
+    pub fn solve(&self, pattern: &KPattern) -> Result<Alg, SearchError> {
+        let (rotation, mut rank) = self.rotation_and_rank(pattern)?;
+        let mut solution = Vec::<usize>::default();
+        while self.tables.distances[rank] > 0 {
+            let (move_index, next_rank) = (0..NUM_MOVES)
+                .map(|move_index| (move_index, self.tables.next_rank(rank, move_index)))
+                .find(|&(_, next_rank)| {
+                    self.tables.distances[next_rank] < self.tables.distances[rank]
+                })
+                .expect("Every unsolved pattern has a neighbor that is closer to being solved.");
+            solution.push(move_index);
+            rank = next_rank;
+        }
+        Ok(solution_alg(rotation, &solution))
+    }
+
+    /// Returns every optimal solution for the pattern (ordered by moves, using
+    /// the order `U`, `U2`, `U'`, `R`, …, `F'`).
+    pub fn all_optimal_solutions(&self, pattern: &KPattern) -> Result<Vec<Alg>, SearchError> {
+        let (rotation, rank) = self.rotation_and_rank(pattern)?;
+        let mut solutions = Vec::<Alg>::default();
+        self.all_optimal_solutions_recursive(rotation, rank, &mut vec![], &mut solutions);
+        Ok(solutions)
+    }
+
+    fn all_optimal_solutions_recursive(
+        &self,
+        rotation: &Alg,
+        rank: usize,
+        solution: &mut Vec<usize>,
+        solutions: &mut Vec<Alg>,
+    ) {
+        let distance = self.tables.distances[rank];
+        if distance == 0 {
+            solutions.push(solution_alg(rotation, solution));
+            return;
+        }
+        for move_index in 0..NUM_MOVES {
+            let next_rank = self.tables.next_rank(rank, move_index);
+            if self.tables.distances[next_rank] < distance {
+                solution.push(move_index);
+                self.all_optimal_solutions_recursive(rotation, next_rank, solution, solutions);
+                solution.pop();
+            }
+        }
+    }
+
+    /// Returns the number of patterns (up to rotation) at each distance from
+    /// the solved pattern, indexed by distance.
+    ///
+    /// The last index is God's number for the 2x2x2 (in HTM), and the sum is
+    /// the number of patterns.
+    pub fn distance_histogram(&self) -> Vec<u64> {
+        let mut histogram = Vec::<u64>::default();
+        for &distance in &self.tables.distances {
+            let distance = distance as usize;
+            if histogram.len() <= distance {
+                histogram.resize(distance + 1, 0);
+            }
+            histogram[distance] += 1;
+        }
+        histogram
+    }
+
+    fn rotation_and_rank(&self, pattern: &KPattern) -> Result<(&Alg, usize), SearchError> {
+        let kpuzzle = pattern.kpuzzle();
+        if !has_same_orbits(kpuzzle, cube2x2x2_kpuzzle()) {
+            return Err("The pattern must be for the built-in 2x2x2 puzzle.".into());
+        }
+        let corners = &kpuzzle.data.ordered_orbit_info[0];
+
+        let mut seen = [false; 8];
+        let mut orientation_sum: u32 = 0;
+        for i in 0..8 {
+            let piece = pattern.get_piece(corners, i);
+            if piece >= 8 || std::mem::replace(&mut seen[piece as usize], true) {
+                return Err("The pattern contains duplicate pieces.".into());
+            }
+            orientation_sum += pattern.get_orientation_with_mod(corners, i).orientation as u32;
+        }
+        if !orientation_sum.is_multiple_of(3) {
+            return Err("The pattern has a twisted corner.".into());
+        }
+
+        for (rotation, transformation) in &self.tables.rotations {
+            let rotated = pattern.apply_transformation(transformation);
+            if rotated.get_piece(corners, FIXED_CORNER) == FIXED_CORNER
+                && rotated
+                    .get_orientation_with_mod(corners, FIXED_CORNER)
+                    .orientation
+                    == 0
+            {
+                let rank = self
+                    .tables
+                    .ranker
+                    .rank(&rotated)
+                    .map_err(|e| SearchError::from(e.description))?;
+                return Ok((rotation, rank as usize));
+            }
+        }
+        Err("No rotation brings the `DLB` corner to its solved position.".into())
+    }
+}
+
+fn solution_alg(rotation: &Alg, solution: &[usize]) -> Alg {
+    let mut nodes = rotation.nodes.clone();
+    nodes.extend(
+        solution
+            .iter()
+            .map(|&move_index| AlgNode::MoveNode(move_for_index(move_index))),
+    );
+    Alg { nodes }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{alg::Alg, puzzles::cube2x2x2_kpuzzle, scramble::RandomPatternGenerator};
+
+    use super::Cube2x2x2Solver;
+
+    #[test]
+    fn solves_optimally() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let solver = Cube2x2x2Solver::new();
+        let generator = RandomPatternGenerator::try_new(
+            kpuzzle,
+            &["U", "R", "F"].map(|r#move| r#move.parse().unwrap()),
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..10 {
+            let pattern = generator.random_pattern(&mut rng);
+            let solution = solver.solve(&pattern).unwrap();
+            assert_eq!(solution.nodes.len(), solver.distance(&pattern).unwrap());
+            assert_eq!(
+                pattern.apply_alg(&solution).unwrap(),
+                kpuzzle.default_pattern()
+            );
+        }
+    }
+
+    #[test]
+    fn finds_all_optimal_solutions() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let solver = Cube2x2x2Solver::new();
+        let pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&"R U2 F' R2".parse::<Alg>().unwrap())
+            .unwrap();
+        let solutions = solver.all_optimal_solutions(&pattern).unwrap();
+        assert!(solutions.contains(&"R2 F U2 R'".parse::<Alg>().unwrap()));
+        for (i, solution) in solutions.iter().enumerate() {
+            assert_eq!(solution.nodes.len(), 4);
+            assert_eq!(
+                pattern.apply_alg(solution).unwrap(),
+                kpuzzle.default_pattern()
+            );
+            assert!(!solutions[..i].contains(solution));
+        }
+    }
+
+    #[test]
+    fn solves_rotated_patterns() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let solver = Cube2x2x2Solver::new();
+        let pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&"x y".parse::<Alg>().unwrap())
+            .unwrap();
+        assert_eq!(solver.distance(&pattern).unwrap(), 0);
+        assert_eq!(
+            pattern.apply_alg(&solver.solve(&pattern).unwrap()).unwrap(),
+            kpuzzle.default_pattern()
+        );
+
+        let pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&"L D' B2 z".parse::<Alg>().unwrap())
+            .unwrap();
+        assert_eq!(solver.distance(&pattern).unwrap(), 3);
+        for solution in solver.all_optimal_solutions(&pattern).unwrap() {
+            assert_eq!(
+                pattern.apply_alg(&solution).unwrap(),
+                kpuzzle.default_pattern()
+            );
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alg::{Alg, AlgNode, Move, QuantumMove},
+    kpuzzle::{KPattern, KPuzzle, KTransformation},
+    scramble::all_move_amounts,
+};
+
+use super::{has_same_orbits, SearchError};
+
+/// Options for [`IddfsSolver::solve`].
+#[derive(Debug, Clone)]
+pub struct IddfsOptions {
+    /// The maximum number of moves in a solution.
+    pub max_depth: usize,
+    /// Stop after finding this many solutions.
+    pub max_num_solutions: usize,
+    /// Stop searching (returning the solutions found so far) after this amount of time.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for IddfsOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            max_num_solutions: 1,
+            timeout: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SearchMove {
+    r#move: Move,
+    quantum_index: usize,
+    transformation: KTransformation,
+}
+
+/// Finds solutions for patterns of any puzzle using a given set of moves,
+/// using iterative deepening depth-first search.
+///
+/// This does not use any pruning tables, so it is only practical for short
+/// solutions (or small sets of moves). Solutions are found in order of length,
+/// and only canonical sequences are searched (e.g. `R L` is searched, but not
+/// `L R` or `R R2`).
+///
+/// ```
+/// use cubing_core::{alg::{Alg, QuantumMove}, puzzles::cube3x3x3_kpuzzle, search::{IddfsOptions, IddfsSolver}};
+///
+/// let kpuzzle = cube3x3x3_kpuzzle();
+/// let pattern = kpuzzle
+///     .default_pattern()
+///     .apply_alg(&"R U R' U R U2 R'".parse::<Alg>().unwrap())
+///     .unwrap();
+///
+/// let solver = IddfsSolver::try_new(kpuzzle, &[QuantumMove::new("R", None), QuantumMove::new("U", None)]).unwrap();
+/// let solutions = solver.solve(&pattern, &IddfsOptions::default()).unwrap();
+/// assert_eq!(solutions, vec!["R U2 R' U' R U' R'".parse::<Alg>().unwrap()]);
+/// ```
+#[derive(Debug)]
+pub struct IddfsSolver {
+    kpuzzle: KPuzzle,
+    moves: Vec<SearchMove>,
+    // Indexed by quantum move index.
+    commutes: Vec<Vec<bool>>,
+}
+
+/// How often (in search nodes) to check the deadline.
+const DEADLINE_CHECK_INTERVAL: usize = 1 << 12;
+
+struct IddfsSearch<'a> {
+    solver: &'a IddfsSolver,
+    target: KPattern,
+    max_num_solutions: usize,
+    deadline: Option<Instant>,
+    num_nodes: usize,
+    timed_out: bool,
+    solution: Vec<usize>,
+    solutions: Vec<Alg>,
+}
+
+impl IddfsSolver {
+    /// Constructs a solver using every amount of the given quantum moves (e.g. `R`, `R2`, and `R'` for `R` on a cube).
+    pub fn try_new(
+        kpuzzle: impl Into<KPuzzle>,
+        quantum_moves: &[QuantumMove],
+    ) -> Result<Self, SearchError> {
+        let kpuzzle: KPuzzle = kpuzzle.into();
+        let mut moves = Vec::<SearchMove>::default();
+        let mut quantum_transformations = Vec::<KTransformation>::default();
+        for (quantum_index, quantum_move) in quantum_moves.iter().enumerate() {
+            let quantum = Arc::new(quantum_move.clone());
+            let to_search_error = |e: crate::kpuzzle::InvalidAlgError| SearchError {
+                description: e.to_string(),
+            };
+            quantum_transformations.push(
+                kpuzzle
+                    .transformation_from_move(&Move {
+                        quantum: quantum.clone(),
+                        amount: 1,
+                    })
+                    .map_err(to_search_error)?,
+            );
+            for amount in all_move_amounts(&kpuzzle, &quantum).map_err(to_search_error)? {
+                let r#move = Move {
+                    quantum: quantum.clone(),
+                    amount,
+                };
+                let transformation = kpuzzle
+                    .transformation_from_move(&r#move)
+                    .map_err(to_search_error)?;
+                moves.push(SearchMove {
+                    r#move,
+                    quantum_index,
+                    transformation,
+                });
+            }
+        }
+        let commutes = quantum_transformations
+            .iter()
+            .map(|t1| {
+                quantum_transformations
+                    .iter()
+                    .map(|t2| t1.apply_transformation(t2) == t2.apply_transformation(t1))
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            kpuzzle,
+            moves,
+            commutes,
+        })
+    }
+
+    pub fn kpuzzle(&self) -> &KPuzzle {
+        &self.kpuzzle
+    }
+
+    /// Returns up to `options.max_num_solutions` solutions (that take the
+    /// pattern to the default pattern) with at most `options.max_depth` moves,
+    /// shortest first. If `options.timeout` passes, only the solutions found
+    /// so far are returned.
+    pub fn solve(
+        &self,
+        pattern: &KPattern,
+        options: &IddfsOptions,
+    ) -> Result<Vec<Alg>, SearchError> {
+        if !has_same_orbits(pattern.kpuzzle(), &self.kpuzzle) {
+            return Err(format!(
+                "Expected a {} pattern, found a {} pattern.",
+                self.kpuzzle.definition().name,
+                pattern.kpuzzle().definition().name
+            )
+            .into());
+        }
+        let mut search = IddfsSearch {
+            solver: self,
+            target: self.kpuzzle.default_pattern(),
+            max_num_solutions: options.max_num_solutions,
+            deadline: options.timeout.map(|timeout| Instant::now() + timeout),
+            num_nodes: 0,
+            timed_out: false,
+            solution: vec![],
+            solutions: vec![],
+        };
+        for depth in 0..=options.max_depth {
+            search.search(pattern, &[], depth);
+            if search.solutions.len() >= search.max_num_solutions || search.timed_out {
+                break;
+            }
+        }
+        Ok(search.solutions)
+    }
+
+    // Same as `RandomMovesScrambler`: a move can follow a run of mutually
+    // commuting moves only if it does not commute with all of them, or sorts
+    // after the last one.
+    fn can_follow(&self, commuting_run: &[usize], candidate: usize) -> bool {
+        if commuting_run.contains(&candidate) {
+            return false;
+        }
+        let commutes_with_run = commuting_run
+            .iter()
+            .all(|&previous| self.commutes[previous][candidate]);
+        if !commutes_with_run {
+            return true;
+        }
+        match commuting_run.last() {
+            Some(&last) => candidate > last,
+            None => true,
+        }
+    }
+}
+
+impl IddfsSearch<'_> {
+    fn check_deadline(&mut self) -> bool {
+        self.num_nodes += 1;
+        if self.num_nodes.is_multiple_of(DEADLINE_CHECK_INTERVAL) {
+            if let Some(deadline) = self.deadline {
+                self.timed_out |= Instant::now() > deadline;
+            }
+        }
+        self.timed_out
+    }
+
+    fn search(&mut self, pattern: &KPattern, commuting_run: &[usize], remaining: usize) {
+        if self.solutions.len() >= self.max_num_solutions || self.check_deadline() {
+            return;
+        }
+        if remaining == 0 {
+            if *pattern == self.target {
+                self.solutions.push(Alg {
+                    nodes: self
+                        .solution
+                        .iter()
+                        .map(|&move_index| {
+                            AlgNode::MoveNode(self.solver.moves[move_index].r#move.clone())
+                        })
+                        .collect(),
+                });
+            }
+            return;
+        }
+        for (move_index, search_move) in self.solver.moves.iter().enumerate() {
+            let quantum_index = search_move.quantum_index;
+            if !self.solver.can_follow(commuting_run, quantum_index) {
+                continue;
+            }
+            let next_commuting_run = if commuting_run
+                .iter()
+                .all(|&previous| self.solver.commutes[previous][quantum_index])
+            {
+                [commuting_run, &[quantum_index]].concat()
+            } else {
+                vec![quantum_index]
+            };
+            self.solution.push(move_index);
+            self.search(
+                &pattern.apply_transformation(&search_move.transformation),
+                &next_commuting_run,
+                remaining - 1,
+            );
+            self.solution.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alg::{Alg, QuantumMove},
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle},
+    };
+
+    use std::time::{Duration, Instant};
+
+    use super::{IddfsOptions, IddfsSolver};
+
+    #[test]
+    fn finds_solutions_in_order() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let solver = IddfsSolver::try_new(
+            kpuzzle,
+            &["R", "U"].map(|family| QuantumMove::new(family, None)),
+        )
+        .unwrap();
+        let pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&"R U R' U'".parse::<Alg>().unwrap())
+            .unwrap();
+        let solutions = solver
+            .solve(
+                &pattern,
+                &IddfsOptions {
+                    max_depth: 9,
+                    max_num_solutions: 3,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(solutions.len(), 3);
+        assert_eq!(solutions[0], "U R U' R'".parse::<Alg>().unwrap());
+        for solution in &solutions {
+            assert_eq!(
+                pattern.apply_alg(solution).unwrap(),
+                kpuzzle.default_pattern()
+            );
+        }
+        assert_eq!(solutions[1].nodes.len(), 6);
+
+        assert_eq!(
+            solver
+                .solve(&kpuzzle.default_pattern(), &IddfsOptions::default())
+                .unwrap(),
+            vec![Alg::default()]
+        );
+        let unreachable = kpuzzle
+            .default_pattern()
+            .apply_alg(&"F".parse::<Alg>().unwrap())
+            .unwrap();
+        assert_eq!(
+            solver
+                .solve(
+                    &unreachable,
+                    &IddfsOptions {
+                        max_depth: 3,
+                        ..Default::default()
+                    }
+                )
+                .unwrap(),
+            vec![]
+        );
+        assert!(solver
+            .solve(
+                &cube3x3x3_kpuzzle().default_pattern(),
+                &IddfsOptions::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn respects_timeout() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let solver = IddfsSolver::try_new(
+            kpuzzle,
+            &["U", "L", "F", "R", "B", "D"].map(|family| QuantumMove::new(family, None)),
+        )
+        .unwrap();
+        let pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&"R U F D L B R U".parse::<Alg>().unwrap())
+            .unwrap();
+        let start = Instant::now();
+        let solutions = solver
+            .solve(
+                &pattern,
+                &IddfsOptions {
+                    max_depth: 12,
+                    timeout: Some(Duration::from_millis(100)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(solutions.is_empty());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
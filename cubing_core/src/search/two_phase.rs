@@ -0,0 +1,922 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alg::{Alg, AlgNode, Amount, Move, QuantumMove},
+    kpuzzle::{
+        combination_rank, combination_unrank, permutation_parity, permutation_rank,
+        permutation_unrank, KPattern, KPuzzle, KPuzzleOrbitInfo, KTransformation,
+    },
+    puzzles::cube3x3x3_kpuzzle,
+};
+
+use super::{build_pruning_table, has_same_orbits, SearchError};
+
+const FACES: [&str; 6] = ["U", "L", "F", "R", "B", "D"];
+// Indexed by face. Faces on the same axis commute.
+const AXES: [usize; 6] = [0, 1, 2, 1, 2, 0];
+const AMOUNTS: [Amount; 3] = [1, 2, -1];
+
+const NUM_MOVES: usize = FACES.len() * AMOUNTS.len();
+/// Indices (into the 18 face moves) of `U`, `U2`, `U'`, `L2`, `F2`, `R2`, `B2`, `D`, `D2`, `D'`.
+const PHASE2_MOVES: [usize; NUM_PHASE2_MOVES] = [0, 1, 2, 4, 7, 10, 13, 15, 16, 17];
+const NUM_PHASE2_MOVES: usize = 10;
+/// Phase 2 can always be completed in this many moves.
+const MAX_PHASE2_LENGTH: usize = 18;
+
+const NUM_TWISTS: usize = 2187; // 3^7
+const NUM_FLIPS: usize = 2048; // 2^11
+const NUM_SLICES: usize = 495; // 12 choose 4
+const NUM_CORNER_PERMUTATIONS: usize = 40320; // 8!
+const NUM_UD_EDGE_PERMUTATIONS: usize = 40320; // 8!
+const NUM_SLICE_PERMUTATIONS: usize = 24; // 4!
+
+/// The first edge (in Reid order) that belongs in the E slice: `FR FL BR BL`.
+const FIRST_SLICE_EDGE: u8 = 8;
+
+fn face(move_index: usize) -> usize {
+    move_index / AMOUNTS.len()
+}
+
+/// Whether `move_index` can follow `previous` in a canonical sequence: moves
+/// on the same face are never consecutive, and moves on opposite faces are only
+/// used in a fixed order (e.g. `U D` but not `D U`).
+fn can_follow(previous: Option<usize>, move_index: usize) -> bool {
+    match previous {
+        None => true,
+        Some(previous) => {
+            let (previous_face, face) = (face(previous), face(move_index));
+            previous_face != face && !(AXES[previous_face] == AXES[face] && previous_face > face)
+        }
+    }
+}
+
+fn is_phase2_move(move_index: usize) -> bool {
+    PHASE2_MOVES.contains(&move_index)
+}
+
+fn move_for_index(move_index: usize) -> Move {
+    Move {
+        quantum: QuantumMove::new(FACES[face(move_index)], None).into(),
+        amount: AMOUNTS[move_index % AMOUNTS.len()],
+    }
+}
+
+/// A 3x3x3 pattern (or transformation) with the centers fixed, using the orbits
+/// of [`cube3x3x3_kpuzzle`] (with pieces and positions in Reid order).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CubieCube {
+    corner_permutation: [u8; 8],
+    corner_orientation: [u8; 8],
+    edge_permutation: [u8; 12],
+    edge_orientation: [u8; 12],
+}
+
+impl CubieCube {
+    const SOLVED: CubieCube = CubieCube {
+        corner_permutation: [0, 1, 2, 3, 4, 5, 6, 7],
+        corner_orientation: [0; 8],
+        edge_permutation: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        edge_orientation: [0; 12],
+    };
+
+    fn from_transformation(transformation: &KTransformation) -> Self {
+        let (edges, corners) = edges_and_corners(transformation.kpuzzle());
+        let mut cube = Self::SOLVED;
+        for i in 0..8 {
+            cube.corner_permutation[i] = transformation.get_permutation_idx(corners, i as u8);
+            cube.corner_orientation[i] = transformation.get_orientation_delta(corners, i as u8);
+        }
+        for i in 0..12 {
+            cube.edge_permutation[i] = transformation.get_permutation_idx(edges, i as u8);
+            cube.edge_orientation[i] = transformation.get_orientation_delta(edges, i as u8);
+        }
+        cube
+    }
+
+    fn try_from_pattern(pattern: &KPattern) -> Result<Self, SearchError> {
+        let kpuzzle = pattern.kpuzzle();
+        if !has_same_orbits(kpuzzle, cube3x3x3_kpuzzle()) {
+            return Err("The pattern must be for the built-in 3x3x3 puzzle.".into());
+        }
+        let (edges, corners) = edges_and_corners(kpuzzle);
+        let centers = &kpuzzle.data.ordered_orbit_info[2];
+        for i in 0..centers.num_pieces {
+            if pattern.get_piece(centers, i) != i {
+                return Err("The centers of the pattern must be solved.".into());
+            }
+        }
+        let mut cube = Self::SOLVED;
+        for i in 0..8 {
+            cube.corner_permutation[i] = pattern.get_piece(corners, i as u8);
+            cube.corner_orientation[i] = pattern
+                .get_orientation_with_mod(corners, i as u8)
+                .orientation;
+        }
+        for i in 0..12 {
+            cube.edge_permutation[i] = pattern.get_piece(edges, i as u8);
+            cube.edge_orientation[i] = pattern.get_orientation_with_mod(edges, i as u8).orientation;
+        }
+
+        let is_permutation = |permutation: &[u8]| {
+            let mut seen = vec![false; permutation.len()];
+            permutation.iter().all(|&piece| {
+                (piece as usize) < seen.len() && !std::mem::replace(&mut seen[piece as usize], true)
+            })
+        };
+        if !is_permutation(&cube.corner_permutation) || !is_permutation(&cube.edge_permutation) {
+            return Err("The pattern contains duplicate pieces.".into());
+        }
+        if permutation_parity(&cube.corner_permutation)
+            != permutation_parity(&cube.edge_permutation)
+        {
+            return Err("The pattern has a single swap of pieces.".into());
+        }
+        if cube
+            .corner_orientation
+            .iter()
+            .map(|&o| o as u32)
+            .sum::<u32>()
+            % 3
+            != 0
+        {
+            return Err("The pattern has a twisted corner.".into());
+        }
+        if cube.edge_orientation.iter().map(|&o| o as u32).sum::<u32>() % 2 != 0 {
+            return Err("The pattern has a flipped edge.".into());
+        }
+        Ok(cube)
+    }
+
+    /// Applies `transformation` to `self`, in the same way as [`KPattern::apply_transformation`].
+    fn apply(&self, transformation: &CubieCube) -> CubieCube {
+        let mut cube = CubieCube::SOLVED;
+        for i in 0..8 {
+            let from = transformation.corner_permutation[i] as usize;
+            cube.corner_permutation[i] = self.corner_permutation[from];
+            cube.corner_orientation[i] =
+                (self.corner_orientation[from] + transformation.corner_orientation[i]) % 3;
+        }
+        for i in 0..12 {
+            let from = transformation.edge_permutation[i] as usize;
+            cube.edge_permutation[i] = self.edge_permutation[from];
+            cube.edge_orientation[i] =
+                (self.edge_orientation[from] + transformation.edge_orientation[i]) % 2;
+        }
+        cube
+    }
+
+    fn twist(&self) -> u16 {
+        self.corner_orientation[..7]
+            .iter()
+            .fold(0, |acc, &o| acc * 3 + o as u16)
+    }
+
+    fn set_twist(&mut self, twist: u16) {
+        let mut twist = twist;
+        let mut sum = 0;
+        for i in (0..7).rev() {
+            self.corner_orientation[i] = (twist % 3) as u8;
+            sum += self.corner_orientation[i];
+            twist /= 3;
+        }
+        self.corner_orientation[7] = (3 - sum % 3) % 3;
+    }
+
+    fn flip(&self) -> u16 {
+        self.edge_orientation[..11]
+            .iter()
+            .fold(0, |acc, &o| acc * 2 + o as u16)
+    }
+
+    fn set_flip(&mut self, flip: u16) {
+        let mut flip = flip;
+        let mut sum = 0;
+        for i in (0..11).rev() {
+            self.edge_orientation[i] = (flip % 2) as u8;
+            sum += self.edge_orientation[i];
+            flip /= 2;
+        }
+        self.edge_orientation[11] = sum % 2;
+    }
+
+    /// The positions of the E slice edges (ignoring their order).
+    fn slice(&self) -> u16 {
+        combination_rank(&self.edge_permutation.map(|piece| piece >= FIRST_SLICE_EDGE)) as u16
+    }
+
+    fn set_slice(&mut self, slice: u16) {
+        let mut is_member = [false; 12];
+        combination_unrank(slice as u64, 4, &mut is_member);
+        let (mut next_slice_edge, mut next_other_edge) = (FIRST_SLICE_EDGE, 0);
+        for (piece, is_member) in self.edge_permutation.iter_mut().zip(is_member) {
+            if is_member {
+                *piece = next_slice_edge;
+                next_slice_edge += 1;
+            } else {
+                *piece = next_other_edge;
+                next_other_edge += 1;
+            }
+        }
+    }
+
+    fn corner_permutation(&self) -> u16 {
+        permutation_rank(&self.corner_permutation) as u16
+    }
+
+    fn set_corner_permutation(&mut self, corner_permutation: u16) {
+        permutation_unrank(corner_permutation as u64, &mut self.corner_permutation);
+    }
+
+    /// Only meaningful in phase 2, where the U and D edges stay in the U and D layers.
+    fn ud_edge_permutation(&self) -> u16 {
+        permutation_rank(&self.edge_permutation[..8]) as u16
+    }
+
+    fn set_ud_edge_permutation(&mut self, ud_edge_permutation: u16) {
+        permutation_unrank(ud_edge_permutation as u64, &mut self.edge_permutation[..8]);
+    }
+
+    /// Only meaningful in phase 2, where the E slice edges stay in the E slice.
+    fn slice_permutation(&self) -> u16 {
+        let slice_edges = [8, 9, 10, 11].map(|i| self.edge_permutation[i] - FIRST_SLICE_EDGE);
+        permutation_rank(&slice_edges) as u16
+    }
+
+    fn set_slice_permutation(&mut self, slice_permutation: u16) {
+        let mut slice_edges = [0; 4];
+        permutation_unrank(slice_permutation as u64, &mut slice_edges);
+        for (i, piece) in slice_edges.into_iter().enumerate() {
+            self.edge_permutation[8 + i] = piece + FIRST_SLICE_EDGE;
+        }
+    }
+}
+
+fn edges_and_corners(kpuzzle: &KPuzzle) -> (&KPuzzleOrbitInfo, &KPuzzleOrbitInfo) {
+    // The built-in 3x3x3 orbits are `EDGES`, `CORNERS`, `CENTERS` (in that order).
+    let orbits = &kpuzzle.data.ordered_orbit_info;
+    (&orbits[0], &orbits[1])
+}
+
+fn face_move_cubes() -> [CubieCube; NUM_MOVES] {
+    let kpuzzle = cube3x3x3_kpuzzle();
+    std::array::from_fn(|move_index| {
+        CubieCube::from_transformation(
+            &kpuzzle
+                .transformation_from_move(&move_for_index(move_index))
+                .expect("Face moves should be valid for the 3x3x3."),
+        )
+    })
+}
+
+fn build_move_table<const N: usize>(
+    size: usize,
+    move_indices: [usize; N],
+    moves: &[CubieCube; NUM_MOVES],
+    set_coordinate: impl Fn(&mut CubieCube, u16),
+    get_coordinate: impl Fn(&CubieCube) -> u16,
+) -> Vec<[u16; N]> {
+    (0..size)
+        .map(|coordinate| {
+            let mut cube = CubieCube::SOLVED;
+            set_coordinate(&mut cube, coordinate as u16);
+            move_indices.map(|move_index| get_coordinate(&cube.apply(&moves[move_index])))
+        })
+        .collect()
+}
+
+/// Move and pruning tables for the two-phase algorithm.
+///
+/// Phase 1 brings the cube into the subgroup `<U, D, L2, F2, R2, B2>` (all
+/// pieces oriented, E slice edges in the E slice), and phase 2 solves the cube
+/// within that subgroup.
+#[derive(Debug)]
+struct TwoPhaseTables {
+    moves: [CubieCube; NUM_MOVES],
+    solved_slice: u16,
+
+    twist_move: Vec<[u16; NUM_MOVES]>,
+    flip_move: Vec<[u16; NUM_MOVES]>,
+    slice_move: Vec<[u16; NUM_MOVES]>,
+    corner_permutation_move: Vec<[u16; NUM_PHASE2_MOVES]>,
+    ud_edge_permutation_move: Vec<[u16; NUM_PHASE2_MOVES]>,
+    slice_permutation_move: Vec<[u16; NUM_PHASE2_MOVES]>,
+
+    twist_slice_pruning: Vec<u8>,
+    flip_slice_pruning: Vec<u8>,
+    corner_permutation_slice_permutation_pruning: Vec<u8>,
+    ud_edge_permutation_slice_permutation_pruning: Vec<u8>,
+}
+
+impl TwoPhaseTables {
+    fn generate() -> Self {
+        let moves = face_move_cubes();
+        let all_moves: [usize; NUM_MOVES] = std::array::from_fn(|move_index| move_index);
+
+        let twist_move = build_move_table(
+            NUM_TWISTS,
+            all_moves,
+            &moves,
+            CubieCube::set_twist,
+            CubieCube::twist,
+        );
+        let flip_move = build_move_table(
+            NUM_FLIPS,
+            all_moves,
+            &moves,
+            CubieCube::set_flip,
+            CubieCube::flip,
+        );
+        let slice_move = build_move_table(
+            NUM_SLICES,
+            all_moves,
+            &moves,
+            CubieCube::set_slice,
+            CubieCube::slice,
+        );
+        let corner_permutation_move = build_move_table(
+            NUM_CORNER_PERMUTATIONS,
+            PHASE2_MOVES,
+            &moves,
+            CubieCube::set_corner_permutation,
+            CubieCube::corner_permutation,
+        );
+        let ud_edge_permutation_move = build_move_table(
+            NUM_UD_EDGE_PERMUTATIONS,
+            PHASE2_MOVES,
+            &moves,
+            CubieCube::set_ud_edge_permutation,
+            CubieCube::ud_edge_permutation,
+        );
+        let slice_permutation_move = build_move_table(
+            NUM_SLICE_PERMUTATIONS,
+            PHASE2_MOVES,
+            &moves,
+            CubieCube::set_slice_permutation,
+            CubieCube::slice_permutation,
+        );
+
+        let solved_slice = CubieCube::SOLVED.slice();
+        let twist_slice_pruning =
+            build_pruning_table(&twist_move, &slice_move, solved_slice as usize);
+        let flip_slice_pruning =
+            build_pruning_table(&flip_move, &slice_move, solved_slice as usize);
+        let corner_permutation_slice_permutation_pruning =
+            build_pruning_table(&corner_permutation_move, &slice_permutation_move, 0);
+        let ud_edge_permutation_slice_permutation_pruning =
+            build_pruning_table(&ud_edge_permutation_move, &slice_permutation_move, 0);
+
+        Self {
+            moves,
+            solved_slice,
+            twist_move,
+            flip_move,
+            slice_move,
+            corner_permutation_move,
+            ud_edge_permutation_move,
+            slice_permutation_move,
+            twist_slice_pruning,
+            flip_slice_pruning,
+            corner_permutation_slice_permutation_pruning,
+            ud_edge_permutation_slice_permutation_pruning,
+        }
+    }
+
+    fn phase1_lower_bound(&self, twist: u16, flip: u16, slice: u16) -> usize {
+        let slice = slice as usize;
+        self.twist_slice_pruning[twist as usize * NUM_SLICES + slice]
+            .max(self.flip_slice_pruning[flip as usize * NUM_SLICES + slice]) as usize
+    }
+
+    fn phase2_lower_bound(
+        &self,
+        corner_permutation: u16,
+        ud_edge_permutation: u16,
+        slice_permutation: u16,
+    ) -> usize {
+        let slice_permutation = slice_permutation as usize;
+        self.corner_permutation_slice_permutation_pruning
+            [corner_permutation as usize * NUM_SLICE_PERMUTATIONS + slice_permutation]
+            .max(
+                self.ud_edge_permutation_slice_permutation_pruning
+                    [ud_edge_permutation as usize * NUM_SLICE_PERMUTATIONS + slice_permutation],
+            ) as usize
+    }
+
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(TABLES_FILE_HEADER)?;
+        write_move_table(writer, &self.twist_move)?;
+        write_move_table(writer, &self.flip_move)?;
+        write_move_table(writer, &self.slice_move)?;
+        write_move_table(writer, &self.corner_permutation_move)?;
+        write_move_table(writer, &self.ud_edge_permutation_move)?;
+        write_move_table(writer, &self.slice_permutation_move)?;
+        writer.write_all(&self.twist_slice_pruning)?;
+        writer.write_all(&self.flip_slice_pruning)?;
+        writer.write_all(&self.corner_permutation_slice_permutation_pruning)?;
+        writer.write_all(&self.ud_edge_permutation_slice_permutation_pruning)
+    }
+
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, SearchError> {
+        let mut bytes = bytes
+            .strip_prefix(TABLES_FILE_HEADER)
+            .ok_or("Invalid two-phase tables header.")?;
+        let twist_move = read_move_table(&mut bytes, NUM_TWISTS)?;
+        let flip_move = read_move_table(&mut bytes, NUM_FLIPS)?;
+        let slice_move = read_move_table(&mut bytes, NUM_SLICES)?;
+        let corner_permutation_move = read_move_table(&mut bytes, NUM_CORNER_PERMUTATIONS)?;
+        let ud_edge_permutation_move = read_move_table(&mut bytes, NUM_UD_EDGE_PERMUTATIONS)?;
+        let slice_permutation_move = read_move_table(&mut bytes, NUM_SLICE_PERMUTATIONS)?;
+        let twist_slice_pruning = read_bytes(&mut bytes, NUM_TWISTS * NUM_SLICES)?;
+        let flip_slice_pruning = read_bytes(&mut bytes, NUM_FLIPS * NUM_SLICES)?;
+        let corner_permutation_slice_permutation_pruning =
+            read_bytes(&mut bytes, NUM_CORNER_PERMUTATIONS * NUM_SLICE_PERMUTATIONS)?;
+        let ud_edge_permutation_slice_permutation_pruning = read_bytes(
+            &mut bytes,
+            NUM_UD_EDGE_PERMUTATIONS * NUM_SLICE_PERMUTATIONS,
+        )?;
+        if !bytes.is_empty() {
+            return Err("Unexpected data at the end of the two-phase tables.".into());
+        }
+        Ok(Self {
+            moves: face_move_cubes(),
+            solved_slice: CubieCube::SOLVED.slice(),
+            twist_move,
+            flip_move,
+            slice_move,
+            corner_permutation_move,
+            ud_edge_permutation_move,
+            slice_permutation_move,
+            twist_slice_pruning,
+            flip_slice_pruning,
+            corner_permutation_slice_permutation_pruning,
+            ud_edge_permutation_slice_permutation_pruning,
+        })
+    }
+}
+
+const TABLES_FILE_HEADER: &[u8] = b"cubing.rs two-phase tables v1\n";
+
+fn write_move_table<const N: usize>(
+    writer: &mut impl Write,
+    move_table: &[[u16; N]],
+) -> io::Result<()> {
+    for entry in move_table.iter().flatten() {
+        writer.write_all(&entry.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_bytes(bytes: &mut &[u8], len: usize) -> Result<Vec<u8>, SearchError> {
+    if bytes.len() < len {
+        return Err("The two-phase tables are truncated.".into());
+    }
+    let (data, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(data.to_vec())
+}
+
+fn read_move_table<const N: usize>(
+    bytes: &mut &[u8],
+    size: usize,
+) -> Result<Vec<[u16; N]>, SearchError> {
+    let data = read_bytes(bytes, size * N * 2)?;
+    let mut entries = data
+        .chunks_exact(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]));
+    let move_table: Vec<[u16; N]> = (0..size)
+        .map(|_| std::array::from_fn(|_| entries.next().unwrap()))
+        .collect();
+    if move_table
+        .iter()
+        .flatten()
+        .any(|&entry| entry as usize >= size)
+    {
+        return Err("The two-phase tables contain an invalid entry.".into());
+    }
+    Ok(move_table)
+}
+
+/// How often (in search nodes) to check the deadline.
+const DEADLINE_CHECK_INTERVAL: usize = 1 << 12;
+
+struct TwoPhaseSearch<'a> {
+    tables: &'a TwoPhaseTables,
+    cube: CubieCube,
+    max_length: usize,
+    deadline: Option<Instant>,
+    num_nodes: usize,
+    timed_out: bool,
+    solution: Vec<usize>,
+}
+
+impl TwoPhaseSearch<'_> {
+    fn check_deadline(&mut self) -> bool {
+        self.num_nodes += 1;
+        if self.num_nodes.is_multiple_of(DEADLINE_CHECK_INTERVAL) {
+            if let Some(deadline) = self.deadline {
+                self.timed_out |= Instant::now() > deadline;
+            }
+        }
+        self.timed_out
+    }
+
+    /// Returns `true` when the search should stop (a solution was found or the deadline passed).
+    fn search(&mut self) -> bool {
+        let tables = self.tables;
+        let (twist, flip, slice) = (self.cube.twist(), self.cube.flip(), self.cube.slice());
+        let lower_bound = tables.phase1_lower_bound(twist, flip, slice);
+        for phase1_length in lower_bound..=self.max_length {
+            if self.phase1(twist, flip, slice, phase1_length) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn phase1(&mut self, twist: u16, flip: u16, slice: u16, remaining: usize) -> bool {
+        let tables = self.tables;
+        if remaining == 0 {
+            // A phase 1 solution that ends in a phase 2 move would also be found
+            // by a shorter phase 1 search, so we skip it.
+            if twist == 0
+                && flip == 0
+                && slice == tables.solved_slice
+                && self
+                    .solution
+                    .last()
+                    .is_none_or(|&last| !is_phase2_move(last))
+            {
+                return self.start_phase2();
+            }
+            return false;
+        }
+        if self.check_deadline() {
+            return true;
+        }
+        for move_index in 0..NUM_MOVES {
+            if !can_follow(self.solution.last().copied(), move_index) {
+                continue;
+            }
+            let next_twist = tables.twist_move[twist as usize][move_index];
+            let next_flip = tables.flip_move[flip as usize][move_index];
+            let next_slice = tables.slice_move[slice as usize][move_index];
+            if tables.phase1_lower_bound(next_twist, next_flip, next_slice) >= remaining {
+                continue;
+            }
+            self.solution.push(move_index);
+            if self.phase1(next_twist, next_flip, next_slice, remaining - 1) {
+                return true;
+            }
+            self.solution.pop();
+        }
+        false
+    }
+
+    fn start_phase2(&mut self) -> bool {
+        let tables = self.tables;
+        let cube = self.solution.iter().fold(self.cube, |cube, &move_index| {
+            cube.apply(&tables.moves[move_index])
+        });
+        let (corner_permutation, ud_edge_permutation, slice_permutation) = (
+            cube.corner_permutation(),
+            cube.ud_edge_permutation(),
+            cube.slice_permutation(),
+        );
+        let lower_bound =
+            tables.phase2_lower_bound(corner_permutation, ud_edge_permutation, slice_permutation);
+        let max_phase2_length = (self.max_length - self.solution.len()).min(MAX_PHASE2_LENGTH);
+        for phase2_length in lower_bound..=max_phase2_length {
+            if self.phase2(
+                corner_permutation,
+                ud_edge_permutation,
+                slice_permutation,
+                phase2_length,
+            ) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn phase2(
+        &mut self,
+        corner_permutation: u16,
+        ud_edge_permutation: u16,
+        slice_permutation: u16,
+        remaining: usize,
+    ) -> bool {
+        if remaining == 0 {
+            return corner_permutation == 0 && ud_edge_permutation == 0 && slice_permutation == 0;
+        }
+        if self.check_deadline() {
+            return true;
+        }
+        let tables = self.tables;
+        for (phase2_move_index, &move_index) in PHASE2_MOVES.iter().enumerate() {
+            if !can_follow(self.solution.last().copied(), move_index) {
+                continue;
+            }
+            let next_corner_permutation =
+                tables.corner_permutation_move[corner_permutation as usize][phase2_move_index];
+            let next_ud_edge_permutation =
+                tables.ud_edge_permutation_move[ud_edge_permutation as usize][phase2_move_index];
+            let next_slice_permutation =
+                tables.slice_permutation_move[slice_permutation as usize][phase2_move_index];
+            if tables.phase2_lower_bound(
+                next_corner_permutation,
+                next_ud_edge_permutation,
+                next_slice_permutation,
+            ) >= remaining
+            {
+                continue;
+            }
+            self.solution.push(move_index);
+            if self.phase2(
+                next_corner_permutation,
+                next_ud_edge_permutation,
+                next_slice_permutation,
+                remaining - 1,
+            ) {
+                return true;
+            }
+            self.solution.pop();
+        }
+        false
+    }
+}
+
+/// Options for [`TwoPhaseSolver::solve`].
+#[derive(Debug, Clone)]
+pub struct TwoPhaseOptions {
+    /// The maximum number of moves in the solution (in HTM).
+    ///
+    /// Every pattern can be solved in 20 moves, but the two-phase algorithm may
+    /// take much longer to find solutions as this approaches 20.
+    pub max_length: usize,
+    /// Give up if no solution has been found after this amount of time.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for TwoPhaseSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for TwoPhaseOptions {
+    fn default() -> Self {
+        Self {
+            max_length: 21,
+            timeout: None,
+        }
+    }
+}
+
+static SHARED_TABLES: OnceLock<Arc<TwoPhaseTables>> = OnceLock::new();
+
+/// Finds near-optimal solutions for patterns of [`cube3x3x3_kpuzzle`] using
+/// [Kociemba's two-phase algorithm](https://kociemba.org/cube.htm).
+///
+/// Solutions use the face moves `U`, `L`, `F`, `R`, `B`, and `D`, so the
+/// centers of the pattern must be solved.
+///
+/// ```
+/// use cubing_core::{alg::Alg, puzzles::cube3x3x3_kpuzzle, search::{TwoPhaseOptions, TwoPhaseSolver}};
+///
+/// let kpuzzle = cube3x3x3_kpuzzle();
+/// let scramble = "R U R' U' F2 D L2 B'".parse::<Alg>().unwrap();
+/// let pattern = kpuzzle.default_pattern().apply_alg(&scramble).unwrap();
+///
+/// let solver = TwoPhaseSolver::new();
+/// let solution = solver
+///     .solve(&pattern, &TwoPhaseOptions::default())
+///     .unwrap()
+///     .expect("Every pattern can be solved in 21 moves.");
+/// assert_eq!(pattern.apply_alg(&solution).unwrap(), kpuzzle.default_pattern());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TwoPhaseSolver {
+    tables: Arc<TwoPhaseTables>,
+}
+
+impl TwoPhaseSolver {
+    /// Constructs a solver using tables that are shared by all solvers.
+    ///
+    /// The tables are generated the first time they are needed, which takes
+    /// about a second (much longer in debug builds). Use
+    /// [`TwoPhaseSolver::try_from_tables_file`] to avoid this.
+    pub fn new() -> Self {
+        Self {
+            tables: SHARED_TABLES
+                .get_or_init(|| Arc::new(TwoPhaseTables::generate()))
+                .clone(),
+        }
+    }
+
+    /// Constructs a solver using tables saved by [`TwoPhaseSolver::write_tables_file`].
+    ///
+    /// If the shared tables have not been generated yet, the loaded tables are
+    /// also used by [`TwoPhaseSolver::new`] (including for scrambles).
+    pub fn try_from_tables_file(path: impl AsRef<Path>) -> Result<Self, SearchError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| {
+            SearchError::from(format!(
+                "Could not read two-phase tables from {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let tables = Arc::new(TwoPhaseTables::try_from_bytes(&bytes)?);
+        // This only has an effect if the shared tables have not been generated yet.
+        let _ = SHARED_TABLES.set(tables.clone());
+        Ok(Self { tables })
+    }
+
+    /// Saves the tables used by this solver, for use with [`TwoPhaseSolver::try_from_tables_file`].
+    pub fn write_tables_file(&self, path: impl AsRef<Path>) -> Result<(), SearchError> {
+        let path = path.as_ref();
+        let write = || -> io::Result<()> {
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.tables.write_to(&mut writer)?;
+            writer.flush()
+        };
+        write().map_err(|e| {
+            format!(
+                "Could not write two-phase tables to {}: {}",
+                path.display(),
+                e
+            )
+            .into()
+        })
+    }
+
+    /// Returns the first solution found with at most `options.max_length`
+    /// moves, or `None` if there is no such solution or the timeout passed
+    /// before one was found.
+    pub fn solve(
+        &self,
+        pattern: &KPattern,
+        options: &TwoPhaseOptions,
+    ) -> Result<Option<Alg>, SearchError> {
+        let mut search = TwoPhaseSearch {
+            tables: &self.tables,
+            cube: CubieCube::try_from_pattern(pattern)?,
+            max_length: options.max_length,
+            deadline: options.timeout.map(|timeout| Instant::now() + timeout),
+            num_nodes: 0,
+            timed_out: false,
+            solution: vec![],
+        };
+        if !search.search() || search.timed_out {
+            return Ok(None);
+        }
+        Ok(Some(Alg {
+            nodes: search
+                .solution
+                .into_iter()
+                .map(|move_index| AlgNode::MoveNode(move_for_index(move_index)))
+                .collect(),
+        }))
+    }
+
+    /// Returns whether the pattern can be solved in at most `max_length` moves (in HTM).
+    ///
+    /// This is an exhaustive search, so it is only practical for small values of `max_length`.
+    pub(crate) fn is_solvable_within(
+        &self,
+        pattern: &KPattern,
+        max_length: usize,
+    ) -> Result<bool, SearchError> {
+        let cube = CubieCube::try_from_pattern(pattern)?;
+        Ok(self.is_solvable_within_recursive(cube, None, max_length))
+    }
+
+    fn is_solvable_within_recursive(
+        &self,
+        cube: CubieCube,
+        previous: Option<usize>,
+        remaining: usize,
+    ) -> bool {
+        if cube == CubieCube::SOLVED {
+            return true;
+        }
+        // Phase 1 distances are lower bounds for the full solution.
+        if self
+            .tables
+            .phase1_lower_bound(cube.twist(), cube.flip(), cube.slice())
+            > remaining
+            || remaining == 0
+        {
+            return false;
+        }
+        (0..NUM_MOVES).any(|move_index| {
+            can_follow(previous, move_index)
+                && self.is_solvable_within_recursive(
+                    cube.apply(&self.tables.moves[move_index]),
+                    Some(move_index),
+                    remaining - 1,
+                )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        alg::{Alg, Move},
+        puzzles::cube3x3x3_kpuzzle,
+        scramble::RandomPatternGenerator,
+    };
+
+    use super::{TwoPhaseOptions, TwoPhaseSolver};
+
+    #[test]
+    fn uses_loaded_tables() {
+        let path = std::env::temp_dir().join(format!(
+            "cubing-rs-two-phase-tables-unit-{}.bin",
+            std::process::id()
+        ));
+        let solver = TwoPhaseSolver::new();
+        solver.write_tables_file(&path).unwrap();
+        let loaded_solver = TwoPhaseSolver::try_from_tables_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!Arc::ptr_eq(&loaded_solver.tables, &solver.tables));
+        assert!(Arc::ptr_eq(&TwoPhaseSolver::new().tables, &solver.tables));
+    }
+
+    #[test]
+    fn solves_random_patterns() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let generator = RandomPatternGenerator::try_new(
+            kpuzzle,
+            &["U", "L", "F", "R", "B", "D"].map(|r#move| r#move.parse::<Move>().unwrap()),
+        )
+        .unwrap();
+        let solver = TwoPhaseSolver::new();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..5 {
+            let pattern = generator.random_pattern(&mut rng);
+            let solution = solver
+                .solve(
+                    &pattern,
+                    &TwoPhaseOptions {
+                        max_length: 22,
+                        timeout: None,
+                    },
+                )
+                .unwrap()
+                .unwrap();
+            assert!(solution.nodes.len() <= 22);
+            assert_eq!(
+                pattern.apply_alg(&solution).unwrap(),
+                kpuzzle.default_pattern()
+            );
+        }
+    }
+
+    #[test]
+    fn checks_short_solutions() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let solver = TwoPhaseSolver::new();
+        let pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(&"R U' D2".parse::<Alg>().unwrap())
+            .unwrap();
+        assert!(!solver.is_solvable_within(&pattern, 2).unwrap());
+        assert!(solver.is_solvable_within(&pattern, 3).unwrap());
+    }
+
+    #[test]
+    fn rejects_unsolvable_patterns() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let solver = TwoPhaseSolver::new();
+        let mut pattern = kpuzzle.default_pattern();
+        let edges = &kpuzzle.data.ordered_orbit_info[0];
+        pattern.set_piece(edges, 0, 1);
+        pattern.set_piece(edges, 1, 0);
+        assert!(solver
+            .solve(
+                &pattern,
+                &TwoPhaseOptions {
+                    max_length: 22,
+                    timeout: None
+                }
+            )
+            .is_err());
+        let rotated = kpuzzle
+            .default_pattern()
+            .apply_alg(&"x".parse::<Alg>().unwrap())
+            .unwrap();
+        assert!(solver.solve(&rotated, &TwoPhaseOptions::default()).is_err());
+    }
+}
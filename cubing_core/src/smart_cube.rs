@@ -0,0 +1,388 @@
+//! Processing of move streams from smart cubes (e.g. Bluetooth cubes), which
+//! report every quarter turn separately and may report jitter (e.g. `R R'`).
+
+use std::fmt::Display;
+
+use crate::{
+    alg::{Alg, AlgNode, LineComment, Move, Newline},
+    kpuzzle::{KPattern, KPatternBuffer, KPuzzle},
+    puzzles::{cube3x3x3_kpuzzle, CfopStep, CfopStepDetector, CubeFace},
+    reconstruction::Reconstruction,
+};
+
+/// A problem with an event passed to [`SmartCubeStreamProcessor::push`].
+#[derive(Debug)]
+pub struct SmartCubeStreamError {
+    pub description: String,
+}
+
+impl From<String> for SmartCubeStreamError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for SmartCubeStreamError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for SmartCubeStreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimestampedMove {
+    pub r#move: Move,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmartCubeStepEvent {
+    pub step: CfopStep,
+    pub timestamp_ms: u64,
+    /// The number of moves in [`SmartCubeStreamProcessor::alg`] when the step was completed.
+    pub num_moves: usize,
+}
+
+/// Turns a stream of timestamped moves from a 3x3x3 smart cube into a clean alg,
+/// tracking the pattern of the cube and detecting when each CFOP step is completed.
+///
+/// Consecutive moves of the same face are merged (e.g. `R R` becomes `R2`, and `R R'` cancels),
+/// except across a completed step, so that e.g. `R U R' R U R'` over the end of an F2L pair stays as-is.
+///
+/// Only face moves (like `R` or `U2'`) are accepted, since smart cubes do not
+/// report rotations or slice moves. The cross can be on any face: the progress
+/// of the solve is the furthest step completed for any cross face, and each
+/// step is only reported once.
+///
+/// ```
+/// use cubing_core::{alg::{Alg, Move}, puzzles::CfopStep, smart_cube::SmartCubeStreamProcessor};
+///
+/// let scramble = "R U R' U'".parse::<Alg>().unwrap();
+/// let mut processor = SmartCubeStreamProcessor::try_new_with_scramble(&scramble).unwrap();
+/// for (i, r#move) in ["U", "R", "R'", "R", "U'", "R'"].iter().enumerate() {
+///     let steps = processor.push(r#move.parse::<Move>().unwrap(), i as u64 * 100).unwrap();
+///     if i == 5 {
+///         assert_eq!(steps.last(), Some(&CfopStep::Pll));
+///     }
+/// }
+/// assert_eq!(processor.alg().to_string(), "U R U' R'");
+/// ```
+pub struct SmartCubeStreamProcessor {
+    kpuzzle: KPuzzle,
+    // One for each cross face.
+    step_detectors: Vec<CfopStepDetector>,
+    pattern: KPatternBuffer,
+    moves: Vec<TimestampedMove>,
+    step_events: Vec<SmartCubeStepEvent>,
+    progress: Option<CfopStep>,
+    last_timestamp_ms: Option<u64>,
+}
+
+fn furthest_progress(step_detectors: &[CfopStepDetector], pattern: &KPattern) -> Option<CfopStep> {
+    step_detectors
+        .iter()
+        .map(|step_detector| step_detector.progress(pattern))
+        .max()
+        .flatten()
+}
+
+impl SmartCubeStreamProcessor {
+    /// Starts from the given 3x3x3 pattern. Steps that are already complete are not reported.
+    pub fn try_new(pattern: KPattern) -> Result<Self, SmartCubeStreamError> {
+        let kpuzzle = cube3x3x3_kpuzzle().clone();
+        if pattern.kpuzzle().definition().name != kpuzzle.definition().name {
+            return Err(format!(
+                "Expected a 3x3x3 pattern, found a {} pattern.",
+                pattern.kpuzzle().definition().name
+            )
+            .into());
+        }
+        let step_detectors: Vec<CfopStepDetector> = CubeFace::ALL
+            .into_iter()
+            .map(CfopStepDetector::new)
+            .collect();
+        let progress = furthest_progress(&step_detectors, &pattern);
+        Ok(Self {
+            kpuzzle,
+            step_detectors,
+            pattern: pattern.into(),
+            moves: vec![],
+            step_events: vec![],
+            progress,
+            last_timestamp_ms: None,
+        })
+    }
+
+    /// Starts from the default pattern with the scramble applied.
+    pub fn try_new_with_scramble(scramble: &Alg) -> Result<Self, SmartCubeStreamError> {
+        let pattern = cube3x3x3_kpuzzle()
+            .default_pattern()
+            .apply_alg(scramble)
+            .map_err(|e| e.to_string())?;
+        Self::try_new(pattern)
+    }
+
+    /// Processes a move, and returns the steps that it completed (if any).
+    ///
+    /// Timestamps must not decrease, and the move must be a face move (e.g. not `x` or `M`).
+    pub fn push(
+        &mut self,
+        r#move: Move,
+        timestamp_ms: u64,
+    ) -> Result<Vec<CfopStep>, SmartCubeStreamError> {
+        if let Some(last_timestamp_ms) = self.last_timestamp_ms {
+            if timestamp_ms < last_timestamp_ms {
+                return Err(format!(
+                    "Timestamps must not decrease (received {} after {}).",
+                    timestamp_ms, last_timestamp_ms
+                )
+                .into());
+            }
+        }
+        if r#move.quantum.prefix.is_some()
+            || !matches!(
+                r#move.quantum.family.as_str(),
+                "U" | "L" | "F" | "R" | "B" | "D"
+            )
+        {
+            return Err(format!("Expected a face move, received: {}", r#move).into());
+        }
+        let transformation = self
+            .kpuzzle
+            .transformation_from_move(&r#move)
+            .map_err(|e| e.to_string())?;
+        self.pattern.apply_transformation(&transformation);
+        self.last_timestamp_ms = Some(timestamp_ms);
+
+        let num_moves_at_last_step = self
+            .step_events
+            .last()
+            .map(|step_event| step_event.num_moves)
+            .unwrap_or(0);
+        let can_merge = self.moves.len() > num_moves_at_last_step;
+        match self.moves.last_mut() {
+            Some(last) if can_merge && last.r#move.quantum == r#move.quantum => {
+                let amount = (last.r#move.amount + r#move.amount).rem_euclid(4);
+                if amount == 0 {
+                    self.moves.pop();
+                } else {
+                    last.r#move.amount = if amount == 3 { -1 } else { amount };
+                    last.timestamp_ms = timestamp_ms;
+                }
+            }
+            _ => self.moves.push(TimestampedMove {
+                r#move,
+                timestamp_ms,
+            }),
+        }
+
+        let progress = furthest_progress(&self.step_detectors, self.pattern.current());
+        let steps: Vec<CfopStep> = CfopStep::ALL
+            .into_iter()
+            .filter(|step| self.progress < Some(*step) && Some(*step) <= progress)
+            .collect();
+        for step in &steps {
+            self.step_events.push(SmartCubeStepEvent {
+                step: *step,
+                timestamp_ms,
+                num_moves: self.moves.len(),
+            });
+        }
+        self.progress = self.progress.max(progress);
+        Ok(steps)
+    }
+
+    pub fn pattern(&self) -> &KPattern {
+        self.pattern.current()
+    }
+
+    pub fn moves(&self) -> &[TimestampedMove] {
+        &self.moves
+    }
+
+    pub fn step_events(&self) -> &[SmartCubeStepEvent] {
+        &self.step_events
+    }
+
+    /// The merged moves so far.
+    pub fn alg(&self) -> Alg {
+        Alg {
+            nodes: self
+                .moves
+                .iter()
+                .map(|timestamped_move| AlgNode::MoveNode(timestamped_move.r#move.clone()))
+                .collect(),
+        }
+    }
+
+    /// A reconstruction with a line for each completed step (labeled
+    /// e.g. `// pair 2`), with timestamps relative to the first move.
+    pub fn reconstruction(&self) -> Reconstruction {
+        let start_ms = self
+            .moves
+            .first()
+            .map(|timestamped_move| timestamped_move.timestamp_ms)
+            .unwrap_or(0);
+        let mut nodes = Vec::<AlgNode>::default();
+        let mut step_events = self.step_events.iter().peekable();
+        for (i, timestamped_move) in self.moves.iter().enumerate() {
+            nodes.push(AlgNode::MoveNode(timestamped_move.r#move.clone()));
+            let mut labels = vec![];
+            while let Some(step_event) =
+                step_events.next_if(|step_event| step_event.num_moves <= i + 1)
+            {
+                labels.push(step_event.step.to_string());
+            }
+            if !labels.is_empty() {
+                let text = format!(" {}", labels.join(", "));
+                nodes.push(AlgNode::LineCommentNode(
+                    LineComment::try_new(&text).expect("Labels do not contain newlines."),
+                ));
+                if i + 1 < self.moves.len() {
+                    nodes.push(AlgNode::NewlineNode(Newline::default()));
+                }
+            }
+        }
+        let timestamps = self
+            .moves
+            .iter()
+            .map(|timestamped_move| timestamped_move.timestamp_ms - start_ms)
+            .collect();
+        Reconstruction::try_new(Alg { nodes }, timestamps)
+            .expect("Each move should have a timestamp.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alg::{Alg, Move},
+        puzzles::CfopStep,
+    };
+
+    use super::SmartCubeStreamProcessor;
+
+    #[test]
+    fn processes_a_recorded_solve() {
+        let insert = "R U R'";
+        let oll = "R U R' U R U2 R'";
+        let pll = "R U R' U' R' F R2 U' R' U' R U R' F'";
+        let scramble = format!("({} {} {})'", insert, oll, pll)
+            .parse::<Alg>()
+            .unwrap();
+        let mut processor = SmartCubeStreamProcessor::try_new_with_scramble(&scramble).unwrap();
+
+        // Quarter turns with jitter, as reported by a smart cube.
+        let log = "R U R' R U R' U R U U' U' U' R' R U F F' R' U' R' F R R U' R' U' R U R' F'";
+        let mut steps = vec![];
+        for (i, r#move) in log.split(' ').enumerate() {
+            for step in processor
+                .push(r#move.parse::<Move>().unwrap(), 1000 + i as u64 * 100)
+                .unwrap()
+            {
+                steps.push((step, processor.alg().nodes.len()));
+            }
+        }
+        assert_eq!(
+            steps,
+            vec![
+                (CfopStep::F2lPair(4), 3),
+                (CfopStep::Oll, 10),
+                (CfopStep::Pll, 24),
+            ]
+        );
+        assert_eq!(
+            processor.alg(),
+            format!("{} {} {}", insert, oll, pll)
+                .parse::<Alg>()
+                .unwrap()
+        );
+        assert_eq!(processor.pattern(), &processor.kpuzzle.default_pattern());
+
+        let reconstruction = processor.reconstruction();
+        assert_eq!(
+            reconstruction.alg().to_string(),
+            format!("{} // pair 4\n{} // OLL\n{} // PLL", insert, oll, pll)
+        );
+        let steps = reconstruction.steps();
+        assert_eq!(steps[1].label.as_deref(), Some("OLL"));
+        assert_eq!(steps[1].end_ms, 1200);
+        assert_eq!(reconstruction.timestamps()[0], 0);
+    }
+
+    #[test]
+    fn detects_a_cross_on_any_face() {
+        // The end of a solve with the cross on U (`R U R' U'` with `U` and `D` swapped).
+        let mut processor =
+            SmartCubeStreamProcessor::try_new_with_scramble(&"D R D' R'".parse::<Alg>().unwrap())
+                .unwrap();
+        let mut steps = vec![];
+        for (i, r#move) in ["R", "D", "R'", "D'"].iter().enumerate() {
+            steps.extend(
+                processor
+                    .push(r#move.parse::<Move>().unwrap(), i as u64 * 100)
+                    .unwrap(),
+            );
+        }
+        assert_eq!(steps.last(), Some(&CfopStep::Pll));
+        assert_eq!(processor.step_events()[0].step, CfopStep::F2lPair(4));
+
+        // The last layer (D) is oriented after `F2`, and permuted after `D'`.
+        let mut processor =
+            SmartCubeStreamProcessor::try_new_with_scramble(&"D F2".parse::<Alg>().unwrap())
+                .unwrap();
+        assert_eq!(
+            processor.push("F2".parse::<Move>().unwrap(), 0).unwrap(),
+            vec![
+                CfopStep::Cross,
+                CfopStep::F2lPair(1),
+                CfopStep::F2lPair(2),
+                CfopStep::F2lPair(3),
+                CfopStep::F2lPair(4),
+                CfopStep::Oll,
+            ]
+        );
+        assert_eq!(
+            processor.push("D'".parse::<Move>().unwrap(), 100).unwrap(),
+            vec![CfopStep::Pll]
+        );
+    }
+
+    #[test]
+    fn reports_every_step_completed_by_a_move() {
+        let mut processor =
+            SmartCubeStreamProcessor::try_new_with_scramble(&"R U".parse::<Alg>().unwrap())
+                .unwrap();
+        // After `U'`, only the last layer (R) is left for a cross on L.
+        assert_eq!(
+            processor.push("U'".parse::<Move>().unwrap(), 0).unwrap(),
+            vec![
+                CfopStep::Cross,
+                CfopStep::F2lPair(1),
+                CfopStep::F2lPair(2),
+                CfopStep::F2lPair(3),
+                CfopStep::F2lPair(4),
+                CfopStep::Oll,
+            ]
+        );
+        assert_eq!(
+            processor.push("R'".parse::<Move>().unwrap(), 50).unwrap(),
+            vec![CfopStep::Pll]
+        );
+        for r#move in ["x", "M", "2R", "Rw", "R++"] {
+            assert!(processor
+                .push(r#move.parse::<Move>().unwrap(), 100)
+                .is_err());
+        }
+        assert!(processor.pattern() == &processor.kpuzzle.default_pattern());
+        assert!(processor.push("R".parse::<Move>().unwrap(), 100).is_ok());
+        assert!(processor.push("R".parse::<Move>().unwrap(), 50).is_err());
+    }
+}
@@ -0,0 +1,480 @@
+//! Links to [Twizzle](https://alpha.twizzle.net/) (and parsing of `alg.cubing.net` links).
+//!
+//! ```
+//! use cubing_core::{
+//!     alg::Alg,
+//!     twizzle_link::{TwizzleLink, TwizzlePuzzle, TwizzleStickering},
+//! };
+//!
+//! let link = TwizzleLink {
+//!     alg: Some("R U R' U R U2' R'".parse::<Alg>().unwrap()),
+//!     puzzle: Some(TwizzlePuzzle::Cube3x3x3),
+//!     stickering: Some(TwizzleStickering::Oll),
+//!     ..Default::default()
+//! };
+//! assert_eq!(
+//!     link.to_url().unwrap(),
+//!     "https://alpha.twizzle.net/edit/?alg=R+U+R%27+U+R+U2%27+R%27&puzzle=3x3x3&stickering=OLL"
+//! );
+//! assert_eq!(link.to_url().unwrap().parse::<TwizzleLink>().unwrap(), link);
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use url::Url;
+
+use crate::{alg::Alg, kpuzzle::KPuzzle, puzzles::kpuzzle_by_id};
+
+// Defines an enum of known parameter values (as used in Twizzle URLs) with a
+// `Custom(String)` variant for any other value.
+macro_rules! twizzle_parameter_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// Any other value, passed through as-is.
+            Custom(String),
+        }
+
+        impl $name {
+            /// The value of the parameter in a Twizzle URL.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Custom(value) => value,
+                }
+            }
+
+            /// Constructs a value from any parameter value. Known values use
+            /// their own variant, and any other value is [`Custom`][`Self::Custom`].
+            pub fn custom(value: impl Into<String>) -> Self {
+                let value: String = value.into();
+                match value.as_str() {
+                    $($value => $name::$variant,)*
+                    _ => $name::Custom(value),
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                Self::custom(value)
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.as_str())
+            }
+        }
+    };
+}
+
+twizzle_parameter_enum! {
+    /// A puzzle ID. The known values are the built-in puzzles of [`kpuzzle_by_id`].
+    pub enum TwizzlePuzzle {
+        Cube2x2x2 => "2x2x2",
+        Cube3x3x3 => "3x3x3",
+        Cube4x4x4 => "4x4x4",
+        Cube5x5x5 => "5x5x5",
+        Cube6x6x6 => "6x6x6",
+        Cube7x7x7 => "7x7x7",
+        Clock => "clock",
+        Megaminx => "megaminx",
+        Pyraminx => "pyraminx",
+        Skewb => "skewb",
+        Square1 => "square1",
+    }
+}
+
+twizzle_parameter_enum! {
+    /// A stickering, using the names from cubing.js (see [`available_stickerings`][`crate::puzzles::available_stickerings`]).
+    pub enum TwizzleStickering {
+        Full => "full",
+        F2l => "F2L",
+        Cross => "Cross",
+        Ll => "LL",
+        Oll => "OLL",
+        Pll => "PLL",
+        Eoll => "EOLL",
+        Ocll => "OCLL",
+        Coll => "COLL",
+        Cll => "CLL",
+        Cpll => "CPLL",
+        Epll => "EPLL",
+        Ell => "ELL",
+        Eo => "EO",
+        EoLine => "EOline",
+        EoCross => "EOcross",
+    }
+}
+
+twizzle_parameter_enum! {
+    /// How the puzzle is displayed.
+    pub enum TwizzleVisualization {
+        Auto => "auto",
+        TwoD => "2D",
+        ThreeD => "3D",
+        /// A 2D view of the last layer (for some puzzles).
+        ExperimentalTwoDLastLayer => "experimental-2D-LL",
+        /// The 3D view used for puzzles defined by the puzzle geometry library.
+        Pg3D => "PG3D",
+    }
+}
+
+twizzle_parameter_enum! {
+    /// Which playback controls are shown.
+    pub enum TwizzleControlPanel {
+        Auto => "auto",
+        BottomRow => "bottom-row",
+        /// `none`
+        Hidden => "none",
+    }
+}
+
+/// Whether the setup alg applies at the start or the end of the alg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TwizzleSetupAnchor {
+    /// The setup alg is applied to the default pattern (before the alg).
+    #[default]
+    Start,
+    /// The setup alg is chosen so that the alg ends at the default pattern.
+    End,
+}
+
+impl TwizzleSetupAnchor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TwizzleSetupAnchor::Start => "start",
+            TwizzleSetupAnchor::End => "end",
+        }
+    }
+}
+
+/// Which Twizzle page a link opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TwizzleLinkTarget {
+    /// Twizzle Editor (`/edit/`).
+    #[default]
+    Edit,
+    /// Twizzle Explorer (`/explore/`).
+    Explore,
+}
+
+/// The parameters of a Twizzle link. Missing parameters use the Twizzle defaults (e.g. the 3x3x3).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TwizzleLink {
+    pub target: TwizzleLinkTarget,
+    pub setup: Option<Alg>,
+    pub alg: Option<Alg>,
+    pub puzzle: Option<TwizzlePuzzle>,
+    pub stickering: Option<TwizzleStickering>,
+    pub setup_anchor: Option<TwizzleSetupAnchor>,
+    pub visualization: Option<TwizzleVisualization>,
+    pub control_panel: Option<TwizzleControlPanel>,
+    pub title: Option<String>,
+}
+
+/// The setup or alg of a [`TwizzleLink`] is not valid for its puzzle.
+#[derive(Debug)]
+pub struct TwizzleLinkError {
+    pub description: String,
+}
+
+impl From<String> for TwizzleLinkError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for TwizzleLinkError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for TwizzleLinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+/// Could not parse a Twizzle or `alg.cubing.net` link.
+#[derive(Debug)]
+pub struct TwizzleLinkParseError {
+    pub description: String,
+}
+
+impl From<String> for TwizzleLinkParseError {
+    fn from(description: String) -> Self {
+        Self { description }
+    }
+}
+
+impl From<&str> for TwizzleLinkParseError {
+    fn from(description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+        }
+    }
+}
+
+impl Display for TwizzleLinkParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+impl TwizzleLink {
+    /// The puzzle to validate algs against, if it is known (Twizzle defaults to the 3x3x3).
+    fn kpuzzle(&self) -> Option<KPuzzle> {
+        kpuzzle_by_id(
+            self.puzzle
+                .as_ref()
+                .unwrap_or(&TwizzlePuzzle::Cube3x3x3)
+                .as_str(),
+        )
+    }
+
+    /// Returns an error if the setup or alg uses moves that are not valid
+    /// for the puzzle. Algs for custom puzzle IDs that are not known to
+    /// [`kpuzzle_by_id`] are not checked.
+    pub fn validate(&self) -> Result<(), TwizzleLinkError> {
+        let Some(kpuzzle) = self.kpuzzle() else {
+            return Ok(());
+        };
+        for (name, alg) in [("setup", &self.setup), ("alg", &self.alg)] {
+            if let Some(alg) = alg {
+                kpuzzle.transformation_from_alg(alg).map_err(|e| {
+                    format!("Invalid {} for {}: {}", name, kpuzzle.definition().name, e)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the link (see [`TwizzleLink::validate`]) and returns its URL.
+    pub fn to_url(&self) -> Result<String, TwizzleLinkError> {
+        self.validate()?;
+        Ok(self.to_url_unchecked())
+    }
+
+    fn to_url_unchecked(&self) -> String {
+        let mut url = Url::parse(match self.target {
+            TwizzleLinkTarget::Edit => "https://alpha.twizzle.net/edit/",
+            TwizzleLinkTarget::Explore => "https://alpha.twizzle.net/explore/",
+        })
+        .unwrap();
+        {
+            let mut query_pairs = url.query_pairs_mut();
+            if let Some(setup) = &self.setup {
+                query_pairs.append_pair("setup-alg", &setup.to_string());
+            }
+            if let Some(alg) = &self.alg {
+                query_pairs.append_pair("alg", &alg.to_string());
+            }
+            if let Some(puzzle) = &self.puzzle {
+                query_pairs.append_pair("puzzle", puzzle.as_str());
+            }
+            if let Some(setup_anchor) = self.setup_anchor {
+                query_pairs.append_pair("setup-anchor", setup_anchor.as_str());
+            }
+            if let Some(stickering) = &self.stickering {
+                query_pairs.append_pair("stickering", stickering.as_str());
+            }
+            if let Some(visualization) = &self.visualization {
+                query_pairs.append_pair("visualization", visualization.as_str());
+            }
+            if let Some(control_panel) = &self.control_panel {
+                query_pairs.append_pair("control-panel", control_panel.as_str());
+            }
+            if let Some(title) = &self.title {
+                query_pairs.append_pair("title", title);
+            }
+        }
+        // `Url` leaves a trailing `?` if no parameters were appended.
+        url.as_str().trim_end_matches('?').to_owned()
+    }
+}
+
+// `alg.cubing.net` writes spaces as `_` and primes as `-`, and escapes
+// literal underscores and hyphens as `&#95;` and `&#45;`.
+fn unescape_alg_cubing_net(s: &str) -> String {
+    s.replace('_', " ")
+        .replace('-', "'")
+        .replace("&#95;", "_")
+        .replace("&#45;", "-")
+}
+
+/// Parses a link to Twizzle (`/edit/` or `/explore/` on `alpha.twizzle.net`
+/// or `twizzle.net`) or `alg.cubing.net`. Unknown query parameters are ignored.
+///
+/// The algs are not validated against the puzzle (see [`TwizzleLink::validate`]).
+impl FromStr for TwizzleLink {
+    type Err = TwizzleLinkParseError;
+
+    fn from_str(link: &str) -> Result<Self, Self::Err> {
+        let url = Url::parse(link).map_err(|e| format!("Invalid URL: {}", e))?;
+        let is_alg_cubing_net = match url.host_str() {
+            Some("alg.cubing.net") => true,
+            Some("alpha.twizzle.net" | "twizzle.net") => false,
+            _ => {
+                return Err(format!("Not a Twizzle or alg.cubing.net link: {}", link).into());
+            }
+        };
+        let target = if is_alg_cubing_net {
+            TwizzleLinkTarget::Edit
+        } else {
+            match url.path().trim_end_matches('/') {
+                "/edit" => TwizzleLinkTarget::Edit,
+                "/explore" => TwizzleLinkTarget::Explore,
+                path => return Err(format!("Unknown Twizzle page: {}", path).into()),
+            }
+        };
+
+        let param = |key: &str| -> Option<String> {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.into_owned())
+        };
+        let alg_param = |key: &str| -> Result<Option<Alg>, TwizzleLinkParseError> {
+            let Some(value) = param(key) else {
+                return Ok(None);
+            };
+            let value = if is_alg_cubing_net {
+                unescape_alg_cubing_net(&value)
+            } else {
+                value
+            };
+            value
+                .parse::<Alg>()
+                .map(Some)
+                .map_err(|e| format!("Invalid alg for `{}`: {}", key, e).into())
+        };
+
+        let setup_anchor = match param("setup-anchor").as_deref() {
+            None => None,
+            Some("start") => Some(TwizzleSetupAnchor::Start),
+            Some("end") => Some(TwizzleSetupAnchor::End),
+            Some(setup_anchor) => {
+                return Err(format!("Invalid setup anchor: {}", setup_anchor).into());
+            }
+        };
+
+        Ok(TwizzleLink {
+            target,
+            setup: alg_param(if is_alg_cubing_net {
+                "setup"
+            } else {
+                "setup-alg"
+            })?,
+            alg: alg_param("alg")?,
+            puzzle: param("puzzle").as_deref().map(TwizzlePuzzle::from),
+            stickering: param("stickering").as_deref().map(TwizzleStickering::from),
+            setup_anchor,
+            visualization: param("visualization")
+                .as_deref()
+                .map(TwizzleVisualization::from),
+            control_panel: param("control-panel")
+                .as_deref()
+                .map(TwizzleControlPanel::from),
+            title: param("title"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alg::Alg;
+
+    use super::{
+        TwizzleControlPanel, TwizzleLink, TwizzleLinkTarget, TwizzlePuzzle, TwizzleSetupAnchor,
+        TwizzleStickering, TwizzleVisualization,
+    };
+
+    #[test]
+    fn round_trips_links() {
+        let link = TwizzleLink {
+            target: TwizzleLinkTarget::Explore,
+            setup: Some("F R U".parse::<Alg>().unwrap()),
+            alg: Some("[R, U']\n// comment & more".parse::<Alg>().unwrap()),
+            puzzle: Some(TwizzlePuzzle::Cube3x3x3),
+            stickering: Some(TwizzleStickering::custom("OLL (experimental)")),
+            setup_anchor: Some(TwizzleSetupAnchor::End),
+            visualization: Some(TwizzleVisualization::TwoD),
+            control_panel: Some(TwizzleControlPanel::Hidden),
+            title: Some("Sune? 100%".to_owned()),
+        };
+        let url = link.to_url().unwrap();
+        assert!(url.starts_with("https://alpha.twizzle.net/explore/?setup-alg=F+R+U&"));
+        assert!(url.ends_with("&visualization=2D&control-panel=none&title=Sune%3F+100%25"));
+        assert_eq!(url.parse::<TwizzleLink>().unwrap(), link);
+
+        assert_eq!(
+            TwizzleLink::default().to_url().unwrap(),
+            "https://alpha.twizzle.net/edit/"
+        );
+        let empty = "https://twizzle.net/edit".parse::<TwizzleLink>().unwrap();
+        assert_eq!(empty, TwizzleLink::default());
+
+        let link = "https://alg.cubing.net/?alg=(3,_%26%2345;4)_/&puzzle=square1&stickering=OLL"
+            .parse::<TwizzleLink>()
+            .unwrap();
+        assert_eq!(link.puzzle, Some(TwizzlePuzzle::Square1));
+        assert_eq!(link.stickering, Some(TwizzleStickering::Oll));
+        assert_eq!(link.alg.as_ref().unwrap().to_string(), "(3, -4) /");
+    }
+
+    #[test]
+    fn validates_algs_for_the_puzzle() {
+        let link = |puzzle: Option<TwizzlePuzzle>, setup: &str, alg: &str| TwizzleLink {
+            setup: Some(setup.parse::<Alg>().unwrap()),
+            alg: Some(alg.parse::<Alg>().unwrap()),
+            puzzle,
+            ..Default::default()
+        };
+        assert!(link(None, "R U", "[R, U] M2").to_url().is_ok());
+        assert!(link(None, "R U", "R++").to_url().is_err());
+        assert!(link(Some(TwizzlePuzzle::Megaminx), "R++", "U")
+            .to_url()
+            .is_ok());
+        assert!(link(Some(TwizzlePuzzle::Cube2x2x2), "M2", "R")
+            .to_url()
+            .is_err());
+        assert!(link(Some(TwizzlePuzzle::Cube5x5x5), "3Rw", "2U")
+            .to_url()
+            .is_ok());
+        assert_eq!(TwizzlePuzzle::from("6x6x6"), TwizzlePuzzle::Cube6x6x6);
+        assert_eq!(TwizzlePuzzle::custom("3x3x3"), TwizzlePuzzle::Cube3x3x3);
+        assert_eq!(TwizzleStickering::custom("OLL"), TwizzleStickering::Oll);
+        assert_eq!(
+            TwizzleControlPanel::custom("floating"),
+            TwizzleControlPanel::Custom("floating".to_owned())
+        );
+        // Puzzle IDs that are not known are passed through without validation.
+        assert!(link(Some(TwizzlePuzzle::custom("fto")), "BR", "T")
+            .to_url()
+            .is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_links() {
+        for link in [
+            "R U R'",
+            "https://example.com/edit/?alg=R",
+            "https://alpha.twizzle.net/play/",
+            "https://alpha.twizzle.net/edit/?alg=(R",
+            "https://alpha.twizzle.net/edit/?setup-anchor=middle",
+        ] {
+            assert!(link.parse::<TwizzleLink>().is_err());
+        }
+    }
+}
@@ -231,6 +231,15 @@ fn it_handles_crowding() -> Result<(), String> {
     Ok(())
 }
 
+#[test]
+fn it_reports_parse_error_positions() {
+    let position = |s: &str| s.parse::<Alg>().unwrap_err().position;
+    assert_eq!(position("R U (F"), Some(4));
+    assert_eq!(position("R U ]"), Some(4));
+    assert_eq!(position("[R, U"), Some(0));
+    assert_eq!(position("R2 ⟨U"), Some(3));
+}
+
 #[test]
 fn alg_can_be_sent_to_and_returned_from_threads() -> Result<(), InvalidAlgError> {
     let alg = "R U R'".parse::<Alg>()?;
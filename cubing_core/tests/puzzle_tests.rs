@@ -6,7 +6,11 @@ use cubing_core::{
         InvalidAlgError, InvalidDefinitionError, KPatternData, KPatternOrbitData, KPuzzle,
         KPuzzleDefinition, KPuzzleOrbitDefinition, KTransformationData, KTransformationOrbitData,
     },
-    puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle},
+    puzzles::{
+        clock_kpuzzle, cube2x2x2_kpuzzle, cube3x3x3_kpuzzle, cube4x4x4_kpuzzle, cube5x5x5_kpuzzle,
+        cube6x6x6_kpuzzle, cube7x7x7_kpuzzle, cube_nxnxn_kpuzzle, megaminx_kpuzzle,
+        puzzle_geometry_kpuzzle, pyraminx_kpuzzle, skewb_kpuzzle, square1_kpuzzle,
+    },
 };
 
 #[test]
@@ -82,3 +86,352 @@ fn avoids_recursion() -> Result<(), InvalidDefinitionError> {
         .starts_with("Recursive derived move definition for: "));
     Ok(())
 }
+
+fn assert_solves(kpuzzle: &KPuzzle, algs: &[&str]) -> Result<(), InvalidAlgError> {
+    for alg in algs {
+        assert_eq!(
+            &kpuzzle.default_pattern().apply_alg(&alg.parse::<Alg>()?)?,
+            &kpuzzle.default_pattern(),
+            "Expected {} to return to the default pattern for {}",
+            alg,
+            kpuzzle.definition().name
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn big_cubes() -> Result<(), InvalidAlgError> {
+    for (kpuzzle, outer_block_and_slices) in [
+        (cube4x4x4_kpuzzle(), "R 2R 2L' L'"),
+        (cube5x5x5_kpuzzle(), "R 2R M' 2L' L'"),
+        (cube6x6x6_kpuzzle(), "R 2R 3R 3L' 2L' L'"),
+        (cube7x7x7_kpuzzle(), "R 2R 3R M' 3L' 2L' L'"),
+    ] {
+        assert_solves(
+            kpuzzle,
+            &[
+                "R4",
+                "(R U R' U')6",
+                "(x y)3",
+                "Rw 2R' R'",
+                "r Rw'",
+                "Rv x'",
+                "(2R U 2R' U')15",
+                &format!("{} x'", outer_block_and_slices),
+            ],
+        )?;
+        assert_ne!(
+            kpuzzle
+                .default_pattern()
+                .apply_alg(&"2R U 2R' U'".parse::<Alg>()?)?,
+            kpuzzle.default_pattern()
+        );
+    }
+    // Swapping two center pieces of the same color is not visible.
+    assert_solves(cube4x4x4_kpuzzle(), &["(2R U 2R' U')5", "3Rw L' x'"])?;
+    assert_solves(cube5x5x5_kpuzzle(), &["3R M", "4Rw L' x'"])?;
+    Ok(())
+}
+
+#[test]
+fn pyraminx() -> Result<(), InvalidAlgError> {
+    assert_solves(
+        pyraminx_kpuzzle(),
+        &[
+            "U3",
+            "u3",
+            "(R U R' U')3",
+            "(R' L R L')3",
+            "(R U)15",
+            "U u' 2U'",
+            "Uv3",
+            "Uv L Uv' R'",
+        ],
+    )
+}
+
+#[test]
+fn skewb() -> Result<(), InvalidAlgError> {
+    assert_solves(
+        skewb_kpuzzle(),
+        &[
+            "R3",
+            "(R U R' U')6",
+            "(R L R' L')6",
+            "(R U)45",
+            "x4",
+            "Rv3",
+            "Rv y x'",
+        ],
+    )
+}
+
+#[test]
+fn megaminx() -> Result<(), InvalidAlgError> {
+    assert_solves(
+        megaminx_kpuzzle(),
+        &[
+            "R5",
+            "(R U R' U')6",
+            "(R U)63",
+            "(R++)5",
+            "D++ D--",
+            "Uv5",
+            "Rv5",
+            "Dv Uv",
+        ],
+    )
+}
+
+#[test]
+fn square1() -> Result<(), InvalidAlgError> {
+    assert_solves(
+        square1_kpuzzle(),
+        &[
+            "/ /",
+            "(3, 0) (3, 0) (6, 0)",
+            "U4",
+            "D4",
+            "(/ (3, 0))6",
+            "(/ (3, 3))4",
+            "(/ (1, 0))18",
+        ],
+    )
+}
+
+#[test]
+fn clock() -> Result<(), InvalidAlgError> {
+    assert_solves(
+        clock_kpuzzle(),
+        &[
+            "UR6+ UR6+",
+            "ALL5+ ALL7+",
+            "y2 y2",
+            "y2 y2'",
+            "z4",
+            "x2 x2",
+            "z UR1+ z' UL1-",
+            "z2 U1+ z2 D1-",
+        ],
+    )?;
+    let kpuzzle = clock_kpuzzle();
+    assert_eq!(
+        kpuzzle.transformation_from_alg(&"y2 UR1+ y2".parse::<Alg>()?)?,
+        kpuzzle.transformation_from_alg(&"y2' UR1+ y2'".parse::<Alg>()?)?,
+    );
+    Ok(())
+}
+
+#[test]
+fn cube_nxnxn_matches_built_in_cubes() -> Result<(), InvalidAlgError> {
+    for (n, built_in) in [
+        (3, cube3x3x3_kpuzzle()),
+        (4, cube4x4x4_kpuzzle()),
+        (5, cube5x5x5_kpuzzle()),
+        (6, cube6x6x6_kpuzzle()),
+        (7, cube7x7x7_kpuzzle()),
+    ] {
+        let kpuzzle = cube_nxnxn_kpuzzle(n).unwrap();
+        assert_eq!(kpuzzle.definition().name, built_in.definition().name);
+        assert_eq!(
+            kpuzzle.default_pattern().to_data(),
+            built_in.default_pattern().to_data()
+        );
+        let definition = built_in.definition();
+        for r#move in definition.moves.keys().chain(
+            definition
+                .derived_moves
+                .iter()
+                .flat_map(|derived_moves| derived_moves.keys()),
+        ) {
+            assert_eq!(
+                kpuzzle.transformation_from_move(r#move)?.to_data(),
+                built_in.transformation_from_move(r#move)?.to_data(),
+                "Mismatched move {} for {}",
+                r#move,
+                definition.name
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn cube_nxnxn() -> Result<(), InvalidAlgError> {
+    assert!(cube_nxnxn_kpuzzle(1).is_err());
+    assert!(cube_nxnxn_kpuzzle(33).is_err());
+    assert_solves(
+        &cube_nxnxn_kpuzzle(2).unwrap(),
+        &["(R U R' U')6", "(x y)3", "R L' x'"],
+    )?;
+    for n in [8, 9, 13, 32] {
+        let kpuzzle = cube_nxnxn_kpuzzle(n).unwrap();
+        assert_solves(
+            &kpuzzle,
+            &[
+                "(R U R' U')6",
+                "(2R U 2R' U')15",
+                "(x y)3",
+                "Rw 2R' R'",
+                "3r 3R' 2R' R'",
+                "2-3r 2-3Rw'",
+                "5Rw' 2-5r R",
+                &format!("{}Rw L' x'", n - 1),
+                &format!("{}R 2L", n - 1),
+            ],
+        )?;
+    }
+    assert_eq!(
+        cube_nxnxn_kpuzzle(32).unwrap().definition().orbits.len(),
+        241
+    );
+    Ok(())
+}
+
+// The number of times the alg has to be applied to the default pattern to return to it.
+fn pattern_order(kpuzzle: &KPuzzle, alg: &str) -> Result<usize, InvalidAlgError> {
+    let transformation = kpuzzle.transformation_from_alg(&alg.parse::<Alg>()?)?;
+    let mut pattern = kpuzzle
+        .default_pattern()
+        .apply_transformation(&transformation);
+    let mut order = 1;
+    while pattern != kpuzzle.default_pattern() {
+        pattern = pattern.apply_transformation(&transformation);
+        order += 1;
+    }
+    Ok(order)
+}
+
+// Checks that don't rely on the built-in definitions (which were generated by the same code).
+#[test]
+fn cube_nxnxn_independent_checks() -> Result<(), InvalidAlgError> {
+    for n in 2..=12 {
+        let kpuzzle = cube_nxnxn_kpuzzle(n).unwrap();
+        // Every cubie on the surface is a piece.
+        let num_pieces: u32 = kpuzzle
+            .definition()
+            .orbits
+            .iter()
+            .map(|orbit| orbit.num_pieces as u32)
+            .sum();
+        assert_eq!(num_pieces, 6 * n * n - 12 * n + 8, "{}x{}x{}", n, n, n);
+
+        // The number of pieces that each move takes to a different position.
+        let num_moved_pieces = |alg: &str| -> Result<u32, InvalidAlgError> {
+            let transformation = kpuzzle.transformation_from_alg(&alg.parse::<Alg>()?)?;
+            Ok(kpuzzle
+                .orbit_info_iter()
+                .map(|orbit_info| {
+                    (0..orbit_info.num_pieces)
+                        .filter(|i| transformation.get_permutation_idx(orbit_info, *i) != *i)
+                        .count() as u32
+                })
+                .sum())
+        };
+        // For odd cubes, the center of the face stays in place.
+        assert_eq!(num_moved_pieces("R")?, n * n - n % 2);
+        assert_eq!(num_moved_pieces("x")?, num_pieces - 2 * (n % 2));
+        if n > 3 {
+            assert_eq!(num_moved_pieces("2R")?, 4 * (n - 1));
+        }
+        assert_eq!(pattern_order(&kpuzzle, "R")?, 4);
+        assert_eq!(pattern_order(&kpuzzle, "R2")?, 2);
+        // The 2x2x2 has no edges, which contribute the factor of 7.
+        assert_eq!(
+            pattern_order(&kpuzzle, "R U")?,
+            if n == 2 { 15 } else { 105 }
+        );
+    }
+
+    // Orders computed with a separate sticker-based simulation.
+    for (kpuzzle, orders) in [
+        (
+            cube_nxnxn_kpuzzle(4).unwrap(),
+            [
+                ("R U'", 63),
+                ("R U2 D' B D'", 1260),
+                ("R 2R' U", 240),
+                ("F 2U'", 20),
+            ],
+        ),
+        (
+            cube_nxnxn_kpuzzle(5).unwrap(),
+            [
+                ("R U'", 63),
+                ("R U2 D' B D'", 1260),
+                ("R 2R' U", 1680),
+                ("F 2U'", 140),
+            ],
+        ),
+    ] {
+        for (alg, order) in orders {
+            assert_eq!(pattern_order(&kpuzzle, alg)?, order, "{}", alg);
+        }
+    }
+    Ok(())
+}
+
+fn orbit_sizes(kpuzzle: &KPuzzle) -> Vec<(&str, u8, u8)> {
+    kpuzzle
+        .definition()
+        .orbits
+        .iter()
+        .map(|orbit| {
+            (
+                orbit.orbit_name.0.as_str(),
+                orbit.num_pieces,
+                orbit.num_orientations,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn puzzle_geometry() -> Result<(), InvalidAlgError> {
+    let cube = puzzle_geometry_kpuzzle("c f 0.333").unwrap();
+    assert_eq!(
+        orbit_sizes(&cube),
+        [("EDGES", 12, 2), ("CORNERS", 8, 3), ("CENTERS", 6, 4)]
+    );
+    assert_solves(&cube, &["(R U R' U')6", "(R U)105", "R 2R 3R Rv'"])?;
+    assert_ne!(
+        cube.default_pattern()
+            .apply_alg(&"(R U)35".parse::<Alg>()?)?,
+        cube.default_pattern()
+    );
+
+    assert_eq!(
+        orbit_sizes(&puzzle_geometry_kpuzzle("c f 0.5 f 0").unwrap()),
+        [("EDGES", 24, 1), ("CORNERS", 8, 3), ("CENTERS", 24, 1)]
+    );
+    let master_pyraminx = puzzle_geometry_kpuzzle("t v 0 v 1 v 2").unwrap();
+    assert_eq!(
+        orbit_sizes(&master_pyraminx),
+        [
+            ("EDGES", 12, 1),
+            ("EDGES2", 6, 2),
+            ("CORNERS", 4, 3),
+            ("CORNERS2", 4, 3),
+            ("CENTERS", 4, 3)
+        ]
+    );
+    assert_solves(&master_pyraminx, &["FLR3", "(FLR 2FLR 3FLR 4FLR FLRv')"])?;
+    let fto = puzzle_geometry_kpuzzle("o f 0.333").unwrap();
+    assert_eq!(
+        orbit_sizes(&fto)[..2],
+        [("EDGES", 12, 2), ("CORNERS", 6, 4)]
+    );
+    assert_solves(&fto, &["R3", "BR3", "(R 2R 3R)' Rv"])?;
+    let megaminx = puzzle_geometry_kpuzzle("d f 0.7").unwrap();
+    assert_eq!(
+        orbit_sizes(&megaminx),
+        [("EDGES", 30, 2), ("CORNERS", 20, 3), ("CENTERS", 12, 5)]
+    );
+    assert_solves(&megaminx, &["R5", "(R U R' U')6"])?;
+
+    for description in ["", "i f 0.5", "c", "c f", "c f x", "c q 0.5"] {
+        assert!(puzzle_geometry_kpuzzle(description).is_err());
+    }
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use cubing_core::{
+    alg::Alg,
+    puzzles::cube3x3x3_kpuzzle,
+    search::{Cube2x2x2Solver, TwoPhaseOptions, TwoPhaseSolver},
+};
+
+#[test]
+fn two_phase_solutions_invert_scrambles() {
+    let kpuzzle = cube3x3x3_kpuzzle();
+    let solver = TwoPhaseSolver::new();
+    for scramble in [
+        "",
+        "R",
+        "R U R' U' R' F R2 U' R' U' R U R' F'",
+        "D2 F2 U' B2 R2 B2 R2 L B' D' F D2 F' L2 F U' F' R U' L2",
+        "(R U)105",
+    ] {
+        let scramble = scramble.parse::<Alg>().unwrap();
+        let pattern = kpuzzle.default_pattern().apply_alg(&scramble).unwrap();
+        let solution = solver
+            .solve(&pattern, &TwoPhaseOptions::default())
+            .unwrap()
+            .unwrap();
+        assert!(solution.nodes.len() <= 21);
+        assert_eq!(
+            kpuzzle.default_pattern().apply_alg(&solution).unwrap(),
+            kpuzzle
+                .default_pattern()
+                .apply_alg(&scramble.invert())
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn two_phase_respects_timeout() {
+    let kpuzzle = cube3x3x3_kpuzzle();
+    let solver = TwoPhaseSolver::new();
+    // Superflip requires 20 moves, so no 19-move solution exists.
+    let pattern = kpuzzle
+        .default_pattern()
+        .apply_alg(
+            &"U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2"
+                .parse::<Alg>()
+                .unwrap(),
+        )
+        .unwrap();
+    let options = TwoPhaseOptions {
+        max_length: 19,
+        timeout: Some(Duration::from_millis(100)),
+    };
+    let start = Instant::now();
+    assert_eq!(solver.solve(&pattern, &options).unwrap(), None);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn two_phase_tables_file_round_trip() {
+    let path = |name: &str| {
+        std::env::temp_dir().join(format!(
+            "cubing-rs-two-phase-tables-{}-{}.bin",
+            name,
+            std::process::id()
+        ))
+    };
+    let solver = TwoPhaseSolver::new();
+    solver.write_tables_file(path("generated")).unwrap();
+    let loaded_solver = TwoPhaseSolver::try_from_tables_file(path("generated")).unwrap();
+    loaded_solver.write_tables_file(path("loaded")).unwrap();
+    assert_eq!(
+        std::fs::read(path("loaded")).unwrap(),
+        std::fs::read(path("generated")).unwrap()
+    );
+    std::fs::remove_file(path("generated")).unwrap();
+    std::fs::remove_file(path("loaded")).unwrap();
+
+    let kpuzzle = cube3x3x3_kpuzzle();
+    let pattern = kpuzzle
+        .default_pattern()
+        .apply_alg(
+            &"F R U' R' U' R U R' F' R U R' U' R' F R F'"
+                .parse::<Alg>()
+                .unwrap(),
+        )
+        .unwrap();
+    let options = TwoPhaseOptions::default();
+    let solution = loaded_solver.solve(&pattern, &options).unwrap().unwrap();
+    assert_eq!(
+        pattern.apply_alg(&solution).unwrap(),
+        kpuzzle.default_pattern()
+    );
+    assert_eq!(Some(solution), solver.solve(&pattern, &options).unwrap());
+
+    assert!(TwoPhaseSolver::try_from_tables_file(std::env::temp_dir().join("missing")).is_err());
+}
+
+#[test]
+fn cube2x2x2_distance_histogram() {
+    let solver = Cube2x2x2Solver::new();
+    assert_eq!(
+        solver.distance_histogram(),
+        vec![1, 9, 54, 321, 1847, 9992, 50136, 227536, 870072, 1887748, 623800, 2644]
+    );
+    assert_eq!(solver.distance_histogram().iter().sum::<u64>(), 3674160);
+}